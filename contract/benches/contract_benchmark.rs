@@ -2,9 +2,9 @@ use std::mem::take;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use meta_names_contract::{
-    contract::{initialize, on_mint_callback, transfer_from},
-    msg::{InitMsg, MintMsg},
-    state::{ContractConfig, ContractState, Fees, PaymentInfo},
+    contract::{initialize, on_mint_callback, transfer_from, transfer_from_batch},
+    msg::{InitMsg, MintMsg, TransferFromMsg},
+    state::{ContractConfig, ContractState, Fees, PaymentInfo, RecordCapacityFees},
 };
 use utils::tests::{
     get_address_for_user, mock_address, mock_contract_context, mock_successful_callback_context,
@@ -23,6 +23,10 @@ fn setup_contract() -> ContractState {
                 default_fee: 1,
                 decimals: 0,
             },
+            record_capacity_fees: RecordCapacityFees {
+                custom_record_slot: 1,
+                data_length_byte: 1,
+            },
         }],
         ..ContractConfig::default()
     };
@@ -97,6 +101,85 @@ fn benchmark_domain_transfer(domains_count: u64) {
     domain_transfer_from(&mut state, user, 0, to);
 }
 
+fn setup_contract_with_domains(domains_count: u64) -> ContractState {
+    let mut state = setup_contract();
+    let user = "alice".to_string();
+
+    for i in 0..domains_count {
+        let new_domain = format!("test{}", i);
+        state = mint_domain(&mut state, user.clone(), new_domain, 0);
+    }
+
+    state
+}
+
+// Only the transfers are measured, the registry is minted once beforehand
+fn benchmark_transfer_only(c: &mut Criterion, name: &str, domains_count: u64) {
+    let mut state = setup_contract_with_domains(domains_count + 1);
+    let token_id = domains_count as u128;
+    let alice = "alice".to_string();
+    let bob = "bob".to_string();
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            state =
+                domain_transfer_from(&mut state, alice.clone(), black_box(token_id), bob.clone());
+            state =
+                domain_transfer_from(&mut state, bob.clone(), black_box(token_id), alice.clone());
+        })
+    });
+}
+
+fn domain_transfer_from_batch(
+    state: &mut ContractState,
+    user: String,
+    token_ids: &[u128],
+    to: String,
+) -> ContractState {
+    let transfers = token_ids
+        .iter()
+        .map(|token_id| TransferFromMsg {
+            from: mock_address(get_address_for_user(user.clone())),
+            to: mock_address(get_address_for_user(to.clone())),
+            token_id: *token_id,
+        })
+        .collect();
+
+    let state = take(state);
+    let (new_state, _) = transfer_from_batch(
+        mock_contract_context(get_address_for_user(user)),
+        state,
+        transfers,
+    );
+
+    new_state
+}
+
+// Only the batch transfers are measured, the registry is minted once beforehand
+fn benchmark_transfer_batch(c: &mut Criterion, name: &str, batch_size: u64) {
+    let mut state = setup_contract_with_domains(batch_size);
+    let token_ids: Vec<u128> = (0..batch_size as u128).collect();
+    let alice = "alice".to_string();
+    let bob = "bob".to_string();
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            state = domain_transfer_from_batch(
+                &mut state,
+                alice.clone(),
+                black_box(&token_ids),
+                bob.clone(),
+            );
+            state = domain_transfer_from_batch(
+                &mut state,
+                bob.clone(),
+                black_box(&token_ids),
+                alice.clone(),
+            );
+        })
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("domain transfer", |b| {
         b.iter(|| benchmark_domain_transfer(black_box(0)))
@@ -107,6 +190,14 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("domain transfer with 10k", |b| {
         b.iter(|| benchmark_domain_transfer(black_box(10_000)))
     });
+
+    benchmark_transfer_only(c, "domain transfer only", 0);
+    benchmark_transfer_only(c, "domain transfer only with 10k", 10_000);
+    benchmark_transfer_only(c, "domain transfer only with 100k", 100_000);
+
+    benchmark_transfer_batch(c, "domain transfer batch of 50", 50);
+    benchmark_transfer_batch(c, "domain transfer batch of 500", 500);
+    benchmark_transfer_batch(c, "domain transfer batch of 5k", 5_000);
 }
 
 criterion_group!(benches, criterion_benchmark);
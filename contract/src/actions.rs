@@ -1,5 +1,10 @@
+use std::collections::BTreeSet;
+
 use crate::{
-    msg::{MPC20TransferFromMsg, MintMsg, RenewDomainMsg},
+    msg::{
+        BuyDomainMsg, BuyRecordCapacityMsg, ImportDomainMsg, MPC20TransferFromMsg, MintMsg,
+        RenewDomainMsg, SafeTransferMsg, TransferFromMsg,
+    },
     state::ContractState,
     ContractError,
 };
@@ -25,6 +30,36 @@ pub struct PaymentIntent {
     pub total_fees: u128,
 }
 
+/// Transfers the token and applies the record retention to its domain.
+/// The manager and the sale listing of the domain are always cleared
+pub fn action_transfer_with_records(
+    ctx: &ContractContext,
+    mut state: ContractState,
+    from: Address,
+    to: Address,
+    token_id: u128,
+    retention: pns_msg::PnsRecordRetention,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut nft_events = nft_actions::execute_transfer_from(
+        ctx,
+        &mut state.nft,
+        &nft_msg::NFTTransferFromMsg { from, to, token_id },
+    );
+
+    let (name, _) = state.pns.get_domain_by_token_id(token_id).unwrap();
+    let msg = &pns_msg::PnsRecordRetainMsg {
+        domain: name,
+        retention,
+    };
+    let pns_events = pns_actions::execute_record_retain(ctx, &mut state.pns, msg);
+    state.pns._set_manager(&msg.domain, None);
+    state.listings.remove(&token_id);
+
+    nft_events.extend(pns_events);
+
+    (state, nft_events)
+}
+
 /// Action to mint contract
 pub fn action_mint(
     ctx: &ContractContext,
@@ -55,15 +90,29 @@ pub fn action_mint(
 
         pns_actions::validate_domain_with_parent(domain, &parent_id);
 
+        if let Some(limit) = state.config.subdomain_count_limit {
+            assert!(
+                state.pns.get_subdomain_count(&parent_id) < limit,
+                "{}",
+                ContractError::SubdomainCountLimitReached
+            );
+        }
+
         let parent_token_id = parent.token_id;
         assert!(
-            state.nft.is_approved_or_owner(ctx.sender, parent_token_id),
+            state
+                .nft
+                .is_approved_or_owner(ctx.sender, parent_token_id, ctx.block_production_time),
             "{}",
             ContractError::Unauthorized
         );
-    } else if let Some(years_active) = subscription_years {
-        let date = ctx.block_production_time + milliseconds_in_years(*years_active as i64);
-        expires_at = Some(date);
+    } else {
+        pns_actions::validate_root_domain(&state.pns, domain);
+
+        if let Some(years_active) = subscription_years {
+            let date = ctx.block_production_time + milliseconds_in_years(*years_active as i64);
+            expires_at = Some(date);
+        }
     }
 
     let token_id = state.nft.get_next_token_id();
@@ -88,6 +137,11 @@ pub fn action_mint(
         },
     );
 
+    if let Some(parent_id) = parent_id {
+        let parent_token_id = state.pns.get_token_id(parent_id).unwrap();
+        state.nft._set_parent_token(token_id, parent_token_id);
+    }
+
     state.stats.increase_mint_count(ctx.sender);
 
     let events = nft_events.into_iter().chain(pns_events).collect();
@@ -95,6 +149,127 @@ pub fn action_mint(
     (state, events)
 }
 
+/// Action to import a domain with its original token id
+/// The domain goes through the same validations as a mint
+/// An already imported domain is skipped, so that a failed batch can be sent again,
+/// it must have been imported with the same data
+pub fn action_import_domain(
+    ctx: &ContractContext,
+    mut state: ContractState,
+    msg: &ImportDomainMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    let pns_import_msg = pns_msg::PnsImportMsg {
+        domain: msg.domain.clone(),
+        token_id: msg.token_id,
+        parent_id: msg.parent_id.clone(),
+        minted_at: msg.minted_at,
+        expires_at: msg.expires_at,
+        records: msg.records.clone(),
+        custom_records: msg.custom_records.clone(),
+    };
+
+    if state.pns.is_minted(&msg.domain) {
+        assert!(
+            is_domain_imported(&state, msg, &pns_import_msg),
+            "{}",
+            ContractError::ImportMismatch
+        );
+
+        return (state, vec![]);
+    }
+
+    pns_actions::validate_domain(&msg.domain);
+    match &msg.parent_id {
+        Some(parent_id) => {
+            pns_actions::validate_domain_with_parent(&msg.domain, parent_id);
+
+            if let Some(limit) = state.config.subdomain_count_limit {
+                assert!(
+                    state.pns.get_subdomain_count(parent_id) < limit,
+                    "{}",
+                    ContractError::SubdomainCountLimitReached
+                );
+            }
+        }
+        None => pns_actions::validate_root_domain(&state.pns, &msg.domain),
+    }
+
+    let nft_events = nft_actions::execute_mint(
+        ctx,
+        &mut state.nft,
+        &nft_msg::NFTMintMsg {
+            to: msg.owner,
+            token_id: msg.token_id,
+            token_uri: msg.token_uri.clone(),
+        },
+    );
+
+    let pns_events = pns_actions::execute_import(ctx, &mut state.pns, &pns_import_msg);
+
+    if let Some(parent_id) = &msg.parent_id {
+        let parent_token_id = state.pns.get_token_id(parent_id).unwrap();
+        state.nft._set_parent_token(msg.token_id, parent_token_id);
+    }
+
+    let events = nft_events.into_iter().chain(pns_events).collect();
+
+    (state, events)
+}
+
+/// Returns true if the domain has already been imported with the token, owner and data of the msg
+fn is_domain_imported(
+    state: &ContractState,
+    msg: &ImportDomainMsg,
+    pns_import_msg: &pns_msg::PnsImportMsg,
+) -> bool {
+    state.pns.get_token_id(&msg.domain) == Some(msg.token_id)
+        && state.nft.owner_of(msg.token_id) == msg.owner
+        && state.nft.token_uri_details.get(&msg.token_id) == msg.token_uri
+        && pns_actions::is_imported(&state.pns, pns_import_msg)
+}
+
+/// Validates every transfer of a batch against the current state,
+/// so that a batch is rejected before any transfer is applied
+pub fn action_validate_transfer_batch(
+    ctx: &ContractContext,
+    state: &ContractState,
+    transfers: &[TransferFromMsg],
+) {
+    if let Some(limit) = state.config.transfer_batch_size_limit {
+        assert!(
+            transfers.len() <= limit as usize,
+            "{}",
+            ContractError::BatchSizeLimitReached
+        );
+    }
+
+    let mut token_ids = BTreeSet::new();
+    for transfer in transfers {
+        assert!(
+            token_ids.insert(transfer.token_id),
+            "{}",
+            ContractError::BatchDuplicateToken
+        );
+        assert!(
+            state
+                .pns
+                .get_domain_by_token_id(transfer.token_id)
+                .is_some(),
+            "{}",
+            ContractError::DomainNotMinted
+        );
+        assert!(
+            state.nft.is_approved_or_owner(
+                ctx.sender,
+                transfer.token_id,
+                ctx.block_production_time
+            ) && state.nft.owner_of(transfer.token_id) == transfer.from,
+            "{}",
+            ContractError::Unauthorized
+        );
+    }
+}
+
 pub fn action_build_mint_callback(
     payment_intent: &PaymentIntent,
     mint_msg: &MintMsg,
@@ -133,6 +308,85 @@ pub fn action_build_renew_callback(
     vec![payout_transfer_events.build()]
 }
 
+pub fn action_build_record_capacity_callback(
+    payment_intent: &PaymentIntent,
+    buy_msg: &BuyRecordCapacityMsg,
+    callback_byte: u32,
+) -> Vec<EventGroup> {
+    assert!(
+        payment_intent.id == buy_msg.payment_coin_id,
+        "{}",
+        ContractError::PaymentInfoNotValid
+    );
+
+    let mut payout_transfer_events = build_payout_fees_event_group(&buy_msg.payer, payment_intent);
+
+    build_msg_callback(&mut payout_transfer_events, callback_byte, buy_msg);
+
+    vec![payout_transfer_events.build()]
+}
+
+pub fn action_build_safe_transfer_callback(
+    safe_transfer_msg: &SafeTransferMsg,
+    data: Vec<u8>,
+    callback_byte: u32,
+) -> Vec<EventGroup> {
+    let mut received_events = EventGroup::builder();
+
+    nft_msg::MPC721ReceivedMsg {
+        operator: safe_transfer_msg.operator,
+        from: safe_transfer_msg.from,
+        token_id: safe_transfer_msg.token_id,
+        data,
+    }
+    .as_interaction(&mut received_events, &safe_transfer_msg.to);
+
+    build_msg_callback(&mut received_events, callback_byte, safe_transfer_msg);
+
+    vec![received_events.build()]
+}
+
+/// Builds the payments of a domain sale by the buyer, followed by the callback completing the sale
+pub fn action_build_buy_domain_callback(
+    state: &ContractState,
+    payment_token: &Address,
+    buy_msg: &BuyDomainMsg,
+    callback_byte: u32,
+) -> Vec<EventGroup> {
+    let mut payout_transfer_events = EventGroup::builder();
+
+    for payout in sale_payouts(state, buy_msg) {
+        payout.as_interaction(&mut payout_transfer_events, payment_token);
+    }
+
+    build_msg_callback(&mut payout_transfer_events, callback_byte, buy_msg);
+
+    vec![payout_transfer_events.build()]
+}
+
+/// Returns the payments of a domain sale by the buyer,
+/// the royalty of the domain, if any, goes to its receiver and the rest of the price to the seller
+pub fn sale_payouts(state: &ContractState, buy_msg: &BuyDomainMsg) -> Vec<MPC20TransferFromMsg> {
+    let royalty = state
+        .nft
+        .royalty_info(buy_msg.token_id, buy_msg.price)
+        .filter(|(_, amount)| *amount > 0);
+    let royalty_amount = royalty.map_or(0, |(_, amount)| amount);
+
+    let royalty_payout = royalty.map(|(receiver, amount)| MPC20TransferFromMsg {
+        from: buy_msg.buyer,
+        to: receiver,
+        amount,
+    });
+    let seller_payout = (buy_msg.price > royalty_amount).then(|| MPC20TransferFromMsg {
+        from: buy_msg.buyer,
+        to: buy_msg.seller,
+        amount: buy_msg.price - royalty_amount,
+    });
+
+    royalty_payout.into_iter().chain(seller_payout).collect()
+}
+
 pub fn action_renew_subscription(
     ctx: ContractContext,
     mut state: ContractState,
@@ -159,6 +413,24 @@ pub fn action_renew_subscription(
     (state, vec![])
 }
 
+pub fn action_add_record_capacity(
+    ctx: ContractContext,
+    mut state: ContractState,
+    msg: &BuyRecordCapacityMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_add_record_capacity(
+        &ctx,
+        &mut state.pns,
+        &pns_msg::PnsAddRecordCapacityMsg {
+            domain: msg.domain.clone(),
+            extra_custom_record_slots: msg.extra_custom_record_slots,
+            extra_data_length: msg.extra_data_length,
+        },
+    );
+
+    (state, events)
+}
+
 fn build_payout_fees_event_group(
     payer: &Address,
     payment_intent: &PaymentIntent,
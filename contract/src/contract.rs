@@ -1,24 +1,39 @@
 use crate::{
     actions::{
-        action_build_mint_callback, action_build_renew_callback, action_mint,
-        action_renew_subscription, PaymentIntent,
+        action_add_record_capacity, action_build_buy_domain_callback, action_build_mint_callback,
+        action_build_record_capacity_callback, action_build_renew_callback,
+        action_build_safe_transfer_callback, action_import_domain, action_mint,
+        action_renew_subscription, action_transfer_with_records, action_validate_transfer_batch,
+        PaymentIntent,
     },
-    msg::{InitMsg, MintMsg, OwnerInfoEvent, RenewDomainMsg},
-    state::{ContractConfig, ContractState, ContractStats, PaymentInfo, UserRole},
+    migration::{migrate_state, LegacyContractState},
+    msg::{
+        AddressEvent, AddressHistoryEvent, AliasEvent, BuyDomainMsg, BuyRecordCapacityMsg,
+        ContentHashEvent, CustomRecordEvent, CustomRecordHistoryEvent, ImportDomainMsg, InitMsg,
+        MintMsg, OwnerInfoEvent, OwnerTokensEvent, RecordEvent, RecordHistoryEvent, RenewDomainMsg,
+        RoyaltyInfoEvent, SafeTransferMsg, SubdomainsEvent, TokenInfoEvent, TokenUriEvent,
+        TransferBatchEvent, TransferDomainMsg, TransferEvent, TransferFromMsg,
+    },
+    state::{ContractConfig, ContractState, ContractStats, DomainListing, PaymentInfo, UserRole},
 };
 
 use contract_version_base::state::ContractVersionBase;
 use pbc_contract_common::{
-    address::Address,
+    address::{Address, AddressType},
+    avl_tree_map::AvlTreeMap,
     context::{CallbackContext, ContractContext},
     events::EventGroup,
+    upgrade::ContractHashes,
 };
 
-use nft::{actions as nft_actions, msg as nft_msg};
+use nft::{actions as nft_actions, msg as nft_msg, state::RoyaltyInfo};
 
 use access_control::{actions as ac_actions, msg as ac_msg};
 use airdrop::actions::{self as airdrop_actions, execute_airdrop};
-use partisia_name_system::{actions as pns_actions, msg as pns_msg, state::RecordClass};
+use partisia_name_system::{
+    actions as pns_actions, msg as pns_msg,
+    state::{RecordClass, RecordLimits, RecordTtl},
+};
 use utils::events::assert_callback_success;
 
 use crate::ContractError;
@@ -67,6 +82,9 @@ pub fn initialize(ctx: ContractContext, msg: InitMsg) -> (ContractState, Vec<Eve
         access_control,
         airdrop,
         config: msg.config,
+        import_finalized: false,
+        listings: AvlTreeMap::new(),
+        migration_skipped_domains: AvlTreeMap::new(),
         nft,
         pns,
         stats: ContractStats::default(),
@@ -76,29 +94,49 @@ pub fn initialize(ctx: ContractContext, msg: InitMsg) -> (ContractState, Vec<Eve
     (state, vec![])
 }
 
+/// Only admins are allowed to upgrade the contract.
+/// Version 2.5.0 has no such hook and cannot be upgraded in place,
+/// its domains are moved to this version with `import_domains` instead
+#[upgrade_is_allowed]
+pub fn is_upgrade_allowed(
+    ctx: ContractContext,
+    state: ContractState,
+    _old_contract_hashes: ContractHashes,
+    _new_contract_hashes: ContractHashes,
+    _new_contract_rpc: Vec<u8>,
+) -> bool {
+    state
+        .access_control
+        .has_role(UserRole::Admin {} as u8, &ctx.sender)
+}
+
+/// Migrates the state of a previous contract version to the current layout,
+/// see [`MIGRATABLE_VERSIONS`](crate::migration::MIGRATABLE_VERSIONS).
+/// Domains whose names cannot be migrated do not abort the upgrade,
+/// they are left out and listed in `migration_skipped_domains`
+#[upgrade]
+pub fn upgrade(_ctx: ContractContext, state: LegacyContractState) -> ContractState {
+    migrate_state(state, CONTRACT_NAME, CONTRACT_VERSION)
+}
+
 #[action(shortname = 0x03)]
 pub fn transfer_from(
     ctx: ContractContext,
-    mut state: ContractState,
+    state: ContractState,
     from: Address,
     to: Address,
     token_id: u128,
 ) -> (ContractState, Vec<EventGroup>) {
     assert_contract_enabled(&state);
 
-    let mut nft_events = nft_actions::execute_transfer_from(
+    action_transfer_with_records(
         &ctx,
-        &mut state.nft,
-        &nft_msg::NFTTransferFromMsg { from, to, token_id },
-    );
-
-    let (name, _) = state.pns.get_domain_by_token_id(token_id).unwrap();
-    let msg = &pns_msg::PnsRecordDeleteAllMsg { domain: name };
-    let pns_events = pns_actions::execute_record_delete_all(&ctx, &mut state.pns, msg);
-
-    nft_events.extend(pns_events);
-
-    (state, nft_events)
+        state,
+        from,
+        to,
+        token_id,
+        pns_msg::PnsRecordRetention::WipeAll {},
+    )
 }
 
 #[action(shortname = 0x04)]
@@ -117,6 +155,30 @@ pub fn transfer_domain(
     transfer_from(ctx, state, from, to, token_id.unwrap())
 }
 
+/// Transfers the domain keeping all, none or some of its records.
+/// Only the owner can keep records, approved addresses and operators always wipe them
+#[action(shortname = 0x4b)]
+pub fn transfer_domain_with_records(
+    ctx: ContractContext,
+    state: ContractState,
+    from: Address,
+    to: Address,
+    domain: String,
+    retention: pns_msg::PnsRecordRetention,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let token_id = state.pns.get_token_id(&domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+    assert!(
+        retention == pns_msg::PnsRecordRetention::WipeAll {} || ctx.sender == from,
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    action_transfer_with_records(&ctx, state, from, to, token_id.unwrap(), retention)
+}
+
 #[action(shortname = 0x05)]
 pub fn approve(
     ctx: ContractContext,
@@ -258,6 +320,68 @@ pub fn is_domain_owner(
     (state, vec![])
 }
 
+/// Returns the token owned by the address at the given index as data in the event
+/// the event data is of type TokenInfoEvent
+#[action(shortname = 0x13)]
+pub fn token_of_owner_by_index(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+    index: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let token_id = state.nft.token_of_owner_by_index(owner, index);
+    event_builder.return_data(build_token_info_event(&state, token_id));
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the token at the given index as data in the event
+/// the event data is of type TokenInfoEvent
+#[action(shortname = 0x14)]
+pub fn token_by_index(
+    ctx: ContractContext,
+    state: ContractState,
+    index: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let token_id = state.nft.token_by_index(index);
+    event_builder.return_data(build_token_info_event(&state, token_id));
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns a page of the tokens owned by the address as data in the event
+/// the event data is of type OwnerTokensEvent
+#[action(shortname = 0x15)]
+pub fn tokens_of_owner(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+    offset: u128,
+    limit: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let tokens = state
+        .nft
+        .tokens_of_owner(owner, offset, limit)
+        .into_iter()
+        .map(|token_id| build_token_info_event(&state, token_id))
+        .collect();
+    let owner_tokens_event = OwnerTokensEvent {
+        owner,
+        tokens,
+        domain_count: state.nft.balance_of(owner),
+    };
+
+    event_builder.return_data(owner_tokens_event);
+
+    (state, vec![event_builder.build()])
+}
+
 #[action(shortname = 0x16)]
 pub fn mint_custom_record_batch(
     ctx: ContractContext,
@@ -268,7 +392,8 @@ pub fn mint_custom_record_batch(
 
     let mut events = vec![];
     for msg in mint_msgs {
-        let mint_events = pns_actions::execute_custom_record_mint(&ctx, &mut state.pns, &msg);
+        let mint_events =
+            pns_actions::execute_custom_record_mint(&ctx, &mut state.pns, &state.nft, &msg);
         events.extend(mint_events);
     }
 
@@ -288,6 +413,7 @@ pub fn mint_custom_record(
     let events = pns_actions::execute_custom_record_mint(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsCustomRecordMintMsg { domain, key, data },
     );
 
@@ -307,6 +433,7 @@ pub fn update_custom_record(
     let events = pns_actions::execute_custom_record_update(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsCustomRecordUpdateMsg { domain, key, data },
     );
 
@@ -325,6 +452,7 @@ pub fn delete_custom_record(
     let events = pns_actions::execute_custom_record_delete(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsCustomRecordDeleteMsg { domain, key },
     );
 
@@ -341,7 +469,7 @@ pub fn mint_record_batch(
 
     let mut events = vec![];
     for msg in mint_msgs {
-        let mint_events = pns_actions::execute_record_mint(&ctx, &mut state.pns, &msg);
+        let mint_events = pns_actions::execute_record_mint(&ctx, &mut state.pns, &state.nft, &msg);
         events.extend(mint_events);
     }
 
@@ -361,6 +489,7 @@ pub fn mint_record(
     let events = pns_actions::execute_record_mint(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsRecordMintMsg {
             domain,
             class,
@@ -384,6 +513,7 @@ pub fn update_record(
     let events = pns_actions::execute_record_update(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsRecordUpdateMsg {
             domain,
             class,
@@ -406,6 +536,7 @@ pub fn delete_record(
     let events = pns_actions::execute_record_delete(
         &ctx,
         &mut state.pns,
+        &state.nft,
         &pns_msg::PnsRecordDeleteMsg { domain, class },
     );
 
@@ -524,153 +655,1296 @@ pub fn add_airdrop(
     (state, vec![])
 }
 
-#[callback(shortname = 0x30)]
-pub fn on_mint_callback(
+/// Imports domains from an existing registry, keeping their original token ids
+/// Only runs while the contract is disabled, so that regular mints
+/// do not take token ids of domains that are still to be imported
+#[action(shortname = 0x28)]
+pub fn import_domains(
     ctx: ContractContext,
-    callback_ctx: CallbackContext,
     state: ContractState,
-    msg: MintMsg,
+    domains: Vec<ImportDomainMsg>,
 ) -> (ContractState, Vec<EventGroup>) {
-    assert_contract_enabled(&state);
-
-    assert_callback_success(&callback_ctx);
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+    assert!(
+        !state.import_finalized,
+        "{}",
+        ContractError::ImportFinalized
+    );
+    assert!(
+        !state.config.contract_enabled,
+        "{}",
+        ContractError::ImportWhileContractEnabled
+    );
 
-    assert_and_get_payment_info(&state.config, msg.payment_coin_id);
+    let mut all_events = vec![];
+    let mut state_holder = state;
+    for msg in domains {
+        let (new_state, import_events) = action_import_domain(&ctx, state_holder, &msg);
+        all_events.extend(import_events);
+        state_holder = new_state;
+    }
 
-    action_mint(
-        &ctx,
-        state,
-        &msg.domain,
-        &msg.to,
-        &msg.token_uri,
-        &msg.parent_id,
-        &msg.subscription_years,
-    )
+    (state_holder, all_events)
 }
 
-#[callback(shortname = 0x31)]
-pub fn on_renew_subscription_callback(
+/// Prevents any further domain import
+#[action(shortname = 0x29)]
+pub fn finalize_import(
     ctx: ContractContext,
-    callback_ctx: CallbackContext,
-    state: ContractState,
-    msg: RenewDomainMsg,
+    mut state: ContractState,
 ) -> (ContractState, Vec<EventGroup>) {
-    assert_contract_enabled(&state);
-
-    assert_callback_success(&callback_ctx);
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+    assert!(
+        !state.import_finalized,
+        "{}",
+        ContractError::ImportFinalized
+    );
 
-    assert_and_get_payment_info(&state.config, msg.payment_coin_id);
+    state.import_finalized = true;
 
-    action_renew_subscription(ctx, state, msg.domain, msg.subscription_years)
+    (state, vec![])
 }
 
-fn mint_domain(
-    ctx: &ContractContext,
+/// Returns a page of the direct subdomains of a domain as data in the event
+/// the event data is of type SubdomainsEvent
+#[action(shortname = 0x32)]
+pub fn subdomains(
+    ctx: ContractContext,
     state: ContractState,
-    mint_msg: &MintMsg,
+    domain: String,
+    offset: u32,
+    limit: u32,
 ) -> (ContractState, Vec<EventGroup>) {
-    let MintMsg {
+    assert!(
+        state.pns.is_minted(&domain),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let subdomains_event = SubdomainsEvent {
+        subdomains: state.pns.get_subdomains(&domain, offset, limit),
+        subdomain_count: state.pns.get_subdomain_count(&domain),
         domain,
-        to,
-        payment_coin_id,
-        token_uri,
-        parent_id,
-        subscription_years,
-    } = mint_msg;
+    };
 
-    assert!(!state.pns.is_minted(domain), "{}", ContractError::Minted);
+    event_builder.return_data(subdomains_event);
 
-    pns_actions::validate_domain(domain);
+    (state, vec![event_builder.build()])
+}
 
-    let mut events = vec![];
-    let mut mut_state = state;
+/// Updates the template which the token uri's fit into
+#[action(shortname = 0x33)]
+pub fn update_uri_template(
+    ctx: ContractContext,
+    mut state: ContractState,
+    uri_template: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
 
-    let is_admin = mut_state
-        .access_control
-        .has_role(UserRole::Admin {} as u8, &ctx.sender);
-    if parent_id.is_some() || is_admin {
-        let (new_state, mint_events) = action_mint(
-            ctx,
-            mut_state,
-            domain,
-            to,
-            token_uri,
-            parent_id,
-            subscription_years,
-        );
+    let events = nft_actions::execute_update_uri_template(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTUpdateUriTemplateMsg { uri_template },
+    );
 
-        mut_state = new_state;
+    (state, events)
+}
 
-        events.extend(mint_events);
-    } else {
-        let config = &mut_state.config;
-        if config.whitelist_enabled {
-            let is_whitelisted = mut_state
-                .access_control
-                .has_role(UserRole::Whitelist {} as u8, &ctx.sender);
-            assert!(is_whitelisted, "{}", ContractError::UserNotWhitelisted);
-        }
+/// Sets or clears the token uri of a domain, optionally freezing it
+/// Only the owner of the token is allowed to
+#[action(shortname = 0x34)]
+pub fn set_token_uri(
+    ctx: ContractContext,
+    mut state: ContractState,
+    token_id: u128,
+    token_uri: Option<String>,
+    freeze: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
 
-        if config.mint_count_limit_enabled && !is_admin {
-            let mint_count = mut_state.stats.mint_count.get(&ctx.sender);
-            assert!(
-                mint_count.is_none() || mint_count <= Some(config.mint_count_limit),
-                "{}",
-                ContractError::MintCountLimitReached
-            );
-        }
+    let TokenInfoEvent { domain, .. } = build_token_info_event(&state, token_id);
+    let events = nft_actions::execute_set_token_uri(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTSetTokenUriMsg {
+            token_id,
+            token_uri,
+            freeze,
+        },
+        &domain,
+    );
 
-        let has_airdrop = mut_state.airdrop.has_airdrop(&ctx.sender);
-        if has_airdrop {
-            let domain_length = domain.chars().count() as u32;
-            assert!(
-                domain_length >= config.airdrop_min_domain_length,
-                "{}",
-                ContractError::AirdropNotValid
-            );
+    (state, events)
+}
 
-            execute_airdrop(&mut mut_state.airdrop, &ctx.sender);
+/// Returns the token uri as data in the event
+/// the event data is of type TokenUriEvent
+#[action(shortname = 0x35)]
+pub fn token_uri(
+    ctx: ContractContext,
+    state: ContractState,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
 
-            let (new_state, mint_events) = action_mint(
-                ctx,
-                mut_state,
-                domain,
-                to,
-                token_uri,
-                parent_id,
-                subscription_years,
-            );
+    let TokenInfoEvent { domain, .. } = build_token_info_event(&state, token_id);
+    let token_uri_event = TokenUriEvent {
+        token_id,
+        token_uri: state.nft.token_uri(token_id, &domain),
+        domain,
+    };
 
-            mut_state = new_state;
+    event_builder.return_data(token_uri_event);
 
-            events.extend(mint_events);
-        } else {
-            let payment_info = assert_and_get_payment_info(config, *payment_coin_id);
-            let subscription_years = subscription_years.unwrap_or(1);
-            let total_fees = payment_info.fees.get(domain) * subscription_years as u128;
-            let payout_transfer_events = action_build_mint_callback(
-                &PaymentIntent {
-                    id: *payment_coin_id,
-                    receiver: payment_info.receiver.unwrap(),
-                    token: payment_info.token.unwrap(),
-                    total_fees,
-                },
-                &MintMsg {
-                    domain: domain.to_string(),
-                    to: *to,
-                    payment_coin_id: *payment_coin_id,
-                    token_uri: token_uri.clone(),
-                    parent_id: parent_id.clone(),
-                    subscription_years: Some(subscription_years),
-                },
-                0x30,
-            );
+    (state, vec![event_builder.build()])
+}
 
-            events.extend(payout_transfer_events);
-        }
+/// Transfers the token like `transfer_from` when the receiver is an account.
+/// When the receiver is a contract, its `on_mpc721_received` hook is called first
+/// and the transfer is only applied once the receiver acknowledged it
+#[action(shortname = 0x36)]
+pub fn safe_transfer_from(
+    ctx: ContractContext,
+    state: ContractState,
+    from: Address,
+    to: Address,
+    token_id: u128,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    if to.address_type == AddressType::Account {
+        return transfer_from(ctx, state, from, to, token_id);
     }
 
-    (mut_state, events)
+    assert!(
+        state
+            .nft
+            .is_approved_or_owner(ctx.sender, token_id, ctx.block_production_time),
+        "{}",
+        ContractError::Unauthorized
+    );
+    assert!(
+        state.nft.owner_of(token_id) == from,
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    let safe_transfer_msg = SafeTransferMsg {
+        operator: ctx.sender,
+        from,
+        to,
+        token_id,
+    };
+    let events = action_build_safe_transfer_callback(&safe_transfer_msg, data, 0x32);
+
+    (state, events)
+}
+
+#[action(shortname = 0x37)]
+pub fn safe_transfer_domain(
+    ctx: ContractContext,
+    state: ContractState,
+    from: Address,
+    to: Address,
+    domain: String,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let token_id = state.pns.get_token_id(&domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+    safe_transfer_from(ctx, state, from, to, token_id.unwrap(), data)
+}
+
+/// Sets or clears the royalty of the domains without a royalty of their own
+#[action(shortname = 0x38)]
+pub fn set_default_royalty(
+    ctx: ContractContext,
+    mut state: ContractState,
+    royalty: Option<RoyaltyInfo>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+
+    let events = nft_actions::execute_set_default_royalty(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTSetDefaultRoyaltyMsg { royalty },
+    );
+
+    (state, events)
+}
+
+/// Sets or clears the royalty of a domain, overriding the default royalty
+#[action(shortname = 0x39)]
+pub fn set_token_royalty(
+    ctx: ContractContext,
+    mut state: ContractState,
+    token_id: u128,
+    royalty: Option<RoyaltyInfo>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+
+    let events = nft_actions::execute_set_token_royalty(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTSetTokenRoyaltyMsg { token_id, royalty },
+    );
+
+    (state, events)
+}
+
+/// Returns the royalty to pay for a sale of the token as data in the event
+/// the event data is of type RoyaltyInfoEvent
+#[action(shortname = 0x3a)]
+pub fn royalty_info(
+    ctx: ContractContext,
+    state: ContractState,
+    token_id: u128,
+    sale_price: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let royalty = state.nft.royalty_info(token_id, sale_price);
+    let royalty_info_event = RoyaltyInfoEvent {
+        token_id,
+        receiver: royalty.map(|(receiver, _)| receiver),
+        royalty_amount: royalty.map_or(0, |(_, amount)| amount),
+    };
+
+    event_builder.return_data(royalty_info_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Transfers a batch of tokens, every transfer is validated before any is applied
+/// the event data is of type TransferBatchEvent, with a TransferEvent per transfer
+#[action(shortname = 0x3b)]
+pub fn transfer_from_batch(
+    ctx: ContractContext,
+    state: ContractState,
+    transfers: Vec<TransferFromMsg>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    action_validate_transfer_batch(&ctx, &state, &transfers);
+
+    let mut all_events = vec![];
+    let mut transfer_events = vec![];
+    let mut state_holder = state;
+    for msg in transfers {
+        let (new_state, events) =
+            transfer_from(ctx.clone(), state_holder, msg.from, msg.to, msg.token_id);
+        all_events.extend(events);
+        state_holder = new_state;
+
+        let (domain, _) = state_holder
+            .pns
+            .get_domain_by_token_id(msg.token_id)
+            .unwrap();
+        transfer_events.push(TransferEvent {
+            from: msg.from,
+            to: msg.to,
+            token_id: msg.token_id,
+            domain,
+        });
+    }
+
+    let mut event_builder = EventGroup::builder();
+    event_builder.return_data(TransferBatchEvent {
+        transfers: transfer_events,
+    });
+    all_events.push(event_builder.build());
+
+    (state_holder, all_events)
+}
+
+/// Transfers a batch of domains, every transfer is validated before any is applied
+#[action(shortname = 0x3c)]
+pub fn transfer_domain_batch(
+    ctx: ContractContext,
+    state: ContractState,
+    transfers: Vec<TransferDomainMsg>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let transfers = transfers
+        .into_iter()
+        .map(|msg| {
+            let token_id = state.pns.get_token_id(&msg.domain);
+            assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+            TransferFromMsg {
+                from: msg.from,
+                to: msg.to,
+                token_id: token_id.unwrap(),
+            }
+        })
+        .collect();
+
+    transfer_from_batch(ctx, state, transfers)
+}
+
+/// Approves the address on the token until the given time, none never expires
+#[action(shortname = 0x3d)]
+pub fn approve_with_expiry(
+    ctx: ContractContext,
+    mut state: ContractState,
+    approved: Option<Address>,
+    token_id: u128,
+    expires_at: Option<i64>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = nft_actions::execute_approve_with_expiry(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveWithExpiryMsg {
+            approved,
+            token_id,
+            expires_at,
+        },
+    );
+
+    (state, events)
+}
+
+/// Approves the operator on all the tokens of the sender until the given time,
+/// none never expires
+#[action(shortname = 0x3e)]
+pub fn set_approval_for_all_with_expiry(
+    ctx: ContractContext,
+    mut state: ContractState,
+    operator: Address,
+    approved: bool,
+    expires_at: Option<i64>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = nft_actions::execute_set_approval_for_all_with_expiry(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveForAllWithExpiryMsg {
+            operator,
+            approved,
+            expires_at,
+        },
+    );
+
+    (state, events)
+}
+
+/// Approves the operator on a domain and all of its subdomains until the given time,
+/// none never expires
+#[action(shortname = 0x3f)]
+pub fn set_domain_approval_for_all(
+    ctx: ContractContext,
+    mut state: ContractState,
+    operator: Address,
+    domain: String,
+    approved: bool,
+    expires_at: Option<i64>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let token_id = state.pns.get_token_id(&domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let events = nft_actions::execute_set_scoped_approval_for_all(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTScopedApproveForAllMsg {
+            operator,
+            token_id: token_id.unwrap(),
+            approved,
+            expires_at,
+        },
+    );
+
+    (state, events)
+}
+
+/// Sets or clears the manager of a domain, which is allowed to manage the records
+/// of the domain without being able to transfer it
+#[action(shortname = 0x42)]
+pub fn set_domain_manager(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    manager: Option<Address>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_manager(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsDomainSetManagerMsg { domain, manager },
+    );
+
+    (state, events)
+}
+
+/// Mints the wallet address of the domain for the given SLIP-44 coin type
+#[action(shortname = 0x43)]
+pub fn mint_address(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    coin_type: u32,
+    address: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_address_mint(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsAddressMintMsg {
+            domain,
+            coin_type,
+            address,
+        },
+    );
+
+    (state, events)
+}
+
+/// Updates the wallet address of the domain for the given SLIP-44 coin type
+#[action(shortname = 0x44)]
+pub fn update_address(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    coin_type: u32,
+    address: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_address_update(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsAddressUpdateMsg {
+            domain,
+            coin_type,
+            address,
+        },
+    );
+
+    (state, events)
+}
+
+/// Deletes the wallet address of the domain for the given SLIP-44 coin type
+#[action(shortname = 0x45)]
+pub fn delete_address(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    coin_type: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_address_delete(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsAddressDeleteMsg { domain, coin_type },
+    );
+
+    (state, events)
+}
+
+/// Returns the wallet address of the domain for the given SLIP-44 coin type
+/// the event data is of type AddressEvent
+#[action(shortname = 0x46)]
+pub fn address(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    coin_type: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_resolvable(&domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let address = state
+        .pns
+        .resolve_address(&domain, coin_type, ctx.block_production_time);
+    let ttl = address.as_ref().and_then(|_| {
+        state
+            .pns
+            .get_address_ttl_hint(&domain, coin_type, ctx.block_production_time)
+    });
+
+    let address_event = AddressEvent {
+        address,
+        domain,
+        coin_type,
+        ttl,
+    };
+
+    event_builder.return_data(address_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the content hash record of the domain, in its canonical text form
+/// the event data is of type ContentHashEvent
+#[action(shortname = 0x47)]
+pub fn content_hash(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_resolvable(&domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let content_hash = state
+        .pns
+        .resolve_content_hash(&domain, ctx.block_production_time);
+    let ttl = content_hash.as_ref().and_then(|_| {
+        state.pns.get_record_ttl_hint(
+            &domain,
+            &RecordClass::ContentHash {},
+            ctx.block_production_time,
+        )
+    });
+
+    let content_hash_event = ContentHashEvent {
+        content_hash,
+        domain,
+        ttl,
+    };
+
+    event_builder.return_data(content_hash_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Applies a list of record upserts and deletes to the domain at once,
+/// the event data is of type SetRecordsEvent
+#[action(shortname = 0x4a)]
+pub fn set_records(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    operations: Vec<pns_msg::PnsRecordOperation>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_records(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsSetRecordsMsg { domain, operations },
+    );
+
+    (state, events)
+}
+
+/// Buys extra record capacity for a domain, custom record slots and data length
+/// for every record of the domain, paid by the sender. Admins get it without payment
+#[action(shortname = 0x48)]
+pub fn buy_record_capacity(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    payment_coin_id: u64,
+    extra_custom_record_slots: u32,
+    extra_data_length: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+    assert!(
+        state.pns.is_minted(&domain),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+    assert!(
+        extra_custom_record_slots > 0 || extra_data_length > 0,
+        "{}",
+        ContractError::InvalidRecordCapacity
+    );
+
+    let is_admin = state
+        .access_control
+        .has_role(UserRole::Admin {} as u8, &ctx.sender);
+
+    let buy_msg = BuyRecordCapacityMsg {
+        domain,
+        payer: ctx.sender,
+        payment_coin_id,
+        extra_custom_record_slots,
+        extra_data_length,
+    };
+
+    let events;
+    if is_admin {
+        let (new_state, capacity_events) = action_add_record_capacity(ctx, state, &buy_msg);
+
+        state = new_state;
+        events = capacity_events;
+    } else {
+        let payment_info = assert_and_get_payment_info(&state.config, payment_coin_id);
+        assert!(
+            payment_info
+                .record_capacity_fees
+                .is_configured_for(extra_custom_record_slots, extra_data_length),
+            "{}",
+            ContractError::RecordCapacityFeesNotConfigured
+        );
+
+        let total_fees =
+            payment_info.get_record_capacity_fee(extra_custom_record_slots, extra_data_length);
+        events = action_build_record_capacity_callback(
+            &PaymentIntent {
+                id: payment_coin_id,
+                receiver: payment_info.receiver.unwrap(),
+                token: payment_info.token.unwrap(),
+                total_fees,
+            },
+            &buy_msg,
+            0x33,
+        );
+    };
+
+    (state, events)
+}
+
+/// Updates the record limits of every domain
+/// Every data length limit must be above zero and no limit over its maximum
+#[action(shortname = 0x49)]
+pub fn update_record_limits(
+    ctx: ContractContext,
+    mut state: ContractState,
+    record_limits: RecordLimits,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+    pns_actions::validate_record_limits(&record_limits);
+
+    state.pns.record_limits = record_limits;
+
+    (state, vec![])
+}
+
+/// Sets the number of versions kept in the history of every record and address of the domain
+#[action(shortname = 0x4c)]
+pub fn set_record_history_size(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    size: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_record_history_size(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsSetRecordHistorySizeMsg { domain, size },
+    );
+
+    (state, events)
+}
+
+/// Returns the kept versions of a record, oldest first,
+/// the event data is of type RecordHistoryEvent
+#[action(shortname = 0x4d)]
+pub fn record_history(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    class: RecordClass,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_minted(&domain),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let record_history_event = RecordHistoryEvent {
+        versions: state.pns.get_record_history(&domain, &class),
+        domain,
+        class,
+    };
+
+    event_builder.return_data(record_history_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the kept versions of a custom record, oldest first,
+/// the event data is of type CustomRecordHistoryEvent
+#[action(shortname = 0x4e)]
+pub fn custom_record_history(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    key: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_minted(&domain),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let custom_record_history_event = CustomRecordHistoryEvent {
+        versions: state.pns.get_custom_record_history(&domain, &key),
+        domain,
+        key,
+    };
+
+    event_builder.return_data(custom_record_history_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Sets the caching and expiry hints of a record, custom record or address of the domain
+#[action(shortname = 0x4f)]
+pub fn set_record_ttl(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    target: pns_msg::PnsRecordTarget,
+    ttl: RecordTtl,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_record_ttl(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsSetRecordTtlMsg {
+            domain,
+            target,
+            ttl,
+        },
+    );
+
+    (state, events)
+}
+
+/// Removes the records of the domain past their expiry, callable by anyone
+#[action(shortname = 0x50)]
+pub fn prune_expired_records(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_prune_expired_records(
+        &ctx,
+        &mut state.pns,
+        &pns_msg::PnsPruneExpiredRecordsMsg { domain },
+    );
+
+    (state, events)
+}
+
+/// Returns the record of the domain for the given class, omitted when past its expiry
+/// the event data is of type RecordEvent
+#[action(shortname = 0x51)]
+pub fn record(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    class: RecordClass,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_resolvable(&domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let data = state
+        .pns
+        .resolve_record(&domain, &class, ctx.block_production_time);
+    let ttl = data.as_ref().and_then(|_| {
+        state
+            .pns
+            .get_record_ttl_hint(&domain, &class, ctx.block_production_time)
+    });
+
+    let record_event = RecordEvent {
+        domain,
+        class,
+        data,
+        ttl,
+    };
+
+    event_builder.return_data(record_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the custom record of the domain for the given key, omitted when past its expiry
+/// the event data is of type CustomRecordEvent
+#[action(shortname = 0x52)]
+pub fn custom_record(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    key: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_resolvable(&domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let data = state
+        .pns
+        .resolve_custom_record(&domain, &key, ctx.block_production_time);
+    let ttl = data.as_ref().and_then(|_| {
+        state
+            .pns
+            .get_custom_record_ttl_hint(&domain, &key, ctx.block_production_time)
+    });
+
+    let custom_record_event = CustomRecordEvent {
+        domain,
+        key,
+        data,
+        ttl,
+    };
+
+    event_builder.return_data(custom_record_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Marks the records of the domain as resolved for its unminted subdomains, or unmarks them
+#[action(shortname = 0x53)]
+pub fn set_wildcard(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    enabled: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_wildcard(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsSetWildcardMsg { domain, enabled },
+    );
+
+    (state, events)
+}
+
+/// Points the resolution of the domain at a target domain, or removes the alias when none
+#[action(shortname = 0x54)]
+pub fn set_alias(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    target: Option<String>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let events = pns_actions::execute_set_alias(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsSetAliasMsg { domain, target },
+    );
+
+    (state, events)
+}
+
+/// Returns the domain resolved for the name through its aliases, and the followed chain
+/// the event data is of type AliasEvent
+#[action(shortname = 0x55)]
+pub fn alias(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    let resolution = state.pns.resolve_alias(&domain, ctx.block_production_time);
+    assert!(
+        !resolution.chain.is_empty(),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let alias_event = AliasEvent {
+        domain,
+        target: resolution.target,
+        chain: resolution.chain,
+    };
+
+    event_builder.return_data(alias_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Lists the domain for sale at the given price in the payment token, none removes the listing
+/// Only the owner of the domain is allowed to, a transfer of the domain removes its listing
+#[action(shortname = 0x56)]
+pub fn list_domain(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    payment_coin_id: u64,
+    price: Option<u128>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let token_id = state.pns.get_token_id(&domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let token_id = token_id.unwrap();
+    assert!(
+        state.nft.owner_of(token_id) == ctx.sender,
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    match price {
+        Some(price) => {
+            assert!(price > 0, "{}", ContractError::InvalidSalePrice);
+            assert_and_get_payment_info(&state.config, payment_coin_id);
+
+            state.listings.insert(
+                token_id,
+                DomainListing {
+                    seller: ctx.sender,
+                    payment_coin_id,
+                    price,
+                },
+            );
+        }
+        None => state.listings.remove(&token_id),
+    }
+
+    (state, vec![])
+}
+
+/// Buys a listed domain, `ctx.sender` pays the price of the listing in its payment token
+/// The royalty of the domain, if any, is paid to its receiver out of the price
+/// and the rest to the seller, the domain is transferred once the payments succeeded
+#[action(shortname = 0x57)]
+pub fn buy_domain(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let token_id = state.pns.get_token_id(&domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let token_id = token_id.unwrap();
+    let listing = state.listings.get(&token_id);
+    assert!(listing.is_some(), "{}", ContractError::DomainNotListed);
+
+    let listing = listing.unwrap();
+    let payment_info = assert_and_get_payment_info(&state.config, listing.payment_coin_id);
+    let buy_msg = BuyDomainMsg {
+        token_id,
+        seller: listing.seller,
+        buyer: ctx.sender,
+        payment_coin_id: listing.payment_coin_id,
+        price: listing.price,
+    };
+    let events =
+        action_build_buy_domain_callback(&state, &payment_info.token.unwrap(), &buy_msg, 0x34);
+
+    (state, events)
+}
+
+/// Returns the kept versions of an address for the given SLIP-44 coin type, oldest first,
+/// the event data is of type AddressHistoryEvent
+#[action(shortname = 0x58)]
+pub fn address_history(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    coin_type: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert!(
+        state.pns.is_minted(&domain),
+        "{}",
+        ContractError::DomainNotMinted
+    );
+
+    let mut event_builder = EventGroup::builder();
+
+    let address_history_event = AddressHistoryEvent {
+        versions: state.pns.get_address_history(&domain, coin_type),
+        domain,
+        coin_type,
+    };
+
+    event_builder.return_data(address_history_event);
+
+    (state, vec![event_builder.build()])
+}
+
+#[callback(shortname = 0x30)]
+pub fn on_mint_callback(
+    ctx: ContractContext,
+    callback_ctx: CallbackContext,
+    state: ContractState,
+    msg: MintMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    assert_callback_success(&callback_ctx);
+
+    assert_and_get_payment_info(&state.config, msg.payment_coin_id);
+
+    action_mint(
+        &ctx,
+        state,
+        &msg.domain,
+        &msg.to,
+        &msg.token_uri,
+        &msg.parent_id,
+        &msg.subscription_years,
+    )
+}
+
+#[callback(shortname = 0x31)]
+pub fn on_renew_subscription_callback(
+    ctx: ContractContext,
+    callback_ctx: CallbackContext,
+    state: ContractState,
+    msg: RenewDomainMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    assert_callback_success(&callback_ctx);
+
+    assert_and_get_payment_info(&state.config, msg.payment_coin_id);
+
+    action_renew_subscription(ctx, state, msg.domain, msg.subscription_years)
+}
+
+#[callback(shortname = 0x33)]
+pub fn on_buy_record_capacity_callback(
+    ctx: ContractContext,
+    callback_ctx: CallbackContext,
+    state: ContractState,
+    msg: BuyRecordCapacityMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    assert_callback_success(&callback_ctx);
+
+    assert_and_get_payment_info(&state.config, msg.payment_coin_id);
+
+    action_add_record_capacity(ctx, state, &msg)
+}
+
+/// Applies the safe transfer once the receiver acknowledged it,
+/// nothing was transferred yet so a rejected transfer leaves the state untouched
+#[callback(shortname = 0x32)]
+pub fn on_safe_transfer_callback(
+    ctx: ContractContext,
+    callback_ctx: CallbackContext,
+    state: ContractState,
+    msg: SafeTransferMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    let acknowledged = callback_ctx.success && callback_ctx.results.iter().all(|res| res.succeeded);
+    if !acknowledged {
+        return (state, vec![]);
+    }
+
+    // The transfer is authorized for the operator which started it
+    let operator_ctx = ContractContext {
+        sender: msg.operator,
+        ..ctx
+    };
+
+    transfer_from(operator_ctx, state, msg.from, msg.to, msg.token_id)
+}
+
+/// Transfers the bought domain once the buyer paid,
+/// the listing must not have changed since the domain was bought
+#[callback(shortname = 0x34)]
+pub fn on_buy_domain_callback(
+    ctx: ContractContext,
+    callback_ctx: CallbackContext,
+    state: ContractState,
+    msg: BuyDomainMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_contract_enabled(&state);
+
+    assert_callback_success(&callback_ctx);
+
+    let listing = DomainListing {
+        seller: msg.seller,
+        payment_coin_id: msg.payment_coin_id,
+        price: msg.price,
+    };
+    assert!(
+        state.listings.get(&msg.token_id) == Some(listing),
+        "{}",
+        ContractError::DomainNotListed
+    );
+
+    // The seller authorized the transfer by listing the domain
+    let seller_ctx = ContractContext {
+        sender: msg.seller,
+        ..ctx
+    };
+
+    action_transfer_with_records(
+        &seller_ctx,
+        state,
+        msg.seller,
+        msg.buyer,
+        msg.token_id,
+        pns_msg::PnsRecordRetention::WipeAll {},
+    )
+}
+
+fn mint_domain(
+    ctx: &ContractContext,
+    state: ContractState,
+    mint_msg: &MintMsg,
+) -> (ContractState, Vec<EventGroup>) {
+    let MintMsg {
+        domain,
+        to,
+        payment_coin_id,
+        token_uri,
+        parent_id,
+        subscription_years,
+    } = mint_msg;
+
+    assert!(!state.pns.is_minted(domain), "{}", ContractError::Minted);
+
+    pns_actions::validate_domain(domain);
+    if parent_id.is_none() {
+        pns_actions::validate_root_domain(&state.pns, domain);
+    }
+
+    let mut events = vec![];
+    let mut mut_state = state;
+
+    let is_admin = mut_state
+        .access_control
+        .has_role(UserRole::Admin {} as u8, &ctx.sender);
+    if parent_id.is_some() || is_admin {
+        let (new_state, mint_events) = action_mint(
+            ctx,
+            mut_state,
+            domain,
+            to,
+            token_uri,
+            parent_id,
+            subscription_years,
+        );
+
+        mut_state = new_state;
+
+        events.extend(mint_events);
+    } else {
+        let config = &mut_state.config;
+        if config.whitelist_enabled {
+            let is_whitelisted = mut_state
+                .access_control
+                .has_role(UserRole::Whitelist {} as u8, &ctx.sender);
+            assert!(is_whitelisted, "{}", ContractError::UserNotWhitelisted);
+        }
+
+        if config.mint_count_limit_enabled && !is_admin {
+            let mint_count = mut_state.stats.mint_count.get(&ctx.sender);
+            assert!(
+                mint_count.is_none() || mint_count <= Some(config.mint_count_limit),
+                "{}",
+                ContractError::MintCountLimitReached
+            );
+        }
+
+        let has_airdrop = mut_state.airdrop.has_airdrop(&ctx.sender);
+        if has_airdrop {
+            let domain_length = domain.chars().count() as u32;
+            assert!(
+                domain_length >= config.airdrop_min_domain_length,
+                "{}",
+                ContractError::AirdropNotValid
+            );
+
+            execute_airdrop(&mut mut_state.airdrop, &ctx.sender);
+
+            let (new_state, mint_events) = action_mint(
+                ctx,
+                mut_state,
+                domain,
+                to,
+                token_uri,
+                parent_id,
+                subscription_years,
+            );
+
+            mut_state = new_state;
+
+            events.extend(mint_events);
+        } else {
+            let payment_info = assert_and_get_payment_info(config, *payment_coin_id);
+            let subscription_years = subscription_years.unwrap_or(1);
+            let total_fees = payment_info.fees.get(domain) * subscription_years as u128;
+            let payout_transfer_events = action_build_mint_callback(
+                &PaymentIntent {
+                    id: *payment_coin_id,
+                    receiver: payment_info.receiver.unwrap(),
+                    token: payment_info.token.unwrap(),
+                    total_fees,
+                },
+                &MintMsg {
+                    domain: domain.to_string(),
+                    to: *to,
+                    payment_coin_id: *payment_coin_id,
+                    token_uri: token_uri.clone(),
+                    parent_id: parent_id.clone(),
+                    subscription_years: Some(subscription_years),
+                },
+                0x30,
+            );
+
+            events.extend(payout_transfer_events);
+        }
+    }
+
+    (mut_state, events)
+}
+
+fn build_token_info_event(state: &ContractState, token_id: u128) -> TokenInfoEvent {
+    let domain = state.pns.get_domain_by_token_id(token_id);
+    assert!(domain.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let (domain, _) = domain.unwrap();
+
+    TokenInfoEvent { token_id, domain }
 }
 
 fn assert_has_role(state: &ContractState, role: UserRole, account: &Address) {
@@ -38,4 +38,43 @@ pub enum ContractError {
 
     #[error("Domain not valid for airdrop")]
     AirdropNotValid,
+
+    #[error("Subdomain count limit reached")]
+    SubdomainCountLimitReached,
+
+    #[error("Batch size limit reached")]
+    BatchSizeLimitReached,
+
+    #[error("The batch contains the same token more than once")]
+    BatchDuplicateToken,
+
+    #[error("The domain import has been finalized")]
+    ImportFinalized,
+
+    #[error("The contract must be disabled during the domain import")]
+    ImportWhileContractEnabled,
+
+    #[error("The domain has already been imported with other data")]
+    ImportMismatch,
+
+    #[error("The contract version is not supported for migration")]
+    UnsupportedMigrationVersion,
+
+    #[error("The contract cannot be downgraded")]
+    MigrationDowngrade,
+
+    #[error("The given record capacity is not valid")]
+    InvalidRecordCapacity,
+
+    #[error("The record capacity fees are not configured")]
+    RecordCapacityFeesNotConfigured,
+
+    #[error("The record capacity fee overflows")]
+    RecordCapacityFeeOverflow,
+
+    #[error("The specified domain is not listed for sale")]
+    DomainNotListed,
+
+    #[error("The given sale price is not valid")]
+    InvalidSalePrice,
 }
@@ -6,6 +6,7 @@ extern crate pbc_contract_codegen;
 mod actions;
 pub mod contract;
 mod error;
+pub mod migration;
 pub mod msg;
 pub mod state;
 
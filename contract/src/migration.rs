@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use access_control::state::AccessControlState;
+use airdrop::state::AirdropState;
+use contract_version_base::state::ContractVersionBase;
+use create_type_spec_derive::CreateTypeSpec;
+use nft::state::{NFTContractState, OperatorApproval, Unit};
+use partisia_name_system::{
+    actions as pns_actions, name,
+    state::{Domain, PartisiaNameSystemState, RecordClass, RecordLimits},
+};
+use pbc_contract_common::{
+    address::Address, avl_tree_map::AvlTreeMap, sorted_vec_map::SortedVecMap,
+};
+use read_write_state_derive::ReadWriteState;
+
+use crate::{
+    state::{ContractConfig, ContractState, ContractStats, Fees, PaymentInfo, RecordCapacityFees},
+    ContractError,
+};
+
+/// Contract versions whose state layout can be migrated to the current one
+pub const MIGRATABLE_VERSIONS: [&str; 1] = ["2.5.0"];
+
+/// State layout of the contract before the migration
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, Default, Debug)]
+pub struct LegacyContractState {
+    pub access_control: AccessControlState,
+    pub airdrop: AirdropState,
+    pub config: LegacyContractConfig,
+    pub nft: LegacyNFTContractState,
+    pub pns: LegacyPartisiaNameSystemState,
+    pub stats: ContractStats,
+    pub version: ContractVersionBase,
+}
+
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, PartialEq, Eq, Default, Clone, Debug)]
+pub struct LegacyContractConfig {
+    pub airdrop_min_domain_length: u32,
+    pub contract_enabled: bool,
+    pub mint_count_limit_enabled: bool,
+    pub mint_count_limit: u32,
+    pub payment_info: Vec<LegacyPaymentInfo>,
+    pub whitelist_enabled: bool,
+}
+
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, PartialEq, Eq, Default, Clone, Debug)]
+pub struct LegacyPaymentInfo {
+    pub id: u64,
+    pub token: Option<Address>,
+    pub receiver: Option<Address>,
+    pub fees: Fees,
+}
+
+#[derive(ReadWriteState, CreateTypeSpec, Default, Debug)]
+pub struct LegacyNFTContractState {
+    pub name: String,
+    pub symbol: String,
+    pub owners: AvlTreeMap<u128, Address>,
+    pub token_approvals: AvlTreeMap<u128, Address>,
+    pub operator_approvals: AvlTreeMap<OperatorApproval, Unit>,
+    pub owners_balance: AvlTreeMap<Address, u128>,
+    pub uri_template: String,
+    pub token_uri_details: AvlTreeMap<u128, String>,
+    pub contract_owner: Option<Address>,
+    pub supply: u128,
+}
+
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, Default, Debug)]
+pub struct LegacyPartisiaNameSystemState {
+    pub version: ContractVersionBase,
+    pub domains: AvlTreeMap<String, LegacyDomain>,
+}
+
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct LegacyDomain {
+    pub token_id: u128,
+    pub parent_id: Option<String>,
+    pub minted_at: i64,
+    pub expires_at: Option<i64>,
+    pub records: SortedVecMap<RecordClass, Vec<u8>>,
+    pub custom_records: SortedVecMap<String, Vec<u8>>,
+}
+
+impl From<LegacyPaymentInfo> for PaymentInfo {
+    fn from(legacy: LegacyPaymentInfo) -> Self {
+        PaymentInfo {
+            id: legacy.id,
+            token: legacy.token,
+            receiver: legacy.receiver,
+            fees: legacy.fees,
+            record_capacity_fees: RecordCapacityFees::default(),
+        }
+    }
+}
+
+impl From<LegacyContractConfig> for ContractConfig {
+    fn from(legacy: LegacyContractConfig) -> Self {
+        ContractConfig {
+            airdrop_min_domain_length: legacy.airdrop_min_domain_length,
+            contract_enabled: legacy.contract_enabled,
+            mint_count_limit_enabled: legacy.mint_count_limit_enabled,
+            mint_count_limit: legacy.mint_count_limit,
+            payment_info: legacy.payment_info.into_iter().map(Into::into).collect(),
+            subdomain_count_limit: None,
+            transfer_batch_size_limit: None,
+            whitelist_enabled: legacy.whitelist_enabled,
+        }
+    }
+}
+
+impl From<LegacyNFTContractState> for NFTContractState {
+    fn from(legacy: LegacyNFTContractState) -> Self {
+        let next_token_id = legacy
+            .owners
+            .iter()
+            .map(|(token_id, _)| token_id + 1)
+            .max()
+            .unwrap_or(0)
+            .max(legacy.supply);
+
+        let mut nft = NFTContractState {
+            name: legacy.name,
+            symbol: legacy.symbol,
+            owners: legacy.owners,
+            token_approvals: legacy.token_approvals,
+            operator_approvals: legacy.operator_approvals,
+            owners_balance: legacy.owners_balance,
+            uri_template: legacy.uri_template,
+            token_uri_details: legacy.token_uri_details,
+            contract_owner: legacy.contract_owner,
+            supply: legacy.supply,
+            next_token_id,
+            owned_tokens: AvlTreeMap::new(),
+            owned_tokens_index: AvlTreeMap::new(),
+            all_tokens: AvlTreeMap::new(),
+            all_tokens_index: AvlTreeMap::new(),
+            frozen_token_uris: AvlTreeMap::new(),
+            token_approval_expirations: AvlTreeMap::new(),
+            operator_approval_expirations: AvlTreeMap::new(),
+            scoped_operator_approvals: AvlTreeMap::new(),
+            parent_tokens: AvlTreeMap::new(),
+            default_royalty: None,
+            token_royalties: AvlTreeMap::new(),
+        };
+        nft._rebuild_enumerations();
+
+        nft
+    }
+}
+
+impl From<LegacyDomain> for Domain {
+    fn from(legacy: LegacyDomain) -> Self {
+        Domain {
+            token_id: legacy.token_id,
+            parent_id: legacy.parent_id,
+            minted_at: legacy.minted_at,
+            expires_at: legacy.expires_at,
+            records: legacy.records,
+            custom_records: legacy.custom_records,
+            manager: None,
+            addresses: SortedVecMap::new(),
+            extra_custom_record_slots: 0,
+            extra_data_length: 0,
+            record_history_size: 0,
+            wildcard: false,
+            alias: None,
+            record_ttls: SortedVecMap::new(),
+            custom_record_ttls: SortedVecMap::new(),
+            address_ttls: SortedVecMap::new(),
+        }
+    }
+}
+
+/// Converts the legacy state into the current state layout
+/// and bumps every version base to the given name and version.
+/// Subdomains are renamed from their legacy left to right order to DNS order,
+/// the domains that cannot be renamed are left out of the name system,
+/// see [`migrate_domain_names`]. Their tokens stay with their owners and their legacy
+/// names are kept in `migration_skipped_domains` so they can be reviewed.
+/// Panics if the legacy version is unknown or newer than the target one
+pub fn migrate_state(legacy: LegacyContractState, name: &str, version: &str) -> ContractState {
+    assert_migration_allowed(&legacy.version, name, version);
+
+    let mut pns = PartisiaNameSystemState {
+        version: legacy.pns.version,
+        domains: AvlTreeMap::new(),
+        domains_by_token_id: AvlTreeMap::new(),
+        subdomains: AvlTreeMap::new(),
+        record_limits: RecordLimits::default(),
+        record_history: AvlTreeMap::new(),
+    };
+    let mut migration_skipped_domains = AvlTreeMap::new();
+    let domain_names = migrate_domain_names(&legacy.pns.domains);
+    for (legacy_name, domain) in legacy.pns.domains.iter() {
+        let domain_name = match domain_names.get(&legacy_name) {
+            Some(domain_name) => domain_name,
+            None => {
+                migration_skipped_domains.insert(domain.token_id, legacy_name);
+                continue;
+            }
+        };
+
+        let mut domain = Domain::from(domain);
+        domain.parent_id = domain
+            .parent_id
+            .map(|parent_id| domain_names[&parent_id].clone());
+
+        pns._insert_domain(domain_name, domain);
+    }
+    pns_actions::execute_update_version(&mut pns);
+
+    let mut nft: NFTContractState = legacy.nft.into();
+    for (_, domain) in pns.domains.iter() {
+        if let Some(parent_token_id) = domain.parent_id.and_then(|id| pns.get_token_id(&id)) {
+            nft._set_parent_token(domain.token_id, parent_token_id);
+        }
+    }
+
+    let mut contract_version = legacy.version;
+    contract_version.set_contract_version(name, version);
+
+    ContractState {
+        access_control: legacy.access_control,
+        airdrop: legacy.airdrop,
+        config: legacy.config.into(),
+        // A migrated registry is already live, imports would clash with its token ids
+        import_finalized: true,
+        listings: AvlTreeMap::new(),
+        migration_skipped_domains,
+        nft,
+        pns,
+        stats: legacy.stats,
+        version: contract_version,
+    }
+}
+
+/// Returns the DNS order names of the legacy domains, keyed by their legacy name.
+/// A domain is left out when its name cannot be migrated, see [`migrated_domain_name`],
+/// when it is a root domain under another migrated domain, when another domain
+/// ends up with the same name, or when its parent is left out
+pub fn migrate_domain_names(
+    domains: &AvlTreeMap<String, LegacyDomain>,
+) -> BTreeMap<String, String> {
+    let mut domain_names: BTreeMap<String, String> = domains
+        .iter()
+        .filter_map(|(legacy_name, _)| {
+            migrated_domain_name(domains, &legacy_name)
+                .map(|migrated_name| (legacy_name, migrated_name))
+        })
+        .collect();
+
+    let migrated_names: BTreeSet<String> = domain_names.values().cloned().collect();
+    domain_names.retain(|legacy_name, migrated_name| {
+        let is_root = domains
+            .get(legacy_name)
+            .map_or(false, |domain| domain.parent_id.is_none());
+
+        !is_root
+            || !name::get_ancestor_names(migrated_name)
+                .into_iter()
+                .any(|ancestor| migrated_names.contains(ancestor))
+    });
+
+    let mut name_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for migrated_name in domain_names.values() {
+        *name_counts.entry(migrated_name.clone()).or_default() += 1;
+    }
+    domain_names.retain(|_, migrated_name| name_counts[migrated_name] == 1);
+
+    let orphans: BTreeSet<String> = domain_names
+        .keys()
+        .filter(|legacy_name| !has_migrated_parents(domains, &domain_names, legacy_name))
+        .cloned()
+        .collect();
+    domain_names.retain(|legacy_name, _| !orphans.contains(legacy_name));
+
+    domain_names
+}
+
+/// Returns the DNS order name of a legacy domain, renaming its parents first.
+/// Root domains keep their name.
+/// Returns none when the name is not valid, when the parent of a legacy subdomain is not
+/// a legacy domain, or when a legacy subdomain is not its parent followed by exactly one label,
+/// as the levels in between were never minted
+pub fn migrated_domain_name(
+    domains: &AvlTreeMap<String, LegacyDomain>,
+    legacy_name: &str,
+) -> Option<String> {
+    let domain = domains.get(&legacy_name.to_string())?;
+
+    let migrated_name = match domain.parent_id {
+        Some(parent_id) => {
+            // A parent is strictly shorter, so that looping parents end the recursion
+            if !legacy_name.starts_with(&format!("{}.", parent_id)) {
+                return None;
+            }
+
+            let migrated_parent = migrated_domain_name(domains, &parent_id)?;
+            name::migrate_legacy_name(legacy_name, &parent_id, &migrated_parent)
+                .filter(|migrated_name| name::is_child_name(migrated_name, &migrated_parent))?
+        }
+        None => legacy_name.to_string(),
+    };
+
+    name::is_valid_name(&migrated_name).then_some(migrated_name)
+}
+
+fn has_migrated_parents(
+    domains: &AvlTreeMap<String, LegacyDomain>,
+    domain_names: &BTreeMap<String, String>,
+    legacy_name: &str,
+) -> bool {
+    match domains
+        .get(&legacy_name.to_string())
+        .and_then(|domain| domain.parent_id)
+    {
+        Some(parent_id) => {
+            domain_names.contains_key(&parent_id)
+                && has_migrated_parents(domains, domain_names, &parent_id)
+        }
+        None => true,
+    }
+}
+
+/// Validates that the source version can be migrated to the target version
+pub fn assert_migration_allowed(source: &ContractVersionBase, name: &str, version: &str) {
+    assert!(
+        source.contract_name == name && MIGRATABLE_VERSIONS.contains(&source.version.as_str()),
+        "{}",
+        ContractError::UnsupportedMigrationVersion
+    );
+
+    assert!(
+        parse_version(&source.version) < parse_version(version),
+        "{}",
+        ContractError::MigrationDowngrade
+    );
+}
+
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>();
+
+    match parts.as_deref() {
+        Ok([major, minor, patch]) => (*major, *minor, *patch),
+        _ => panic!("{}", ContractError::UnsupportedMigrationVersion),
+    }
+}
@@ -5,6 +5,11 @@ use read_write_rpc_derive::ReadWriteRPC;
 use rpc_msg_derive::IntoShortnameRPCEvent;
 use utils::events::IntoShortnameRPCEvent;
 
+use partisia_name_system::{
+    msg::{PnsCustomRecordEntry, PnsRecordEntry},
+    state::{RecordClass, RecordVersion},
+};
+
 use crate::state::ContractConfig;
 
 /// This structure describes fields for PNS initialize msg
@@ -36,6 +41,26 @@ pub struct MintMsg {
     pub subscription_years: Option<u32>,
 }
 
+/// This structure describes fields for import domain msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct ImportDomainMsg {
+    pub domain: String,
+    /// owner address
+    pub owner: Address,
+    /// original token id
+    pub token_id: u128,
+    /// optional token_uri
+    pub token_uri: Option<String>,
+    /// optional parent
+    pub parent_id: Option<String>,
+    /// Unix millis timestamp
+    pub minted_at: i64,
+    /// Unix millis timestamp
+    pub expires_at: Option<i64>,
+    pub records: Vec<PnsRecordEntry>,
+    pub custom_records: Vec<PnsCustomRecordEntry>,
+}
+
 #[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
 #[rpc_msg(action = 0x03)]
 pub struct MPC20TransferFromMsg {
@@ -58,6 +83,68 @@ pub struct RenewDomainMsg {
     pub subscription_years: u32,
 }
 
+/// This structure describes fields for buy record capacity msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x48)]
+pub struct BuyRecordCapacityMsg {
+    pub domain: String,
+    pub payer: Address,
+    /// BYOC token id
+    pub payment_coin_id: u64,
+    pub extra_custom_record_slots: u32,
+    pub extra_data_length: u32,
+}
+
+/// This structure describes fields for safe transfer msg,
+/// passed to the callback completing the transfer once the receiver acknowledged it
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct SafeTransferMsg {
+    /// address which started the transfer
+    pub operator: Address,
+    /// owner address
+    pub from: Address,
+    /// receiver address
+    pub to: Address,
+    /// token id
+    pub token_id: u128,
+}
+
+/// This structure describes fields for buy domain msg,
+/// passed to the callback completing the sale once the buyer paid
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct BuyDomainMsg {
+    /// token id
+    pub token_id: u128,
+    /// owner address which listed the domain
+    pub seller: Address,
+    /// receiver address, paying the price
+    pub buyer: Address,
+    pub payment_coin_id: u64,
+    /// price of the listing, royalty included
+    pub price: u128,
+}
+
+/// This structure describes fields for a transfer of a batch by token id
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TransferFromMsg {
+    /// owner address
+    pub from: Address,
+    /// receiver address
+    pub to: Address,
+    /// token id
+    pub token_id: u128,
+}
+
+/// This structure describes fields for a transfer of a batch by domain
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TransferDomainMsg {
+    /// owner address
+    pub from: Address,
+    /// receiver address
+    pub to: Address,
+    pub domain: String,
+}
+
 // Events structs
 
 /// Struct for owner info event
@@ -69,3 +156,161 @@ pub struct OwnerInfoEvent {
     pub domain_count: u128,
     pub total_supply: u128,
 }
+
+/// Struct for token info event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TokenInfoEvent {
+    pub token_id: u128,
+    pub domain: String,
+}
+
+/// Struct for owner tokens event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct OwnerTokensEvent {
+    pub owner: Address,
+    pub tokens: Vec<TokenInfoEvent>,
+    pub domain_count: u128,
+}
+
+/// Struct for subdomains event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct SubdomainsEvent {
+    pub domain: String,
+    pub subdomains: Vec<String>,
+    pub subdomain_count: u32,
+}
+
+/// Struct for token uri event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TokenUriEvent {
+    pub token_id: u128,
+    pub domain: String,
+    pub token_uri: String,
+}
+
+/// Struct for royalty info event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RoyaltyInfoEvent {
+    pub token_id: u128,
+    pub receiver: Option<Address>,
+    pub royalty_amount: u128,
+}
+
+/// Struct for transfer event, one per transfer of a batch
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TransferEvent {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u128,
+    pub domain: String,
+}
+
+/// Struct for transfer batch event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TransferBatchEvent {
+    pub transfers: Vec<TransferEvent>,
+}
+
+/// Struct for address event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct AddressEvent {
+    pub domain: String,
+    pub coin_type: u32,
+    pub address: Option<String>,
+    /// Seconds the address may be cached
+    pub ttl: Option<u32>,
+}
+
+/// Struct for content hash event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct ContentHashEvent {
+    pub domain: String,
+    pub content_hash: Option<String>,
+    /// Seconds the content hash may be cached
+    pub ttl: Option<u32>,
+}
+
+/// Struct for alias event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct AliasEvent {
+    pub domain: String,
+    /// Domain whose records are resolved, none when the alias chain is broken
+    pub target: Option<String>,
+    /// Followed domains, starting with the domain answering for the name
+    pub chain: Vec<String>,
+}
+
+/// Struct for record event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordEvent {
+    pub domain: String,
+    pub class: RecordClass,
+    pub data: Option<Vec<u8>>,
+    /// Seconds the record may be cached
+    pub ttl: Option<u32>,
+}
+
+/// Struct for custom record event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct CustomRecordEvent {
+    pub domain: String,
+    pub key: String,
+    pub data: Option<Vec<u8>>,
+    /// Seconds the custom record may be cached
+    pub ttl: Option<u32>,
+}
+
+/// Struct for record history event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordHistoryEvent {
+    pub domain: String,
+    pub class: RecordClass,
+    pub versions: Vec<RecordVersion>,
+}
+
+/// Struct for custom record history event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct CustomRecordHistoryEvent {
+    pub domain: String,
+    pub key: String,
+    pub versions: Vec<RecordVersion>,
+}
+
+/// Struct for address history event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct AddressHistoryEvent {
+    pub domain: String,
+    pub coin_type: u32,
+    /// Versions of the binary form of the address
+    pub versions: Vec<RecordVersion>,
+}
@@ -8,6 +8,8 @@ use pbc_contract_common::{address::Address, avl_tree_map::AvlTreeMap};
 use read_write_rpc_derive::ReadWriteRPC;
 use read_write_state_derive::ReadWriteState;
 
+use crate::ContractError;
+
 #[allow(unused_imports)]
 use crate::contract::__PBC_IS_ZK_CONTRACT;
 
@@ -17,6 +19,12 @@ pub struct ContractState {
     pub access_control: AccessControlState,
     pub airdrop: AirdropState,
     pub config: ContractConfig,
+    /// Once set, domains can no longer be imported
+    pub import_finalized: bool,
+    /// Domains listed for sale by their owner, by token id
+    pub listings: AvlTreeMap<u128, DomainListing>,
+    /// Legacy names of the domains the state migration left out, by token id
+    pub migration_skipped_domains: AvlTreeMap<u128, String>,
     pub nft: NFTContractState,
     pub pns: PartisiaNameSystemState,
     pub stats: ContractStats,
@@ -31,6 +39,17 @@ pub struct PaymentInfo {
     pub token: Option<Address>,
     pub receiver: Option<Address>,
     pub fees: Fees,
+    pub record_capacity_fees: RecordCapacityFees,
+}
+
+/// Sale of a domain listed by its owner
+#[repr(C)]
+#[derive(ReadWriteRPC, ReadWriteState, CreateTypeSpec, PartialEq, Eq, Clone, Debug)]
+pub struct DomainListing {
+    pub seller: Address,
+    pub payment_coin_id: u64,
+    /// Price paid by the buyer, royalty included
+    pub price: u128,
 }
 
 #[repr(u8)]
@@ -59,6 +78,16 @@ pub struct Fees {
     pub decimals: u32,
 }
 
+/// Fees of the extra record capacity of a domain, using the decimals of the fees
+#[repr(C)]
+#[derive(ReadWriteRPC, ReadWriteState, CreateTypeSpec, PartialEq, Eq, Default, Clone, Debug)]
+pub struct RecordCapacityFees {
+    /// Fee of an extra custom record slot
+    pub custom_record_slot: u128,
+    /// Fee of an extra byte of data for every record
+    pub data_length_byte: u128,
+}
+
 #[repr(C)]
 #[derive(ReadWriteRPC, ReadWriteState, CreateTypeSpec, PartialEq, Eq, Default, Clone, Debug)]
 pub struct ContractConfig {
@@ -67,6 +96,10 @@ pub struct ContractConfig {
     pub mint_count_limit_enabled: bool,
     pub mint_count_limit: u32,
     pub payment_info: Vec<PaymentInfo>,
+    /// Maximum number of direct subdomains per domain, unlimited when none
+    pub subdomain_count_limit: Option<u32>,
+    /// Maximum number of transfers per batch, unlimited when none
+    pub transfer_batch_size_limit: Option<u32>,
     pub whitelist_enabled: bool,
 }
 
@@ -88,6 +121,52 @@ impl ContractConfig {
     }
 }
 
+impl PaymentInfo {
+    /// Returns the fee of the extra record capacity of a domain
+    /// Panics if the fee overflows, rather than charging a wrapped fee
+    pub fn get_record_capacity_fee(
+        &self,
+        extra_custom_record_slots: u32,
+        extra_data_length: u32,
+    ) -> u128 {
+        let fees = &self.record_capacity_fees;
+        let fee = 10_u128
+            .checked_pow(self.fees.decimals)
+            .and_then(|decimals| {
+                let slots_fee = fees
+                    .custom_record_slot
+                    .checked_mul(extra_custom_record_slots as u128)?;
+                let data_length_fee = fees
+                    .data_length_byte
+                    .checked_mul(extra_data_length as u128)?;
+
+                slots_fee
+                    .checked_add(data_length_fee)?
+                    .checked_mul(decimals)
+            });
+
+        assert!(
+            fee.is_some(),
+            "{}",
+            ContractError::RecordCapacityFeeOverflow
+        );
+
+        fee.unwrap()
+    }
+}
+
+impl RecordCapacityFees {
+    /// Every requested kind of record capacity has a fee, an unset fee would make it free
+    pub fn is_configured_for(
+        &self,
+        extra_custom_record_slots: u32,
+        extra_data_length: u32,
+    ) -> bool {
+        (extra_custom_record_slots == 0 || self.custom_record_slot > 0)
+            && (extra_data_length == 0 || self.data_length_byte > 0)
+    }
+}
+
 impl ContractStats {
     pub fn increase_mint_count(&mut self, address: Address) {
         let count = self.mint_count.get(&address).unwrap_or(0);
@@ -1,4 +1,106 @@
-use crate::state::{Fee, Fees};
+use contract_version_base::state::ContractVersionBase;
+use nft::state::RoyaltyInfo;
+use partisia_name_system::state::{Domain, RecordClass};
+use pbc_contract_common::sorted_vec_map::SortedVecMap;
+use pbc_traits::ReadWriteState;
+use utils::tests::{mock_address, mock_contract_context, ALICE_ADDRESS};
+
+use crate::{
+    actions::{action_import_domain, sale_payouts},
+    migration::{
+        assert_migration_allowed, migrate_state, LegacyContractConfig, LegacyContractState,
+        LegacyDomain,
+    },
+    msg::{BuyDomainMsg, ImportDomainMsg, MPC20TransferFromMsg},
+    state::{ContractConfig, ContractState, Fee, Fees, PaymentInfo, RecordCapacityFees},
+};
+
+const CONTRACT_NAME: &str = "meta-names-contract";
+
+#[rustfmt::skip]
+const LEGACY_DOMAIN_FIXTURE: &[u8] = &[
+    // token_id: 1
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // parent_id: Some("mpc")
+    0x01, 0x03, 0x00, 0x00, 0x00, 0x6d, 0x70, 0x63,
+    // minted_at: 1000
+    0xe8, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // expires_at: Some(2000)
+    0x01, 0xd0, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // records: { Wallet: "data" }
+    0x01, 0x00, 0x00, 0x00, 0x04, 0x04, 0x00, 0x00, 0x00, 0x64, 0x61, 0x74, 0x61,
+    // custom_records: { "key": "val" }
+    0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x6b, 0x65, 0x79,
+    0x03, 0x00, 0x00, 0x00, 0x76, 0x61, 0x6c,
+];
+
+#[rustfmt::skip]
+const LEGACY_CONFIG_FIXTURE: &[u8] = &[
+    // airdrop_min_domain_length: 5
+    0x05, 0x00, 0x00, 0x00,
+    // contract_enabled: true, mint_count_limit_enabled: false
+    0x01, 0x00,
+    // mint_count_limit: 10
+    0x0a, 0x00, 0x00, 0x00,
+    // payment_info: 1 entry, id: 0
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // token: Some(mock_address(10))
+    0x01, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // receiver: Some(mock_address(1))
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // fees: { mapping: [], default_fee: 1, decimals: 0 }
+    0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00,
+    // whitelist_enabled: true
+    0x01,
+];
+
+fn legacy_state_with_domains(domains: &[(u128, &str, Option<&str>)]) -> LegacyContractState {
+    let mut legacy = LegacyContractState {
+        version: ContractVersionBase::new(CONTRACT_NAME, "2.5.0"),
+        ..LegacyContractState::default()
+    };
+
+    for (token_id, domain_name, parent_id) in domains {
+        legacy.pns.domains.insert(
+            domain_name.to_string(),
+            LegacyDomain {
+                token_id: *token_id,
+                parent_id: parent_id.map(str::to_string),
+                minted_at: 1000,
+                expires_at: None,
+                records: SortedVecMap::new(),
+                custom_records: SortedVecMap::new(),
+            },
+        );
+    }
+
+    legacy
+}
+
+fn import_domain_msg(domain: &str, token_id: u128, parent_id: Option<&str>) -> ImportDomainMsg {
+    ImportDomainMsg {
+        domain: domain.to_string(),
+        owner: mock_address(10),
+        token_id,
+        token_uri: None,
+        parent_id: parent_id.map(str::to_string),
+        minted_at: 1000,
+        expires_at: None,
+        records: vec![],
+        custom_records: vec![],
+    }
+}
+
+fn import_domains(state: ContractState, msgs: &[ImportDomainMsg]) -> ContractState {
+    let ctx = mock_contract_context(ALICE_ADDRESS);
+
+    msgs.iter()
+        .fold(state, |state, msg| action_import_domain(&ctx, state, msg).0)
+}
 
 #[test]
 fn test_mint_fees() {
@@ -44,3 +146,402 @@ fn test_mint_fees() {
         assert_eq!(fees, fee);
     }
 }
+
+#[test]
+fn record_capacity_fees_are_configured_for_priced_capacity_only() {
+    let fees = RecordCapacityFees {
+        custom_record_slot: 10,
+        data_length_byte: 0,
+    };
+
+    assert!(fees.is_configured_for(2, 0));
+    assert!(!fees.is_configured_for(2, 10));
+    assert!(!fees.is_configured_for(0, 10));
+    assert!(!RecordCapacityFees::default().is_configured_for(1, 0));
+}
+
+#[test]
+fn proper_record_capacity_fee() {
+    let payment_info = PaymentInfo {
+        fees: Fees {
+            decimals: 2,
+            ..Fees::default()
+        },
+        record_capacity_fees: RecordCapacityFees {
+            custom_record_slot: 10,
+            data_length_byte: 1,
+        },
+        ..PaymentInfo::default()
+    };
+
+    assert_eq!(payment_info.get_record_capacity_fee(2, 5), 2500);
+}
+
+#[test]
+#[should_panic(expected = "The record capacity fee overflows")]
+fn record_capacity_fee_fails_on_overflow() {
+    let payment_info = PaymentInfo {
+        fees: Fees {
+            decimals: 18,
+            ..Fees::default()
+        },
+        record_capacity_fees: RecordCapacityFees {
+            custom_record_slot: 1,
+            data_length_byte: u128::MAX / 1_000_000,
+        },
+        ..PaymentInfo::default()
+    };
+
+    payment_info.get_record_capacity_fee(0, u32::MAX);
+}
+
+#[test]
+fn proper_legacy_domain_migration() {
+    let mut fixture = LEGACY_DOMAIN_FIXTURE;
+    let legacy = LegacyDomain::state_read_from(&mut fixture);
+    let domain = Domain::from(legacy);
+
+    assert_eq!(domain.token_id, 1);
+    assert_eq!(domain.parent_id, Some("mpc".to_string()));
+    assert_eq!(domain.minted_at, 1000);
+    assert_eq!(domain.expires_at, Some(2000));
+    assert_eq!(
+        domain.get_record(&RecordClass::Wallet {}),
+        Some(&b"data".to_vec())
+    );
+    assert_eq!(domain.get_custom_record("key"), Some(&b"val".to_vec()));
+}
+
+#[test]
+fn proper_legacy_config_migration() {
+    let mut fixture = LEGACY_CONFIG_FIXTURE;
+    let legacy = LegacyContractConfig::state_read_from(&mut fixture);
+    let config = ContractConfig::from(legacy);
+
+    assert_eq!(config.airdrop_min_domain_length, 5);
+    assert!(config.contract_enabled);
+    assert!(!config.mint_count_limit_enabled);
+    assert_eq!(config.mint_count_limit, 10);
+    assert!(config.whitelist_enabled);
+
+    let payment_info = config.get_payment_info(0).unwrap();
+    assert_eq!(payment_info.token, Some(mock_address(10)));
+    assert_eq!(payment_info.receiver, Some(mock_address(1)));
+    assert_eq!(payment_info.fees.get("name"), 1);
+}
+
+#[test]
+fn proper_legacy_state_migration() {
+    let mut legacy = LegacyContractState {
+        version: ContractVersionBase::new(CONTRACT_NAME, "2.5.0"),
+        ..LegacyContractState::default()
+    };
+    legacy.pns.version = ContractVersionBase::new("partisia-name-system", "2.0.0");
+    legacy.nft.owners.insert(1, mock_address(10));
+    legacy.nft.owners_balance.insert(mock_address(10), 1);
+    legacy.nft.supply = 1;
+
+    let mut fixture = LEGACY_DOMAIN_FIXTURE;
+    legacy.pns.domains.insert(
+        "mpc.name".to_string(),
+        LegacyDomain::state_read_from(&mut fixture),
+    );
+    legacy.pns.domains.insert(
+        "mpc".to_string(),
+        LegacyDomain {
+            token_id: 2,
+            parent_id: None,
+            minted_at: 1000,
+            expires_at: None,
+            records: SortedVecMap::new(),
+            custom_records: SortedVecMap::new(),
+        },
+    );
+
+    let state = migrate_state(legacy, CONTRACT_NAME, "2.6.0");
+
+    assert_eq!(state.version.get_contract_version(), "2.6.0");
+    assert_eq!(
+        state.pns.version.get_contract_name(),
+        "partisia-name-system"
+    );
+    assert_ne!(state.pns.version.get_contract_version(), "2.0.0");
+    assert_eq!(state.pns.get_token_id("name.mpc"), Some(1));
+    assert_eq!(
+        state.pns.get_domain_by_token_id(1).map(|(name, _)| name),
+        Some("name.mpc".to_string())
+    );
+    assert_eq!(
+        state.pns.get_domain("name.mpc").unwrap().parent_id,
+        Some("mpc".to_string())
+    );
+    assert!(state.import_finalized);
+    assert_eq!(state.nft.token_of_owner_by_index(mock_address(10), 0), 1);
+    assert_eq!(state.nft.token_by_index(0), 1);
+    assert_eq!(state.nft.get_next_token_id(), 2);
+}
+
+#[test]
+fn proper_legacy_subdomain_names_migration() {
+    let legacy = legacy_state_with_domains(&[
+        (1, "mpc", None),
+        (2, "mpc.name", Some("mpc")),
+        (3, "mpc.name.sub", Some("mpc.name")),
+        (4, "mpc.name.a.b", Some("mpc.name")),
+        (5, "mpc.other", None),
+        (6, "mpc.name.a.b.c", Some("mpc.name.a.b")),
+    ]);
+
+    let state = migrate_state(legacy, CONTRACT_NAME, "2.6.0");
+
+    for (token_id, domain_name) in [
+        (1, "mpc"),
+        (2, "name.mpc"),
+        (3, "sub.name.mpc"),
+        (5, "mpc.other"),
+    ] {
+        assert_eq!(state.pns.get_token_id(domain_name), Some(token_id));
+    }
+    assert!(!state.pns.is_minted("mpc.name"));
+    assert_eq!(
+        state.pns.get_domain("sub.name.mpc").unwrap().parent_id,
+        Some("name.mpc".to_string())
+    );
+    assert_eq!(
+        state.pns.get_subdomains("name.mpc", 0, 10),
+        vec!["sub.name.mpc".to_string()]
+    );
+
+    // The levels between a parent and a child two labels under it were never minted
+    assert!(!state.pns.is_minted("b.a.name.mpc"));
+    assert!(state.pns.get_domain_by_token_id(4).is_none());
+    assert!(state.pns.get_domain_by_token_id(6).is_none());
+    assert_eq!(
+        state.migration_skipped_domains.get(&4),
+        Some("mpc.name.a.b".to_string())
+    );
+    assert_eq!(
+        state.migration_skipped_domains.get(&6),
+        Some("mpc.name.a.b.c".to_string())
+    );
+    assert_eq!(state.migration_skipped_domains.len(), 2);
+}
+
+#[test]
+fn legacy_state_migration_skips_a_root_domain_under_another_domain() {
+    let legacy = legacy_state_with_domains(&[
+        (1, "mpc", None),
+        (2, "mpc.name", Some("mpc")),
+        (3, "name.mpc", None),
+        (4, "name.mpc.sub", Some("name.mpc")),
+        (5, "other.mpc", None),
+    ]);
+
+    let state = migrate_state(legacy, CONTRACT_NAME, "2.6.0");
+
+    assert_eq!(state.pns.get_token_id("mpc"), Some(1));
+    assert_eq!(state.pns.get_token_id("name.mpc"), Some(2));
+    assert_eq!(
+        state.pns.get_domain("name.mpc").unwrap().parent_id,
+        Some("mpc".to_string())
+    );
+    assert!(!state.pns.is_minted("other.mpc"));
+    assert!(state.pns.get_domain_by_token_id(4).is_none());
+    assert_eq!(state.migration_skipped_domains.len(), 3);
+    assert_eq!(
+        state.migration_skipped_domains.get(&3),
+        Some("name.mpc".to_string())
+    );
+}
+
+#[test]
+fn legacy_state_migration_skips_a_subdomain_outside_its_parent_or_without_it() {
+    let legacy = legacy_state_with_domains(&[
+        (1, "mpc.name", None),
+        (2, "mpc.namexyz", Some("mpc.name")),
+        (3, "gone.sub", Some("gone")),
+    ]);
+
+    let state = migrate_state(legacy, CONTRACT_NAME, "2.6.0");
+
+    assert_eq!(state.pns.get_token_id("mpc.name"), Some(1));
+    assert!(!state.pns.is_minted("mpc.namexyz"));
+    assert_eq!(state.pns.get_subdomain_count("mpc.name"), 0);
+    assert_eq!(
+        state.migration_skipped_domains.get(&2),
+        Some("mpc.namexyz".to_string())
+    );
+    assert_eq!(
+        state.migration_skipped_domains.get(&3),
+        Some("gone.sub".to_string())
+    );
+}
+
+#[test]
+fn legacy_state_migration_skips_an_invalid_name() {
+    let legacy = legacy_state_with_domains(&[
+        (1, "mpc", None),
+        (2, "\u{0430}pple", None),
+        (3, "mpc.na\u{200B}me", Some("mpc")),
+    ]);
+
+    let state = migrate_state(legacy, CONTRACT_NAME, "2.6.0");
+
+    assert_eq!(state.pns.get_token_id("mpc"), Some(1));
+    assert_eq!(state.pns.get_subdomain_count("mpc"), 0);
+    assert_eq!(state.migration_skipped_domains.len(), 2);
+    assert_eq!(
+        state.migration_skipped_domains.get(&3),
+        Some("mpc.na\u{200B}me".to_string())
+    );
+}
+
+#[test]
+#[should_panic(expected = "The contract cannot be downgraded")]
+fn migration_downgrade_fails() {
+    let source = ContractVersionBase::new(CONTRACT_NAME, "2.5.0");
+
+    assert_migration_allowed(&source, CONTRACT_NAME, "2.4.0");
+}
+
+#[test]
+#[should_panic(expected = "The contract version is not supported for migration")]
+fn migration_from_unknown_version_fails() {
+    let source = ContractVersionBase::new(CONTRACT_NAME, "1.0.0");
+
+    assert_migration_allowed(&source, CONTRACT_NAME, "2.6.0");
+}
+
+#[test]
+#[should_panic(expected = "The contract version is not supported for migration")]
+fn migration_from_unknown_contract_fails() {
+    let source = ContractVersionBase::new("another-contract", "2.5.0");
+
+    assert_migration_allowed(&source, CONTRACT_NAME, "2.6.0");
+}
+
+#[test]
+fn import_of_an_already_imported_domain_is_skipped() {
+    let state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+    let msg = import_domain_msg("name.mpc", 5, None);
+
+    let state = import_domains(state, &[msg.clone(), msg]);
+
+    assert_eq!(state.pns.get_token_id("name.mpc"), Some(5));
+    assert_eq!(state.nft.balance_of(mock_address(10)), 1);
+}
+
+#[test]
+#[should_panic(expected = "The domain has already been imported with other data")]
+fn import_of_an_already_imported_domain_with_another_owner_fails() {
+    let state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+    let msg = import_domain_msg("name.mpc", 5, None);
+    let changed_msg = ImportDomainMsg {
+        owner: mock_address(11),
+        ..msg.clone()
+    };
+
+    import_domains(state, &[msg, changed_msg]);
+}
+
+#[test]
+#[should_panic(expected = "The domain has already been imported with other data")]
+fn import_of_an_already_imported_domain_with_another_expiry_fails() {
+    let state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+    let msg = import_domain_msg("name.mpc", 5, None);
+    let changed_msg = ImportDomainMsg {
+        expires_at: Some(2000),
+        ..msg.clone()
+    };
+
+    import_domains(state, &[msg, changed_msg]);
+}
+
+#[test]
+#[should_panic(
+    expected = "The specified domain is under a minted domain and must be minted as its subdomain"
+)]
+fn import_of_a_root_domain_under_a_minted_domain_fails() {
+    let state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+
+    import_domains(
+        state,
+        &[
+            import_domain_msg("name.mpc", 5, None),
+            import_domain_msg("sub.name.mpc", 6, None),
+        ],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Subdomain count limit reached")]
+fn import_of_a_subdomain_past_the_subdomain_count_limit_fails() {
+    let mut state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+    state.config.subdomain_count_limit = Some(1);
+
+    import_domains(
+        state,
+        &[
+            import_domain_msg("name.mpc", 5, None),
+            import_domain_msg("a.name.mpc", 6, Some("name.mpc")),
+            import_domain_msg("b.name.mpc", 7, Some("name.mpc")),
+        ],
+    );
+}
+
+#[test]
+fn proper_sale_payouts() {
+    let state = migrate_state(legacy_state_with_domains(&[]), CONTRACT_NAME, "2.6.0");
+    let mut state = import_domains(state, &[import_domain_msg("name.mpc", 5, None)]);
+    let buy_msg = BuyDomainMsg {
+        token_id: 5,
+        seller: mock_address(10),
+        buyer: mock_address(11),
+        payment_coin_id: 0,
+        price: 1000,
+    };
+
+    assert_eq!(
+        sale_payouts(&state, &buy_msg),
+        vec![MPC20TransferFromMsg {
+            from: mock_address(11),
+            to: mock_address(10),
+            amount: 1000,
+        }]
+    );
+
+    state.nft.default_royalty = Some(RoyaltyInfo {
+        receiver: mock_address(12),
+        basis_points: 250,
+    });
+
+    assert_eq!(
+        sale_payouts(&state, &buy_msg),
+        vec![
+            MPC20TransferFromMsg {
+                from: mock_address(11),
+                to: mock_address(12),
+                amount: 25,
+            },
+            MPC20TransferFromMsg {
+                from: mock_address(11),
+                to: mock_address(10),
+                amount: 975,
+            },
+        ]
+    );
+
+    state.nft.default_royalty = Some(RoyaltyInfo {
+        receiver: mock_address(12),
+        basis_points: 10000,
+    });
+
+    assert_eq!(
+        sale_payouts(&state, &buy_msg),
+        vec![MPC20TransferFromMsg {
+            from: mock_address(11),
+            to: mock_address(12),
+            amount: 1000,
+        }]
+    );
+}
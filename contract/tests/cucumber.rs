@@ -3,18 +3,28 @@ use std::{mem::take, panic::catch_unwind};
 use cucumber::{given, then, when, World};
 use meta_names_contract::{
     contract::{
-        add_airdrop, approve_domain, initialize, mint, mint_batch, on_mint_callback,
-        on_renew_subscription_callback, renew_subscription, transfer_domain, update_config,
-        update_user_role,
+        add_airdrop, approve_domain, buy_record_capacity, finalize_import, import_domains,
+        initialize, list_domain, mint, mint_batch, on_buy_domain_callback,
+        on_buy_record_capacity_callback, on_mint_callback, on_renew_subscription_callback,
+        on_safe_transfer_callback, renew_subscription, safe_transfer_domain,
+        set_domain_approval_for_all, set_domain_manager, set_token_uri, transfer_domain,
+        transfer_domain_batch, update_config, update_uri_template, update_user_role,
     },
-    msg::{InitMsg, MintMsg, RenewDomainMsg},
-    state::{ContractConfig, ContractState, Fees, PaymentInfo, UserRole},
+    msg::{
+        BuyDomainMsg, BuyRecordCapacityMsg, ImportDomainMsg, InitMsg, MintMsg, RenewDomainMsg,
+        SafeTransferMsg, TransferDomainMsg,
+    },
+    state::{ContractConfig, ContractState, Fees, PaymentInfo, RecordCapacityFees, UserRole},
 };
 use partisia_name_system::{
     actions::{execute_record_mint, execute_record_update, execute_update_expiration},
-    msg::{PnsDomainUpdateExpirationMsg, PnsRecordMintMsg, PnsRecordUpdateMsg},
+    msg::{PnsDomainUpdateExpirationMsg, PnsRecordMintMsg, PnsRecordRetention, PnsRecordUpdateMsg},
     state::RecordClass,
 };
+use pbc_contract_common::{
+    address::{Address, AddressType},
+    context::CallbackContext,
+};
 use utils::{
     tests::{
         get_address_for_user, mock_address, mock_contract_context,
@@ -23,6 +33,15 @@ use utils::{
     time::milliseconds_in_years,
 };
 
+const RECEIVER_CONTRACT_ADDRESS: u8 = 20;
+
+fn mock_receiver_contract_address() -> Address {
+    Address {
+        address_type: AddressType::PublicContract,
+        ..mock_address(RECEIVER_CONTRACT_ADDRESS)
+    }
+}
+
 #[derive(Debug, Default, World)]
 pub struct ContractWorld {
     state: ContractState,
@@ -51,6 +70,12 @@ fn get_record_class_given(class: String) -> RecordClass {
         "Custom3" => RecordClass::Custom3 {},
         "Custom4" => RecordClass::Custom4 {},
         "Custom5" => RecordClass::Custom5 {},
+        "ContentHash" => RecordClass::ContentHash {},
+        "DnsA" => RecordClass::DnsA {},
+        "DnsAaaa" => RecordClass::DnsAaaa {},
+        "DnsCname" => RecordClass::DnsCname {},
+        "DnsTxt" => RecordClass::DnsTxt {},
+        "DnsMx" => RecordClass::DnsMx {},
         _ => panic!("Unknown record class"),
     }
 }
@@ -68,6 +93,10 @@ fn meta_names_contract(world: &mut ContractWorld) {
                 default_fee: 1,
                 decimals: 0,
             },
+            record_capacity_fees: RecordCapacityFees {
+                custom_record_slot: 1,
+                data_length_byte: 1,
+            },
         }],
         ..ContractConfig::default()
     };
@@ -113,6 +142,16 @@ fn update_contract_config(world: &mut ContractWorld, user: String, key: String,
                 new_config.mint_count_limit = value.parse::<u32>().unwrap();
                 new_config
             }
+            "subdomain_count_limit" => {
+                let mut new_config = world.state.config.clone();
+                new_config.subdomain_count_limit = Some(value.parse::<u32>().unwrap());
+                new_config
+            }
+            "transfer_batch_size_limit" => {
+                let mut new_config = world.state.config.clone();
+                new_config.transfer_batch_size_limit = Some(value.parse::<u32>().unwrap());
+                new_config
+            }
             _ => panic!("Unknown config key"),
         };
 
@@ -259,6 +298,26 @@ fn user_approve_domain(world: &mut ContractWorld, user: String, approved: String
     world.state = new_state;
 }
 
+#[given(expr = "{word} approved {word} on '{word}' domain and its subdomains")]
+fn user_approve_domain_scope(
+    world: &mut ContractWorld,
+    user: String,
+    operator: String,
+    domain: String,
+) {
+    let state = take(&mut world.state);
+    let (new_state, _) = set_domain_approval_for_all(
+        mock_contract_context(get_address_for_user(user)),
+        state,
+        mock_address(get_address_for_user(operator)),
+        domain,
+        true,
+        None,
+    );
+
+    world.state = new_state;
+}
+
 // Taken from partisia-name-system/tests/cucumber.rs
 #[given(regex = ".+ (minted) the '(.+)' record with '(.+)' data for the '(.+)' domain")]
 #[when(regex = ".+ (mints|updates) the '(.+)' record with '(.+)' data for the '(.+)' domain")]
@@ -279,7 +338,7 @@ fn mint_a_record(
                     class: get_record_class_given(class),
                     data: data.into_bytes(),
                 };
-                execute_record_mint(context, &mut state.pns, &msg);
+                execute_record_mint(context, &mut state.pns, &state.nft, &msg);
             }
 
             "updates" => {
@@ -289,7 +348,7 @@ fn mint_a_record(
                     data: data.into_bytes(),
                 };
 
-                execute_record_update(context, &mut state.pns, &msg);
+                execute_record_update(context, &mut state.pns, &state.nft, &msg);
             }
 
             _ => panic!("Not handled"),
@@ -344,6 +403,65 @@ fn renew_domain_on_callback(
     }
 }
 
+#[when(
+    expr = "{word} buys {int} custom record slots and {int} bytes of record data for '{word}' domain"
+)]
+fn buy_domain_record_capacity(
+    world: &mut ContractWorld,
+    user: String,
+    slots: u32,
+    bytes: u32,
+    domain_name: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        buy_record_capacity(
+            mock_contract_context(get_address_for_user(user)),
+            state,
+            domain_name,
+            0,
+            slots,
+            bytes,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(
+    expr = "{word} buys {int} custom record slots and {int} bytes of record data for '{word}' domain with {int} payment token id"
+)]
+fn buy_domain_record_capacity_on_callback(
+    world: &mut ContractWorld,
+    user: String,
+    slots: u32,
+    bytes: u32,
+    domain_name: String,
+    payment_coin_id: u64,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        on_buy_record_capacity_callback(
+            mock_contract_context(get_address_for_user(user.clone())),
+            mock_successful_callback_context(),
+            state,
+            BuyRecordCapacityMsg {
+                domain: domain_name,
+                payer: mock_address(get_address_for_user(user)),
+                payment_coin_id,
+                extra_custom_record_slots: slots,
+                extra_data_length: bytes,
+            },
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
 #[given(expr = "{word} renewed '{word}' domain for {int} years")]
 #[when(expr = "{word} renews '{word}' domain for {int} years")]
 fn renew_domain(world: &mut ContractWorld, user: String, domain_name: String, years: u32) {
@@ -394,6 +512,7 @@ fn airdrop(world: &mut ContractWorld, user: String, to: String) {
     }
 }
 
+#[given(expr = "{word} minted '{word}' domain with '{word}' domain as the parent")]
 #[when(expr = "{word} mints '{word}' domain with '{word}' domain as the parent")]
 #[when(regex = r"(\w+) mints '(.+)' domain without (a parent)")]
 fn mint_domain_with_parent(
@@ -445,6 +564,334 @@ fn transfer_domain_to(world: &mut ContractWorld, user: String, domain: String, t
     }
 }
 
+#[given(expr = "{word} listed '{word}' domain for {int}")]
+#[when(expr = "{word} lists '{word}' domain for {int}")]
+fn list_domain_for_sale(world: &mut ContractWorld, user: String, domain: String, price: u128) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        list_domain(
+            mock_contract_context(get_address_for_user(user)),
+            state,
+            domain,
+            0,
+            Some(price),
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} buys '{word}' domain")]
+fn buy_listed_domain_on_callback(world: &mut ContractWorld, user: String, domain: String) {
+    let token_id = world.state.pns.get_token_id(&domain).unwrap();
+    let listing = world.state.listings.get(&token_id);
+
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        let listing = listing.unwrap();
+        on_buy_domain_callback(
+            mock_contract_context(get_address_for_user(user.clone())),
+            mock_successful_callback_context(),
+            state,
+            BuyDomainMsg {
+                token_id,
+                seller: listing.seller,
+                buyer: mock_address(get_address_for_user(user)),
+                payment_coin_id: listing.payment_coin_id,
+                price: listing.price,
+            },
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} transfers the '{word}' domain to {word} keeping all records")]
+fn transfer_domain_keeping_all_records(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    to: String,
+) {
+    transfer_domain_with_retention(world, user, domain, to, PnsRecordRetention::KeepAll {});
+}
+
+#[when(expr = "{word} transfers the '{word}' domain to {word} keeping only the '{word}' record")]
+fn transfer_domain_keeping_only_record(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    to: String,
+    class: String,
+) {
+    let retention = PnsRecordRetention::KeepOnly {
+        classes: vec![get_record_class_given(class)],
+        custom_keys: vec![],
+        coin_types: vec![],
+    };
+
+    transfer_domain_with_retention(world, user, domain, to, retention);
+}
+
+fn transfer_domain_with_retention(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    to: String,
+    retention: PnsRecordRetention,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        transfer_domain_with_records(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+            mock_address(get_address_for_user(user.clone())),
+            mock_address(get_address_for_user(to)),
+            domain,
+            retention,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[given(expr = "{word} set {word} as the manager of '{word}' domain")]
+fn set_manager_of_domain(world: &mut ContractWorld, user: String, manager: String, domain: String) {
+    let (new_state, _) = set_domain_manager(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        domain,
+        Some(mock_address(get_address_for_user(manager))),
+    );
+
+    world.state = new_state;
+}
+
+#[when(expr = "{word} batch transfers the '{word}' and '{word}' domains to {word}")]
+fn transfer_domain_batch_to(
+    world: &mut ContractWorld,
+    user: String,
+    first_domain: String,
+    second_domain: String,
+    to: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let transfers = [first_domain, second_domain]
+            .into_iter()
+            .map(|domain| TransferDomainMsg {
+                from: mock_address(get_address_for_user(user.clone())),
+                to: mock_address(get_address_for_user(to.clone())),
+                domain,
+            })
+            .collect();
+
+        let state = take(&mut world.state);
+        transfer_domain_batch(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+            transfers,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} safe transfers the '{word}' domain to {word}")]
+fn safe_transfer_domain_to(world: &mut ContractWorld, user: String, domain: String, to: String) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        safe_transfer_domain(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+            mock_address(get_address_for_user(user.clone())),
+            mock_address(get_address_for_user(to)),
+            domain,
+            vec![],
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(
+    regex = r"(\w+) safe transfers the '(.+)' domain to the (accepting|rejecting) receiver contract"
+)]
+fn safe_transfer_domain_to_contract(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    acknowledgement: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let token_id = world.state.pns.get_token_id(&domain).unwrap();
+        let state = take(&mut world.state);
+
+        let (state, _) = safe_transfer_domain(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+            mock_address(get_address_for_user(user.clone())),
+            mock_receiver_contract_address(),
+            domain,
+            vec![],
+        );
+
+        // The callback runs once the receiver answered the hook
+        on_safe_transfer_callback(
+            mock_contract_context(SYSTEM_ADDRESS),
+            CallbackContext {
+                success: acknowledgement == "accepting",
+                results: vec![],
+            },
+            state,
+            SafeTransferMsg {
+                operator: mock_address(get_address_for_user(user.clone())),
+                from: mock_address(get_address_for_user(user)),
+                to: mock_receiver_contract_address(),
+                token_id,
+            },
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[given(expr = "{word} imported '{word}' domain with token id {int} for {word}")]
+#[when(expr = "{word} imports '{word}' domain with token id {int} for {word}")]
+fn import_domain(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    token_id: u128,
+    to: String,
+) {
+    import_domain_with_parent(world, user, domain, token_id, None, to);
+}
+
+#[when(
+    expr = "{word} imports '{word}' domain with token id {int} and '{word}' as parent for {word}"
+)]
+fn import_subdomain(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    token_id: u128,
+    parent: String,
+    to: String,
+) {
+    import_domain_with_parent(world, user, domain, token_id, Some(parent), to);
+}
+
+fn import_domain_with_parent(
+    world: &mut ContractWorld,
+    user: String,
+    domain: String,
+    token_id: u128,
+    parent_id: Option<String>,
+    to: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        import_domains(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+            vec![ImportDomainMsg {
+                domain,
+                owner: mock_address(get_address_for_user(to)),
+                token_id,
+                token_uri: None,
+                parent_id,
+                minted_at: world.point_in_time,
+                expires_at: None,
+                records: vec![],
+                custom_records: vec![],
+            }],
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[given(expr = "{word} finalized the import")]
+fn user_finalizes_import(world: &mut ContractWorld, user: String) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        finalize_import(
+            mock_contract_context(get_address_for_user(user.clone())),
+            state,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} updates the uri template to '{word}'")]
+fn update_uri_template_step(world: &mut ContractWorld, user: String, uri_template: String) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let state = take(&mut world.state);
+        update_uri_template(
+            mock_contract_context(get_address_for_user(user)),
+            state,
+            uri_template,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} sets the '{word}' domain token uri to '{word}'")]
+fn set_domain_token_uri(world: &mut ContractWorld, user: String, domain: String, uri: String) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let token_id = world.state.pns.get_token_id(&domain).unwrap();
+        let state = take(&mut world.state);
+
+        set_token_uri(
+            mock_contract_context(get_address_for_user(user)),
+            state,
+            token_id,
+            Some(uri),
+            false,
+        )
+    }));
+
+    if let Ok((new_state, _)) = res {
+        world.state = new_state;
+    }
+}
+
+#[given(expr = "{word} froze the '{word}' domain token uri")]
+fn freeze_domain_token_uri(world: &mut ContractWorld, user: String, domain: String) {
+    let token_id = world.state.pns.get_token_id(&domain).unwrap();
+    let state = take(&mut world.state);
+
+    let (new_state, _) = set_token_uri(
+        mock_contract_context(get_address_for_user(user)),
+        state,
+        token_id,
+        None,
+        true,
+    );
+
+    world.state = new_state;
+}
+
 #[then(expr = "{word} owns '{word}' domain")]
 fn owns_the_domain(world: &mut ContractWorld, user: String, domain: String) {
     let domain = world.state.pns.get_domain(&domain).unwrap();
@@ -455,6 +902,56 @@ fn owns_the_domain(world: &mut ContractWorld, user: String, domain: String) {
     );
 }
 
+#[then(expr = "the receiver contract owns '{word}' domain")]
+fn receiver_contract_owns_the_domain(world: &mut ContractWorld, domain: String) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert_eq!(
+        world.state.nft.owner_of(domain.token_id),
+        mock_receiver_contract_address()
+    );
+}
+
+#[then(expr = "{word} is the manager of '{word}' domain")]
+fn is_manager_of_domain(world: &mut ContractWorld, user: String, domain: String) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert_eq!(
+        domain.manager,
+        Some(mock_address(get_address_for_user(user)))
+    );
+}
+
+#[then(expr = "'{word}' domain has no manager")]
+fn domain_has_no_manager(world: &mut ContractWorld, domain: String) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert_eq!(domain.manager, None);
+}
+
+#[then(expr = "'{word}' domain has {int} custom record slots")]
+fn domain_has_custom_record_slots(world: &mut ContractWorld, domain: String, slots: usize) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert_eq!(
+        world
+            .state
+            .pns
+            .record_limits
+            .get_custom_record_slots(&domain),
+        slots
+    );
+}
+
+#[then(expr = "'{word}' domain has no extra record capacity")]
+fn domain_has_no_extra_record_capacity(world: &mut ContractWorld, domain: String) {
+    let domain = world.state.pns.get_domain(&domain);
+
+    assert!(domain.map_or(true, |domain| {
+        domain.extra_custom_record_slots == 0 && domain.extra_data_length == 0
+    }));
+}
+
 #[then(expr = "'{word}' domain is not minted")]
 fn domain_is_not_minted(world: &mut ContractWorld, domain: String) {
     let domain = world.state.pns.get_domain(&domain);
@@ -462,6 +959,25 @@ fn domain_is_not_minted(world: &mut ContractWorld, domain: String) {
     assert_eq!(domain, None);
 }
 
+#[then(expr = "'{word}' domain is not listed")]
+fn domain_is_not_listed(world: &mut ContractWorld, domain: String) {
+    let token_id = world.state.pns.get_token_id(&domain).unwrap();
+
+    assert_eq!(world.state.listings.get(&token_id), None);
+}
+
+#[then(expr = "'{word}' domain has token id {int}")]
+fn domain_has_token_id(world: &mut ContractWorld, domain: String, token_id: u128) {
+    assert_eq!(world.state.pns.get_token_id(&domain), Some(token_id));
+}
+
+#[then(expr = "'{word}' domain token uri is '{word}'")]
+fn domain_token_uri_is(world: &mut ContractWorld, domain: String, uri: String) {
+    let token_id = world.state.pns.get_token_id(&domain).unwrap();
+
+    assert_eq!(world.state.nft.token_uri(token_id, &domain), uri);
+}
+
 #[then(expr = "{word} mint count is {int}")]
 fn mint_counts(world: &mut ContractWorld, user: String, count: u32) {
     let user = mock_address(get_address_for_user(user));
@@ -506,6 +1022,13 @@ fn domain_has_no_record(world: &mut ContractWorld, domain: String, class: String
     }
 }
 
+#[then(expr = "'{word}' domain has a '{word}' record")]
+fn domain_has_record(world: &mut ContractWorld, domain: String, class: String) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert!(domain.get_record(&get_record_class_given(class)).is_some());
+}
+
 #[then(regex = r"'(.+)' domain (does not expire|expires) in (\d+) years")]
 fn domain_expires_in(world: &mut ContractWorld, domain: String, action: String, years: u32) {
     let domain = world.state.pns.get_domain(&domain);
@@ -0,0 +1,63 @@
+Feature: Import feature
+
+  Scenario: An admin imports a domain with its original token id
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    When Alice imports 'mpc.name' domain with token id 5 for Bob
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain has token id 5
+
+  Scenario: An admin imports a subdomain after its parent
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    And Alice imported 'mpc.name' domain with token id 5 for Bob
+    When Alice imports 'sub.mpc.name' domain with token id 8 and 'mpc.name' as parent for Bob
+    Then Bob owns 'sub.mpc.name' domain
+
+  Scenario: The import of a subdomain that is not a child of its parent does not happen
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    And Alice imported 'mpc.name' domain with token id 5 for Bob
+    When Alice imports 'mpc.namexyz' domain with token id 8 and 'mpc.name' as parent for Bob
+    Then 'mpc.namexyz' domain is not minted
+
+  Scenario: Importing the same batch twice does not fail
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    And Alice imported 'mpc.name' domain with token id 5 for Bob
+    When Alice imports 'mpc.name' domain with token id 5 for Bob
+    Then Bob owns 'mpc.name' domain
+
+  Scenario: A mint after the import takes a token id past the imported ones
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    And Alice imported 'mpc.name' domain with token id 5 for Bob
+    And contract config 'contract_enabled' is 'true'
+    When Alice mints 'meta.name' domain without fees and a parent
+    Then Alice owns 'meta.name' domain
+    And 'meta.name' domain has token id 6
+
+  Scenario: A user without the admin role cannot import domains
+    Given a meta names contract
+    And contract config 'contract_enabled' is 'false'
+    When Alice imports 'mpc.name' domain with token id 5 for Bob
+    Then 'mpc.name' domain is not minted
+
+  Scenario: The import does not happen while the contract is enabled
+    Given a meta names contract
+    And Alice user with the admin role
+    When Alice imports 'mpc.name' domain with token id 5 for Bob
+    Then 'mpc.name' domain is not minted
+
+  Scenario: The import does not happen once finalized
+    Given a meta names contract
+    And Alice user with the admin role
+    And contract config 'contract_enabled' is 'false'
+    And Alice finalized the import
+    When Alice imports 'mpc.name' domain with token id 5 for Bob
+    Then 'mpc.name' domain is not minted
@@ -0,0 +1,16 @@
+Feature: Domain manager feature
+
+  Scenario: The owner sets the manager of a domain
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice set Bob as the manager of 'mpc.name' domain
+    Then Bob is the manager of 'mpc.name' domain
+    And Alice owns 'mpc.name' domain
+
+  Scenario: The transfer clears the manager of a domain
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice set Bob as the manager of 'mpc.name' domain
+    When Alice transfers the 'mpc.name' domain to Bob
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain has no manager
@@ -0,0 +1,20 @@
+Feature: Metadata feature
+
+  Scenario: The token uri is resolved from the uri template
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When contract updates the uri template to 'https://metanames.io/{name}.json'
+    Then 'mpc.name' domain token uri is 'https://metanames.io/mpc.name.json'
+
+  Scenario: The token uri set by the owner takes precedence over the uri template
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice sets the 'mpc.name' domain token uri to 'ipfs://metadata'
+    Then 'mpc.name' domain token uri is 'ipfs://metadata'
+
+  Scenario: The frozen token uri does not follow the uri template
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice froze the 'mpc.name' domain token uri
+    When contract updates the uri template to 'https://metanames.io/{name}.json'
+    Then 'mpc.name' domain token uri is 'metanames.io0'
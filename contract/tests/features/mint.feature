@@ -50,21 +50,21 @@ Feature: Mint feature
   Scenario: The mint with the owned parent occurs properly
     Given a meta names contract
     And Alice minted 'mpc.name' domain without a parent
-    When Alice mints 'mpc.name.sub' domain with 'mpc.name' domain as the parent
-    Then Alice owns 'mpc.name.sub' domain
+    When Alice mints 'sub.mpc.name' domain with 'mpc.name' domain as the parent
+    Then Alice owns 'sub.mpc.name' domain
 
   Scenario: The mint with the approved parent occurs properly
     Given a meta names contract
     And Alice minted 'mpc.name' domain without a parent
     And Alice approved Bob on 'mpc.name' domain
-    When Bob mints 'mpc.name.sub' domain with 'mpc.name' domain as the parent
-    Then Bob owns 'mpc.name.sub' domain
+    When Bob mints 'sub.mpc.name' domain with 'mpc.name' domain as the parent
+    Then Bob owns 'sub.mpc.name' domain
 
   Scenario: The mint with a not owned parent does not happen
     Given a meta names contract
     And Alice minted 'mpc.name' domain without a parent
-    When Bob mints 'mpc.name.sub' domain with 'mpc.name' domain as the parent
-    Then 'mpc.name.sub' domain is not minted
+    When Bob mints 'sub.mpc.name' domain with 'mpc.name' domain as the parent
+    Then 'sub.mpc.name' domain is not minted
 
   Scenario: The mint with the owned but not coherent parent does not happen
     Given a meta names contract
@@ -72,6 +72,18 @@ Feature: Mint feature
     When Alice mints 'mpc.random' domain with 'mpc.name' domain as the parent
     Then 'mpc.random' domain is not minted
 
+  Scenario: The mint of a domain under a minted domain without its parent does not happen
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Bob mints 'sub.mpc.name' domain without fees and a parent
+    Then 'sub.mpc.name' domain is not minted
+
+  Scenario: The mint of a domain under a minted ancestor without its parent does not happen
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Bob mints 'deep.sub.mpc.name' domain without fees and a parent
+    Then 'deep.sub.mpc.name' domain is not minted
+
   Scenario: The mint of a 35 chars long domain does not happen
     Given a meta names contract
     When Alice mints 'this.is.a.too.long.domain.meta.name' domain without fees and a parent
@@ -84,3 +96,19 @@ Feature: Mint feature
     When Alice batch mints 'meta.name' and 'meta.test' domain without fees and a parent
     Then Alice owns 'meta.name' domain
     And Alice owns 'meta.test' domain
+
+  Scenario: The mint with a parent covered by a scoped approval occurs properly
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted 'sub.mpc.name' domain with 'mpc.name' domain as the parent
+    And Alice approved Bob on 'mpc.name' domain and its subdomains
+    When Bob mints 'deep.sub.mpc.name' domain with 'sub.mpc.name' domain as the parent
+    Then Bob owns 'deep.sub.mpc.name' domain
+
+  Scenario: The mint with the owned parent does not happen when the subdomain count limit is reached
+    Given a meta names contract
+    And contract config 'subdomain_count_limit' is '1'
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted 'sub.mpc.name' domain with 'mpc.name' domain as the parent
+    When Alice mints 'other.mpc.name' domain with 'mpc.name' domain as the parent
+    Then 'other.mpc.name' domain is not minted
@@ -0,0 +1,20 @@
+Feature: Record capacity feature
+
+  Scenario: The purchase of record capacity adds custom record slots
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice buys 2 custom record slots and 10 bytes of record data for 'mpc.name' domain with 0 payment token id
+    Then 'mpc.name' domain has 12 custom record slots
+
+  Scenario: The purchase of record capacity with the wrong payment token id, fails
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice buys 2 custom record slots and 10 bytes of record data for 'mpc.name' domain with 1 payment token id
+    Then 'mpc.name' domain has no extra record capacity
+
+  Scenario: An admin gets record capacity without payment
+    Given a meta names contract
+    And Alice user with the admin role
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice buys 5 custom record slots and 0 bytes of record data for 'mpc.name' domain
+    Then 'mpc.name' domain has 15 custom record slots
@@ -0,0 +1,26 @@
+Feature: Sale feature
+
+  Scenario: A listed domain is transferred to its buyer once paid
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice listed 'mpc.name' domain for 100
+    When Bob buys 'mpc.name' domain
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain is not listed
+
+  Scenario: The transfer of a listed domain removes its listing
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice listed 'mpc.name' domain for 100
+    When Alice transfers the 'mpc.name' domain to Bob
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain is not listed
+
+  Scenario: The sale of a domain clears its records
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
+    And Alice listed 'mpc.name' domain for 100
+    When Bob buys 'mpc.name' domain
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain does not have a 'Wallet' record
@@ -3,7 +3,55 @@ Feature: Transfer feature
   Scenario: The transfer cleans all the existing records
     Given a meta names contract
     And Alice minted 'mpc.name' domain without a parent
-    And Alice minted the 'Wallet' record with 'data' data for the 'mpc.name' domain
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
     When Alice transfers the 'mpc.name' domain to Bob
     Then Bob owns 'mpc.name' domain
     And 'mpc.name' domain does not have a 'Wallet' record
+
+  Scenario: The safe transfer to an account occurs right away
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice safe transfers the 'mpc.name' domain to Bob
+    Then Bob owns 'mpc.name' domain
+
+  Scenario: The safe transfer to a contract occurs once acknowledged
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    When Alice safe transfers the 'mpc.name' domain to the accepting receiver contract
+    Then the receiver contract owns 'mpc.name' domain
+
+  Scenario: The safe transfer to a contract does not happen when rejected
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
+    When Alice safe transfers the 'mpc.name' domain to the rejecting receiver contract
+    Then Alice owns 'mpc.name' domain
+    And 'mpc.name' domain has a 'Wallet' record
+
+  Scenario: The batch transfer moves every domain and cleans their records
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted 'mpc.other' domain without a parent
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
+    When Alice batch transfers the 'mpc.name' and 'mpc.other' domains to Bob
+    Then Bob owns 'mpc.name' domain
+    And Bob owns 'mpc.other' domain
+    And 'mpc.name' domain does not have a 'Wallet' record
+
+  Scenario: The transfer between own wallets keeps all the records
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
+    When Alice transfers the 'mpc.name' domain to Bob keeping all records
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain has a 'Wallet' record
+
+  Scenario: The transfer keeps only the allowed records
+    Given a meta names contract
+    And Alice minted 'mpc.name' domain without a parent
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'mpc.name' domain
+    And Alice minted the 'Twitter' record with 'meta_names' data for the 'mpc.name' domain
+    When Alice transfers the 'mpc.name' domain to Bob keeping only the 'Twitter' record
+    Then Bob owns 'mpc.name' domain
+    And 'mpc.name' domain has a 'Twitter' record
+    And 'mpc.name' domain does not have a 'Wallet' record
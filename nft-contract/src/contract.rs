@@ -0,0 +1,366 @@
+use crate::{
+    msg::{
+        ApprovedEvent, ApprovedForAllEvent, BalanceOfEvent, InitMsg, OwnerOfEvent,
+        OwnerTokensEvent, TokenEvent, TokenUriEvent,
+    },
+    state::{ContractState, UserRole},
+    ContractError,
+};
+
+use access_control::{actions as ac_actions, msg as ac_msg};
+use contract_version_base::state::ContractVersionBase;
+use nft::{actions as nft_actions, msg as nft_msg};
+use pbc_contract_common::{address::Address, context::ContractContext, events::EventGroup};
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[init]
+pub fn initialize(ctx: ContractContext, msg: InitMsg) -> (ContractState, Vec<EventGroup>) {
+    let mut nft = nft_actions::execute_init(
+        &ctx,
+        &nft_msg::NFTInitMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            uri_template: msg.uri_template,
+        },
+    );
+    // Minting is gated by the minter role, the deployer must not operate on the holders' tokens
+    nft.contract_owner = None;
+
+    let mut access_control = ac_actions::execute_init(&ac_msg::ACInitMsg {
+        admin_addresses: msg.admin_addresses,
+        additional_roles: vec![UserRole::Minter {} as u8],
+    });
+    for minter in msg.minter_addresses.iter() {
+        access_control._set_role(UserRole::Minter {} as u8, minter);
+    }
+
+    let state = ContractState {
+        access_control,
+        nft,
+        version: ContractVersionBase::new(CONTRACT_NAME, CONTRACT_VERSION),
+    };
+
+    (state, vec![])
+}
+
+#[action(shortname = 0x03)]
+pub fn transfer_from(
+    ctx: ContractContext,
+    mut state: ContractState,
+    from: Address,
+    to: Address,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_transfer_from(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTTransferFromMsg { from, to, token_id },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x05)]
+pub fn approve(
+    ctx: ContractContext,
+    mut state: ContractState,
+    approved: Option<Address>,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_approve(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveMsg { approved, token_id },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x07)]
+pub fn set_approval_for_all(
+    ctx: ContractContext,
+    mut state: ContractState,
+    operator: Address,
+    approved: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_set_approval_for_all(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveForAllMsg { operator, approved },
+    );
+
+    (state, events)
+}
+
+/// Mints a new token, only accounts with the minter role are allowed to mint
+#[action(shortname = 0x09)]
+pub fn mint(
+    ctx: ContractContext,
+    mut state: ContractState,
+    to: Address,
+    token_id: u128,
+    token_uri: Option<String>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Minter {}, &ctx.sender);
+
+    let events = nft_actions::execute_mint(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTMintMsg {
+            token_id,
+            to,
+            token_uri,
+        },
+    );
+
+    (state, events)
+}
+
+/// Returns the owner of the token as data in the event
+/// the event data is of type OwnerOfEvent
+#[action(shortname = 0x10)]
+pub fn owner_of(
+    ctx: ContractContext,
+    state: ContractState,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let owner_of_event = OwnerOfEvent {
+        token_id,
+        owner: state.nft.owner_of(token_id),
+    };
+
+    event_builder.return_data(owner_of_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the number of tokens owned by the address as data in the event
+/// the event data is of type BalanceOfEvent
+#[action(shortname = 0x11)]
+pub fn balance_of(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let balance_of_event = BalanceOfEvent {
+        owner,
+        balance: state.nft.balance_of(owner),
+        total_supply: state.nft.supply,
+    };
+
+    event_builder.return_data(balance_of_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the approved address of the token as data in the event
+/// the event data is of type ApprovedEvent
+#[action(shortname = 0x12)]
+pub fn get_approved(
+    ctx: ContractContext,
+    state: ContractState,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let approved = state.nft.get_approved(token_id).filter(|approved| {
+        state
+            .nft
+            .is_approved_at(*approved, token_id, ctx.block_production_time)
+    });
+    let approved_event = ApprovedEvent { token_id, approved };
+
+    event_builder.return_data(approved_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the token owned by the address at the given index as data in the event
+/// the event data is of type TokenEvent
+#[action(shortname = 0x13)]
+pub fn token_of_owner_by_index(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+    index: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let token_id = state.nft.token_of_owner_by_index(owner, index);
+    event_builder.return_data(TokenEvent { token_id });
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the token at the given index as data in the event
+/// the event data is of type TokenEvent
+#[action(shortname = 0x14)]
+pub fn token_by_index(
+    ctx: ContractContext,
+    state: ContractState,
+    index: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let token_id = state.nft.token_by_index(index);
+    event_builder.return_data(TokenEvent { token_id });
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns a page of the tokens owned by the address as data in the event
+/// the event data is of type OwnerTokensEvent
+#[action(shortname = 0x15)]
+pub fn tokens_of_owner(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+    offset: u128,
+    limit: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let owner_tokens_event = OwnerTokensEvent {
+        owner,
+        token_ids: state.nft.tokens_of_owner(owner, offset, limit),
+        balance: state.nft.balance_of(owner),
+    };
+
+    event_builder.return_data(owner_tokens_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns whether the operator is approved for all the tokens of the owner as data in the event
+/// the event data is of type ApprovedForAllEvent
+#[action(shortname = 0x16)]
+pub fn is_approved_for_all(
+    ctx: ContractContext,
+    state: ContractState,
+    owner: Address,
+    operator: Address,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let approved_for_all_event = ApprovedForAllEvent {
+        owner,
+        operator,
+        approved: state
+            .nft
+            .is_approved_for_all(owner, operator, ctx.block_production_time),
+    };
+
+    event_builder.return_data(approved_for_all_event);
+
+    (state, vec![event_builder.build()])
+}
+
+#[action(shortname = 0x17)]
+pub fn burn(
+    ctx: ContractContext,
+    mut state: ContractState,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_burn(&ctx, &mut state.nft, &nft_msg::NFTBurnMsg { token_id });
+
+    (state, events)
+}
+
+#[action(shortname = 0x24)]
+pub fn update_user_role(
+    ctx: ContractContext,
+    mut state: ContractState,
+    role: UserRole,
+    address: Address,
+    active: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    let msg = ac_msg::ACRoleMsg {
+        role: role as u8,
+        account: address,
+    };
+
+    if active {
+        ac_actions::execute_grant_role(&ctx, &mut state.access_control, &msg);
+    } else {
+        ac_actions::execute_revoke_role(&ctx, &mut state.access_control, &msg);
+    }
+
+    (state, vec![])
+}
+
+/// Updates the template which the token uri's fit into
+#[action(shortname = 0x33)]
+pub fn update_uri_template(
+    ctx: ContractContext,
+    mut state: ContractState,
+    uri_template: String,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Admin {}, &ctx.sender);
+
+    let events = nft_actions::execute_update_uri_template(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTUpdateUriTemplateMsg { uri_template },
+    );
+
+    (state, events)
+}
+
+/// Sets or clears the token uri, optionally freezing it
+/// Only the owner of the token is allowed to
+#[action(shortname = 0x34)]
+pub fn set_token_uri(
+    ctx: ContractContext,
+    mut state: ContractState,
+    token_id: u128,
+    token_uri: Option<String>,
+    freeze: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_set_token_uri(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTSetTokenUriMsg {
+            token_id,
+            token_uri,
+            freeze,
+        },
+        &token_id.to_string(),
+    );
+
+    (state, events)
+}
+
+/// Returns the token uri as data in the event,
+/// the tokens have no name, so the `{name}` placeholder of the template is replaced by the token id
+/// the event data is of type TokenUriEvent
+#[action(shortname = 0x35)]
+pub fn token_uri(
+    ctx: ContractContext,
+    state: ContractState,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let token_uri_event = TokenUriEvent {
+        token_id,
+        token_uri: state.nft.token_uri(token_id, &token_id.to_string()),
+    };
+
+    event_builder.return_data(token_uri_event);
+
+    (state, vec![event_builder.build()])
+}
+
+fn assert_has_role(state: &ContractState, role: UserRole, account: &Address) {
+    assert!(
+        state.access_control.has_role(role as u8, account),
+        "{}",
+        ContractError::Unauthorized
+    );
+}
@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+/// This enum describes nft collection contract errors
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("Unauthorized")]
+    Unauthorized,
+}
@@ -0,0 +1,12 @@
+#![doc = include_str!("../README.md")]
+#![allow(unused_variables)]
+
+#[macro_use]
+extern crate pbc_contract_codegen;
+
+pub mod contract;
+mod error;
+pub mod msg;
+pub mod state;
+
+pub use crate::error::ContractError;
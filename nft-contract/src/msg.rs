@@ -0,0 +1,79 @@
+use create_type_spec_derive::CreateTypeSpec;
+use pbc_contract_common::address::Address;
+use read_write_rpc_derive::ReadWriteRPC;
+
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct InitMsg {
+    pub admin_addresses: Vec<Address>,
+    pub minter_addresses: Vec<Address>,
+    pub name: String,
+    pub symbol: String,
+    pub uri_template: String,
+}
+
+// Events structs
+
+/// Struct for owner of event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct OwnerOfEvent {
+    pub token_id: u128,
+    pub owner: Address,
+}
+
+/// Struct for balance of event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct BalanceOfEvent {
+    pub owner: Address,
+    pub balance: u128,
+    pub total_supply: u128,
+}
+
+/// Struct for approved event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct ApprovedEvent {
+    pub token_id: u128,
+    pub approved: Option<Address>,
+}
+
+/// Struct for approved for all event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct ApprovedForAllEvent {
+    pub owner: Address,
+    pub operator: Address,
+    pub approved: bool,
+}
+
+/// Struct for token uri event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TokenUriEvent {
+    pub token_id: u128,
+    pub token_uri: String,
+}
+
+/// Struct for token event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct TokenEvent {
+    pub token_id: u128,
+}
+
+/// Struct for owner tokens event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct OwnerTokensEvent {
+    pub owner: Address,
+    pub token_ids: Vec<u128>,
+    pub balance: u128,
+}
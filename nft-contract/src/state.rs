@@ -0,0 +1,26 @@
+use access_control::state::AccessControlState;
+use contract_version_base::state::ContractVersionBase;
+use create_type_spec_derive::CreateTypeSpec;
+use nft::state::NFTContractState;
+use read_write_rpc_derive::ReadWriteRPC;
+use read_write_state_derive::ReadWriteState;
+
+#[allow(unused_imports)]
+use crate::contract::__PBC_IS_ZK_CONTRACT;
+
+#[state]
+#[derive(Default, Debug)]
+pub struct ContractState {
+    pub access_control: AccessControlState,
+    pub nft: NFTContractState,
+    pub version: ContractVersionBase,
+}
+
+#[repr(u8)]
+#[derive(ReadWriteRPC, ReadWriteState, CreateTypeSpec, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum UserRole {
+    #[discriminant(0)]
+    Admin {},
+    #[discriminant(1)]
+    Minter {},
+}
@@ -0,0 +1,173 @@
+use std::{mem::take, panic::catch_unwind};
+
+use cucumber::{given, then, when, World};
+use nft_contract::{
+    contract::{
+        approve, burn, initialize, mint, set_approval_for_all, transfer_from, update_user_role,
+    },
+    msg::InitMsg,
+    state::{ContractState, UserRole},
+};
+use utils::tests::{get_address_for_user, mock_address, mock_contract_context, SYSTEM_ADDRESS};
+
+#[derive(Debug, Default, World)]
+pub struct ContractWorld {
+    state: ContractState,
+}
+
+#[given(regex = "an nft collection contract")]
+fn nft_collection_contract(world: &mut ContractWorld) {
+    let msg = InitMsg {
+        admin_addresses: vec![mock_address(SYSTEM_ADDRESS)],
+        minter_addresses: vec![],
+        name: "Meta Names Collection".to_string(),
+        symbol: "mpc".to_string(),
+        uri_template: "metanames.io/".to_string(),
+    };
+
+    let (state, _) = initialize(mock_contract_context(SYSTEM_ADDRESS), msg);
+
+    world.state = state;
+}
+
+#[given(expr = "{word} user with the minter role")]
+fn user_with_minter_role(world: &mut ContractWorld, user: String) {
+    let (state, _) = update_user_role(
+        mock_contract_context(SYSTEM_ADDRESS),
+        take(&mut world.state),
+        UserRole::Minter {},
+        mock_address(get_address_for_user(user)),
+        true,
+    );
+
+    world.state = state;
+}
+
+#[given(expr = "{word} minted the token {int} to {word}")]
+#[when(expr = "{word} mints the token {int} to {word}")]
+fn mint_token(world: &mut ContractWorld, user: String, token_id: u128, to: String) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let (new_state, _) = mint(
+            mock_contract_context(get_address_for_user(user)),
+            take(&mut world.state),
+            mock_address(get_address_for_user(to)),
+            token_id,
+            None,
+        );
+
+        new_state
+    }));
+
+    if let Ok(new_state) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "{word} transfers the token {int} from {word} to {word}")]
+fn transfer_token(
+    world: &mut ContractWorld,
+    user: String,
+    token_id: u128,
+    from: String,
+    to: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let (new_state, _) = transfer_from(
+            mock_contract_context(get_address_for_user(user)),
+            take(&mut world.state),
+            mock_address(get_address_for_user(from)),
+            mock_address(get_address_for_user(to)),
+            token_id,
+        );
+
+        new_state
+    }));
+
+    if let Ok(new_state) = res {
+        world.state = new_state;
+    }
+}
+
+#[given(expr = "{word} approved {word} on the token {int}")]
+fn approve_token(world: &mut ContractWorld, user: String, approved: String, token_id: u128) {
+    let (new_state, _) = approve(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        Some(mock_address(get_address_for_user(approved))),
+        token_id,
+    );
+
+    world.state = new_state;
+}
+
+#[given(expr = "{word} approved {word} as operator")]
+fn approve_operator(world: &mut ContractWorld, user: String, operator: String) {
+    let (new_state, _) = set_approval_for_all(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        mock_address(get_address_for_user(operator)),
+        true,
+    );
+
+    world.state = new_state;
+}
+
+#[when(expr = "{word} burns the token {int}")]
+fn burn_token(world: &mut ContractWorld, user: String, token_id: u128) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let (new_state, _) = burn(
+            mock_contract_context(get_address_for_user(user)),
+            take(&mut world.state),
+            token_id,
+        );
+
+        new_state
+    }));
+
+    if let Ok(new_state) = res {
+        world.state = new_state;
+    }
+}
+
+#[then(expr = "{word} owns the token {int}")]
+fn owns_the_token(world: &mut ContractWorld, user: String, token_id: u128) {
+    assert_eq!(
+        world.state.nft.owner_of(token_id),
+        mock_address(get_address_for_user(user))
+    );
+}
+
+#[then(expr = "{word} is not allowed to operate on the token {int}")]
+fn not_allowed_on_the_token(world: &mut ContractWorld, user: String, token_id: u128) {
+    let ctx = mock_contract_context(get_address_for_user(user.clone()));
+
+    assert!(!world.state.nft.is_approved_or_owner(
+        mock_address(get_address_for_user(user)),
+        token_id,
+        ctx.block_production_time
+    ));
+}
+
+#[then(expr = "the token {int} is not minted")]
+fn token_is_not_minted(world: &mut ContractWorld, token_id: u128) {
+    assert!(!world.state.nft.exists(token_id));
+}
+
+#[then(expr = "{word} has a balance of {int}")]
+fn has_balance(world: &mut ContractWorld, user: String, balance: u128) {
+    assert_eq!(
+        world
+            .state
+            .nft
+            .balance_of(mock_address(get_address_for_user(user))),
+        balance
+    );
+}
+
+// This runs before everything else, so you can setup things here.
+fn main() {
+    // You may choose any executor you like (`tokio`, `async-std`, etc.).
+    // You may even have an `async` main, it doesn't matter. The point is that
+    // Cucumber is composable. :)
+    futures::executor::block_on(ContractWorld::run("tests/features"));
+}
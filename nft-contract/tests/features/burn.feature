@@ -0,0 +1,10 @@
+Feature: Burn feature
+
+  Scenario: The owner burns a token
+    Given an nft collection contract
+    And Alice user with the minter role
+    And Alice minted the token 1 to Alice
+    When Alice burns the token 1
+    Then the token 1 is not minted
+    And Alice has a balance of 0
+
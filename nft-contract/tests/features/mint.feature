@@ -0,0 +1,13 @@
+Feature: Mint feature
+
+  Scenario: The minter mints a token
+    Given an nft collection contract
+    And Alice user with the minter role
+    When Alice mints the token 1 to Bob
+    Then Bob owns the token 1
+    And Bob has a balance of 1
+
+  Scenario: The mint without the minter role does not happen
+    Given an nft collection contract
+    When Alice mints the token 1 to Alice
+    Then the token 1 is not minted
@@ -0,0 +1,31 @@
+Feature: Transfer feature
+
+  Scenario: The owner transfers a token
+    Given an nft collection contract
+    And Alice user with the minter role
+    And Alice minted the token 1 to Alice
+    When Alice transfers the token 1 from Alice to Bob
+    Then Bob owns the token 1
+    And Alice has a balance of 0
+
+  Scenario: The approved address transfers a token
+    Given an nft collection contract
+    And Alice user with the minter role
+    And Alice minted the token 1 to Alice
+    And Alice approved Bob on the token 1
+    When Bob transfers the token 1 from Alice to Bob
+    Then Bob owns the token 1
+
+  Scenario: The operator transfers a token
+    Given an nft collection contract
+    And Alice user with the minter role
+    And Alice minted the token 1 to Alice
+    And Alice approved Bob as operator
+    When Bob transfers the token 1 from Alice to Bob
+    Then Bob owns the token 1
+
+  Scenario: The deployer cannot operate on a token it is not approved for
+    Given an nft collection contract
+    And Alice user with the minter role
+    And Alice minted the token 1 to Alice
+    Then contract is not allowed to operate on the token 1
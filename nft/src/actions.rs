@@ -2,9 +2,14 @@ use pbc_contract_common::{avl_tree_map::AvlTreeMap, context::ContractContext, ev
 
 use crate::{
     msg::{
-        NFTApproveForAllMsg, NFTApproveMsg, NFTBurnMsg, NFTInitMsg, NFTMintMsg, NFTTransferFromMsg,
+        NFTApproveForAllMsg, NFTApproveForAllWithExpiryMsg, NFTApproveMsg, NFTApproveWithExpiryMsg,
+        NFTBurnMsg, NFTInitMsg, NFTMintMsg, NFTScopedApproveForAllMsg, NFTSetDefaultRoyaltyMsg,
+        NFTSetTokenRoyaltyMsg, NFTSetTokenUriMsg, NFTTransferFromMsg, NFTUpdateUriTemplateMsg,
+    },
+    state::{
+        NFTContractState, OperatorApproval, RoyaltyInfo, ScopedOperatorApproval, Unit,
+        MAX_URI_LENGTH, ROYALTY_BASIS_POINTS_DENOMINATOR,
     },
-    state::{NFTContractState, OperatorApproval, Unit},
     ContractError,
 };
 
@@ -17,12 +22,24 @@ pub fn execute_init(ctx: &ContractContext, msg: &NFTInitMsg) -> NFTContractState
         name: msg.name.clone(),
         symbol: msg.symbol.clone(),
         supply: 0,
+        next_token_id: 0,
         operator_approvals: AvlTreeMap::new(),
         owners: AvlTreeMap::new(),
         token_approvals: AvlTreeMap::new(),
         owners_balance: AvlTreeMap::new(),
         uri_template: msg.uri_template.clone(),
         token_uri_details: AvlTreeMap::new(),
+        owned_tokens: AvlTreeMap::new(),
+        owned_tokens_index: AvlTreeMap::new(),
+        all_tokens: AvlTreeMap::new(),
+        all_tokens_index: AvlTreeMap::new(),
+        frozen_token_uris: AvlTreeMap::new(),
+        token_approval_expirations: AvlTreeMap::new(),
+        operator_approval_expirations: AvlTreeMap::new(),
+        scoped_operator_approvals: AvlTreeMap::new(),
+        parent_tokens: AvlTreeMap::new(),
+        default_royalty: None,
+        token_royalties: AvlTreeMap::new(),
     }
 }
 
@@ -37,13 +54,17 @@ pub fn execute_mint(
     assert!(!state.exists(msg.token_id), "{}", ContractError::Minted);
 
     state.owners.insert(msg.token_id, msg.to);
+    state._add_token_to_owner_enumeration(msg.to, msg.token_id);
     state._increase_owner_balance(msg.to);
 
     if let Some(token_uri) = msg.token_uri.clone() {
+        assert_uri_length(&token_uri);
         state.token_uri_details.insert(msg.token_id, token_uri);
     }
 
+    state._add_token_to_all_tokens_enumeration(msg.token_id);
     state.increase_supply();
+    state.next_token_id = state.next_token_id.max(msg.token_id + 1);
 
     vec![]
 }
@@ -56,14 +77,35 @@ pub fn execute_approve(
     ctx: &ContractContext,
     state: &mut NFTContractState,
     msg: &NFTApproveMsg,
+) -> Vec<EventGroup> {
+    execute_approve_with_expiry(
+        ctx,
+        state,
+        &NFTApproveWithExpiryMsg {
+            approved: msg.approved,
+            token_id: msg.token_id,
+            expires_at: None,
+        },
+    )
+}
+
+/// Change or reaffirm the approved address for an NFT until the given time.
+/// None indicates there is no approved address, or that the approval never expires.
+/// Throws unless `ctx.sender` is the current NFT owner, or an authorized
+/// operator of the current owner.
+pub fn execute_approve_with_expiry(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTApproveWithExpiryMsg,
 ) -> Vec<EventGroup> {
     let owner = state.owner_of(msg.token_id);
     assert!(
-        ctx.sender == owner || state.is_approved_for_all(owner, ctx.sender),
+        ctx.sender == owner
+            || state.is_approved_for_all(owner, ctx.sender, ctx.block_production_time),
         "{}",
         ContractError::Unauthorized
     );
-    state._approve(msg.approved, msg.token_id);
+    state._approve(msg.approved, msg.token_id, msg.expires_at);
 
     vec![]
 }
@@ -75,6 +117,25 @@ pub fn execute_set_approval_for_all(
     ctx: &ContractContext,
     state: &mut NFTContractState,
     msg: &NFTApproveForAllMsg,
+) -> Vec<EventGroup> {
+    execute_set_approval_for_all_with_expiry(
+        ctx,
+        state,
+        &NFTApproveForAllWithExpiryMsg {
+            operator: msg.operator,
+            approved: msg.approved,
+            expires_at: None,
+        },
+    )
+}
+
+/// Enable or disable approval for a third party ("operator") to manage all of
+/// `ctx.sender`'s assets until the given time, none never expires.
+/// Throws if `operator` == `ctx.sender`.
+pub fn execute_set_approval_for_all_with_expiry(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTApproveForAllWithExpiryMsg,
 ) -> Vec<EventGroup> {
     assert!(
         msg.operator != ctx.sender,
@@ -93,6 +154,46 @@ pub fn execute_set_approval_for_all(
         state.operator_approvals.remove(&operator_approval)
     }
 
+    match (msg.approved, msg.expires_at) {
+        (true, Some(expires_at)) => state
+            .operator_approval_expirations
+            .insert(operator_approval, expires_at),
+        _ => state
+            .operator_approval_expirations
+            .remove(&operator_approval),
+    }
+
+    vec![]
+}
+
+/// Enable or disable approval for a third party ("operator") to manage an NFT
+/// of `ctx.sender` and all of its child NFTs, until the given time, none never expires.
+/// Throws if `operator` == `ctx.sender` or `ctx.sender` is not the owner of the NFT.
+pub fn execute_set_scoped_approval_for_all(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTScopedApproveForAllMsg,
+) -> Vec<EventGroup> {
+    assert!(
+        msg.operator != ctx.sender && state.owner_of(msg.token_id) == ctx.sender,
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    let scoped_approval = ScopedOperatorApproval {
+        owner: ctx.sender,
+        operator: msg.operator,
+        token_id: msg.token_id,
+    };
+
+    if msg.approved {
+        state
+            .scoped_operator_approvals
+            .insert(scoped_approval, msg.expires_at);
+    } else {
+        state.scoped_operator_approvals.remove(&scoped_approval)
+    }
+
     vec![]
 }
 
@@ -109,7 +210,7 @@ pub fn execute_transfer_from(
     msg: &NFTTransferFromMsg,
 ) -> Vec<EventGroup> {
     assert!(
-        state.is_approved_or_owner(ctx.sender, msg.token_id),
+        state.is_approved_or_owner(ctx.sender, msg.token_id, ctx.block_production_time),
         "{}",
         ContractError::Unauthorized
     );
@@ -129,19 +230,120 @@ pub fn execute_burn(
 ) -> Vec<EventGroup> {
     let token_id = msg.token_id;
     assert!(
-        state.is_approved_or_owner(ctx.sender, token_id),
+        state.is_approved_or_owner(ctx.sender, token_id, ctx.block_production_time),
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    state._burn(token_id);
+
+    vec![]
+}
+
+/// Change the template which the uri's of the NFTs fit into.
+/// The permission check is left to the caller.
+/// Throws if the template is too long.
+pub fn execute_update_uri_template(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTUpdateUriTemplateMsg,
+) -> Vec<EventGroup> {
+    assert_uri_length(&msg.uri_template);
+
+    state.uri_template = msg.uri_template.clone();
+
+    vec![]
+}
+
+/// Set or clear the URI of an NFT, optionally freezing it.
+/// Freezing pins the URI currently resolved for `name`,
+/// so later template updates do not change it.
+///
+/// Throws unless `ctx.sender` is the current owner of the NFT,
+/// neither approved addresses nor the contract owner can change or freeze it.
+/// Throws if the URI of the NFT is frozen.
+pub fn execute_set_token_uri(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTSetTokenUriMsg,
+    name: &str,
+) -> Vec<EventGroup> {
+    assert!(
+        state.owner_of(msg.token_id) == ctx.sender,
         "{}",
         ContractError::Unauthorized
     );
+    assert!(
+        !state.is_token_uri_frozen(msg.token_id),
+        "{}",
+        ContractError::MetadataFrozen
+    );
+
+    match &msg.token_uri {
+        Some(token_uri) => {
+            assert_uri_length(token_uri);
+            state
+                .token_uri_details
+                .insert(msg.token_id, token_uri.clone());
+        }
+        None => state.token_uri_details.remove(&msg.token_id),
+    }
+
+    if msg.freeze {
+        let token_uri = state.token_uri(msg.token_id, name);
+        state.token_uri_details.insert(msg.token_id, token_uri);
+        state.frozen_token_uris.insert(msg.token_id, Unit {});
+    }
+
+    vec![]
+}
+
+/// Set or clear the royalty of the tokens without a royalty of their own.
+/// The permission check is left to the caller.
+/// Throws if the basis points exceed the sale price.
+pub fn execute_set_default_royalty(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTSetDefaultRoyaltyMsg,
+) -> Vec<EventGroup> {
+    if let Some(royalty) = &msg.royalty {
+        assert_royalty_valid(royalty);
+    }
+
+    state.default_royalty = msg.royalty;
+
+    vec![]
+}
 
-    let owner = state.owner_of(token_id);
-    // Clear approvals
-    state._approve(None, token_id);
+/// Set or clear the royalty of an NFT, overriding the default royalty.
+/// The permission check is left to the caller.
+/// Throws if `token_id` is not a valid NFT or the basis points exceed the sale price.
+pub fn execute_set_token_royalty(
+    ctx: &ContractContext,
+    state: &mut NFTContractState,
+    msg: &NFTSetTokenRoyaltyMsg,
+) -> Vec<EventGroup> {
+    assert!(state.exists(msg.token_id), "{}", ContractError::NotFound);
 
-    state.owners.remove(&token_id);
-    state._decrease_owner_balance(owner);
-    state.token_uri_details.remove(&token_id);
-    state.decrease_supply();
+    match &msg.royalty {
+        Some(royalty) => {
+            assert_royalty_valid(royalty);
+            state.token_royalties.insert(msg.token_id, *royalty);
+        }
+        None => state.token_royalties.remove(&msg.token_id),
+    }
 
     vec![]
 }
+
+fn assert_royalty_valid(royalty: &RoyaltyInfo) {
+    assert!(
+        royalty.basis_points <= ROYALTY_BASIS_POINTS_DENOMINATOR,
+        "{}",
+        ContractError::InvalidRoyalty
+    );
+}
+
+fn assert_uri_length(uri: &str) {
+    assert!(uri.len() <= MAX_URI_LENGTH, "{}", ContractError::UriTooLong);
+}
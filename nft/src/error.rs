@@ -20,4 +20,13 @@ pub enum ContractError {
 
     #[error("URI is too long")]
     UriTooLong,
+
+    #[error("Index out of bounds")]
+    IndexOutOfBounds,
+
+    #[error("Token metadata is frozen")]
+    MetadataFrozen,
+
+    #[error("Royalty basis points exceed the sale price")]
+    InvalidRoyalty,
 }
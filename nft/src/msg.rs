@@ -3,6 +3,8 @@ use pbc_contract_common::address::{Address, Shortname};
 use read_write_rpc_derive::ReadWriteRPC;
 
 use rpc_msg_derive::IntoShortnameRPCEvent;
+
+use crate::state::RoyaltyInfo;
 use utils::events::IntoShortnameRPCEvent;
 
 /// This structure describes fields for NFT initialize msg
@@ -43,6 +45,41 @@ pub struct NFTApproveForAllMsg {
     pub approved: bool,
 }
 
+/// This structure describes fields for NFT approve with expiry msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x3d)]
+pub struct NFTApproveWithExpiryMsg {
+    pub approved: Option<Address>,
+    /// token id
+    pub token_id: u128,
+    /// time the approval expires at, none never expires
+    pub expires_at: Option<i64>,
+}
+
+/// This structure describes fields for NFT approve for all with expiry msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x3e)]
+pub struct NFTApproveForAllWithExpiryMsg {
+    /// operator address to approve
+    pub operator: Address,
+    pub approved: bool,
+    /// time the approval expires at, none never expires
+    pub expires_at: Option<i64>,
+}
+
+/// This structure describes fields for NFT scoped approve for all msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x3f)]
+pub struct NFTScopedApproveForAllMsg {
+    /// operator address to approve
+    pub operator: Address,
+    /// token at the root of the scope
+    pub token_id: u128,
+    pub approved: bool,
+    /// time the approval expires at, none never expires
+    pub expires_at: Option<i64>,
+}
+
 /// This structure describes fields for NFT mint msg
 #[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
 #[rpc_msg(action = 0x09)]
@@ -62,3 +99,57 @@ pub struct NFTBurnMsg {
     /// token id to burn
     pub token_id: u128,
 }
+
+/// This structure describes fields for NFT update uri template msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x33)]
+pub struct NFTUpdateUriTemplateMsg {
+    /// new uri template
+    pub uri_template: String,
+}
+
+/// This structure describes fields for NFT set token uri msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x34)]
+pub struct NFTSetTokenUriMsg {
+    /// token id
+    pub token_id: u128,
+    /// token uri, none falls back to the uri template
+    pub token_uri: Option<String>,
+    /// freeze the token uri, it can no longer be changed afterwards
+    pub freeze: bool,
+}
+
+/// This structure describes fields for the hook called on contracts receiving an NFT
+/// through a safe transfer. The receiver acknowledges the transfer by handling the call
+/// successfully, failing the call rejects the transfer.
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x40)]
+pub struct MPC721ReceivedMsg {
+    /// address which started the transfer
+    pub operator: Address,
+    /// previous owner address
+    pub from: Address,
+    /// token id
+    pub token_id: u128,
+    /// additional data with no specified format
+    pub data: Vec<u8>,
+}
+
+/// This structure describes fields for NFT set default royalty msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x38)]
+pub struct NFTSetDefaultRoyaltyMsg {
+    /// default royalty, none disables it
+    pub royalty: Option<RoyaltyInfo>,
+}
+
+/// This structure describes fields for NFT set token royalty msg
+#[derive(ReadWriteRPC, CreateTypeSpec, IntoShortnameRPCEvent, Clone, PartialEq, Eq, Debug)]
+#[rpc_msg(action = 0x39)]
+pub struct NFTSetTokenRoyaltyMsg {
+    /// token id
+    pub token_id: u128,
+    /// token royalty, none falls back to the default royalty
+    pub royalty: Option<RoyaltyInfo>,
+}
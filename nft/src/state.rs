@@ -1,10 +1,25 @@
 use core::default::Default;
+use std::collections::BTreeMap;
+
 use create_type_spec_derive::CreateTypeSpec;
 use pbc_contract_common::{address::Address, avl_tree_map::AvlTreeMap};
+use read_write_rpc_derive::ReadWriteRPC;
 use read_write_state_derive::ReadWriteState;
 
 use crate::ContractError;
 
+/// Maximum length of a token URI or of the URI template
+pub const MAX_URI_LENGTH: usize = 256;
+
+/// Placeholder of the URI template replaced by the token id
+pub const URI_TEMPLATE_TOKEN_ID: &str = "{id}";
+
+/// Placeholder of the URI template replaced by the token name
+pub const URI_TEMPLATE_NAME: &str = "{name}";
+
+/// Basis points of the whole sale price
+pub const ROYALTY_BASIS_POINTS_DENOMINATOR: u16 = 10_000;
+
 /// This structure describes main NFT contract state.
 /// A permission to transfer and approve NFTs given from an NFT owner to a separate address, called an operator.
 #[repr(C)]
@@ -16,6 +31,40 @@ pub struct OperatorApproval {
     pub operator: Address,
 }
 
+/// A permission given from an NFT owner to an operator, covering a token and its child tokens.
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Debug)]
+pub struct ScopedOperatorApproval {
+    /// NFT owner.
+    pub owner: Address,
+    /// Operator of the owner's tokens in the scope.
+    pub operator: Address,
+    /// Token at the root of the scope.
+    pub token_id: u128,
+}
+
+/// Position of a token in the list of tokens owned by an address.
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, PartialEq, Copy, Clone, Ord, PartialOrd, Eq, Debug)]
+pub struct OwnerTokenIndex {
+    /// NFT owner.
+    pub owner: Address,
+    /// Index in the owner's list of tokens.
+    pub index: u128,
+}
+
+/// Royalty paid to a receiver on every sale of an NFT.
+#[repr(C)]
+#[derive(
+    ReadWriteRPC, ReadWriteState, CreateTypeSpec, PartialEq, Eq, Copy, Clone, Default, Debug,
+)]
+pub struct RoyaltyInfo {
+    /// Receiver of the royalty.
+    pub receiver: Address,
+    /// Share of the sale price, in basis points.
+    pub basis_points: u16,
+}
+
 /// Unit
 #[repr(C)]
 #[derive(CreateTypeSpec, ReadWriteState, Debug)]
@@ -40,10 +89,34 @@ pub struct NFTContractState {
     pub uri_template: String,
     /// Mapping from token_id to the URI of the token.
     pub token_uri_details: AvlTreeMap<u128, String>,
-    /// Owner of the contract. Is allowed to mint new NFTs.
+    /// Owner of the contract. Is allowed to mint new NFTs and to operate on every NFT.
     pub contract_owner: Option<Address>,
     /// Total supply of the NFTs.
     pub supply: u128,
+    /// Identifier of the next minted token, past every token id minted so far.
+    pub next_token_id: u128,
+    /// Mapping from owner and index to the token_id owned.
+    pub owned_tokens: AvlTreeMap<OwnerTokenIndex, u128>,
+    /// Mapping from token_id to its index in the owner's list of tokens.
+    pub owned_tokens_index: AvlTreeMap<u128, u128>,
+    /// Mapping from index to token_id, over all the tokens.
+    pub all_tokens: AvlTreeMap<u128, u128>,
+    /// Mapping from token_id to its index in the list of all tokens.
+    pub all_tokens_index: AvlTreeMap<u128, u128>,
+    /// Tokens whose URI can no longer be changed.
+    pub frozen_token_uris: AvlTreeMap<u128, Unit>,
+    /// Mapping from token_id to the expiry of its approved address.
+    pub token_approval_expirations: AvlTreeMap<u128, i64>,
+    /// Mapping from operator approvals to their expiry.
+    pub operator_approval_expirations: AvlTreeMap<OperatorApproval, i64>,
+    /// Containing operators approved on a token and its child tokens, with their optional expiry.
+    pub scoped_operator_approvals: AvlTreeMap<ScopedOperatorApproval, Option<i64>>,
+    /// Mapping from token_id to the token it is a child of.
+    pub parent_tokens: AvlTreeMap<u128, u128>,
+    /// Royalty of the tokens without a royalty of their own.
+    pub default_royalty: Option<RoyaltyInfo>,
+    /// Mapping from token_id to the royalty overriding the default one.
+    pub token_royalties: AvlTreeMap<u128, RoyaltyInfo>,
 }
 
 impl NFTContractState {
@@ -77,7 +150,25 @@ impl NFTContractState {
         self.token_approvals.get(&token_id)
     }
 
-    /// Query if an address is an authorized operator for another address.
+    /// Query if an address is the approved address for an NFT at a given time.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `spender`: [`Address`] The address to check the approval for.
+    ///
+    /// * `token_id`: [`u128`] The identifier for an NFT.
+    ///
+    /// * `block_time`: [`i64`] The time the approval is checked at.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`bool`] true if `spender` is the approved address and the approval has not expired.
+    pub fn is_approved_at(&self, spender: Address, token_id: u128, block_time: i64) -> bool {
+        self.get_approved(token_id) == Some(spender)
+            && is_grant_active(self.token_approval_expirations.get(&token_id), block_time)
+    }
+
+    /// Query if an address is an authorized operator for another address at a given time.
     ///
     /// ### Parameters:
     ///
@@ -85,12 +176,206 @@ impl NFTContractState {
     ///
     /// * `operator`: [`Address`] The address that acts on behalf of the owner.
     ///
+    /// * `block_time`: [`i64`] The time the approval is checked at.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`bool`] true if `operator` is an approved operator for `owner` and the approval has not expired.
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address, block_time: i64) -> bool {
+        let operator_approval = OperatorApproval { owner, operator };
+
+        self.operator_approvals.contains_key(&operator_approval)
+            && is_grant_active(
+                self.operator_approval_expirations.get(&operator_approval),
+                block_time,
+            )
+    }
+
+    /// Query if an address is an authorized operator for a token, through a scoped approval
+    /// given on the token or any of its parent tokens, at a given time.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `owner`: [`Address`] The address that owns the NFT.
+    ///
+    /// * `operator`: [`Address`] The address that acts on behalf of the owner.
+    ///
+    /// * `token_id`: [`u128`] The identifier for an NFT.
+    ///
+    /// * `block_time`: [`i64`] The time the approval is checked at.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`bool`] true if a scoped approval of `owner` covers `token_id` and has not expired.
+    pub fn is_scoped_operator_at(
+        &self,
+        owner: Address,
+        operator: Address,
+        token_id: u128,
+        block_time: i64,
+    ) -> bool {
+        let mut scope_token_id = Some(token_id);
+        while let Some(current_token_id) = scope_token_id {
+            let scoped_approval = ScopedOperatorApproval {
+                owner,
+                operator,
+                token_id: current_token_id,
+            };
+            if let Some(expires_at) = self.scoped_operator_approvals.get(&scoped_approval) {
+                if is_grant_active(expires_at, block_time) {
+                    return true;
+                }
+            }
+
+            scope_token_id = self.parent_tokens.get(&current_token_id);
+        }
+
+        false
+    }
+
+    /// Count the NFTs owned by an address.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `owner`: [`Address`] The address to count the NFTs for.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`u128`] the number of NFTs owned by `owner`.
+    pub fn balance_of(&self, owner: Address) -> u128 {
+        self.owners_balance.get(&owner).unwrap_or(0)
+    }
+
+    /// Get a token owned by an address at a given index of its list of tokens.
+    /// Throws if `index` is out of bounds.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `owner`: [`Address`] The address that owns the NFTs.
+    ///
+    /// * `index`: [`u128`] A counter less than `balance_of(owner)`.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`u128`] the token identifier at `index` of the tokens owned by `owner`.
+    pub fn token_of_owner_by_index(&self, owner: Address, index: u128) -> u128 {
+        let token_id = self.owned_tokens.get(&OwnerTokenIndex { owner, index });
+        assert!(token_id.is_some(), "{}", ContractError::IndexOutOfBounds);
+
+        token_id.unwrap()
+    }
+
+    /// Get a token at a given index of all the tokens.
+    /// Throws if `index` is out of bounds.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `index`: [`u128`] A counter less than `supply`.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`u128`] the token identifier at `index`.
+    pub fn token_by_index(&self, index: u128) -> u128 {
+        let token_id = self.all_tokens.get(&index);
+        assert!(token_id.is_some(), "{}", ContractError::IndexOutOfBounds);
+
+        token_id.unwrap()
+    }
+
+    /// Get a page of the tokens owned by an address.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `owner`: [`Address`] The address that owns the NFTs.
+    ///
+    /// * `offset`: [`u128`] The index of the first token of the page.
+    ///
+    /// * `limit`: [`u128`] The maximum number of tokens in the page.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`Vec<u128>`] of the token identifiers owned by `owner`, empty past the last page.
+    pub fn tokens_of_owner(&self, owner: Address, offset: u128, limit: u128) -> Vec<u128> {
+        let end = self.balance_of(owner).min(offset.saturating_add(limit));
+
+        (offset..end)
+            .map(|index| self.token_of_owner_by_index(owner, index))
+            .collect()
+    }
+
+    /// Get the URI of a token.
+    /// The URI set for the token takes precedence over the URI template.
+    /// The template placeholders `{id}` and `{name}` are replaced by the token id and name,
+    /// a template without any placeholder is used as a base URI followed by the token id.
+    /// Throws if no such token exists.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `token_id`: [`u128`] The identifier for an NFT.
+    ///
+    /// * `name`: [`&str`] The name of the NFT.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`String`] the URI of the NFT.
+    pub fn token_uri(&self, token_id: u128, name: &str) -> String {
+        assert!(self.exists(token_id), "{}", ContractError::NotFound);
+
+        if let Some(token_uri) = self.token_uri_details.get(&token_id) {
+            return token_uri;
+        }
+
+        let template = &self.uri_template;
+        if template.contains(URI_TEMPLATE_TOKEN_ID) || template.contains(URI_TEMPLATE_NAME) {
+            template
+                .replace(URI_TEMPLATE_TOKEN_ID, &token_id.to_string())
+                .replace(URI_TEMPLATE_NAME, name)
+        } else {
+            format!("{}{}", template, token_id)
+        }
+    }
+
+    /// Query if the URI of a token is frozen.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `token_id`: [`u128`] The identifier for an NFT.
+    ///
+    /// ### Returns:
+    ///
+    /// A [`bool`] true if the URI of `token_id` can no longer be changed, false otherwise.
+    pub fn is_token_uri_frozen(&self, token_id: u128) -> bool {
+        self.frozen_token_uris.contains_key(&token_id)
+    }
+
+    /// Get the royalty to pay for a sale of an NFT.
+    /// The royalty set for the token takes precedence over the default royalty.
+    /// Throws if no such token exists.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `token_id`: [`u128`] The identifier for an NFT.
+    ///
+    /// * `sale_price`: [`u128`] The sale price of the NFT.
+    ///
     /// ### Returns:
     ///
-    /// A [`bool`] true if `operator` is an approved operator for `owner`, false otherwise.
-    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
-        let as_operator_approval: OperatorApproval = OperatorApproval { owner, operator };
-        self.operator_approvals.contains_key(&as_operator_approval)
+    /// An [`Option<(Address, u128)>`] The royalty receiver and amount, or none if there is no royalty.
+    pub fn royalty_info(&self, token_id: u128, sale_price: u128) -> Option<(Address, u128)> {
+        assert!(self.exists(token_id), "{}", ContractError::NotFound);
+
+        let royalty = self
+            .token_royalties
+            .get(&token_id)
+            .or(self.default_royalty)?;
+
+        let denominator = ROYALTY_BASIS_POINTS_DENOMINATOR as u128;
+        let basis_points = royalty.basis_points as u128;
+        // Split the price to avoid overflowing on large amounts
+        let amount = sale_price / denominator * basis_points
+            + sale_price % denominator * basis_points / denominator;
+
+        Some((royalty.receiver, amount))
     }
 
     /// Helper function to check whether a tokenId exists.
@@ -110,6 +395,7 @@ impl NFTContractState {
     }
 
     /// Helper function to check whether a spender is owner or approved for a given token.
+    /// Expired approvals are ignored.
     /// Throws if token_id does not exist.
     ///
     /// ### Parameters:
@@ -118,17 +404,19 @@ impl NFTContractState {
     ///
     /// * `token_id`: [`u128`] The tokenId which is checked.
     ///
+    /// * `block_time`: [`i64`] The time the approvals are checked at.
+    ///
     /// ### Returns:
     ///
     /// A [`bool`] True if `token_id` is owned or approved for `spender`, false otherwise.
-    pub fn is_approved_or_owner(&self, spender: Address, token_id: u128) -> bool {
-        let contract_owner = self.contract_owner.unwrap();
+    pub fn is_approved_or_owner(&self, spender: Address, token_id: u128, block_time: i64) -> bool {
         let owner = self.owner_of(token_id);
 
         spender == owner
-            || spender == contract_owner
-            || self.is_approved_for_all(owner, spender)
-            || self.get_approved(token_id) == Some(spender)
+            || self.contract_owner == Some(spender)
+            || self.is_approved_for_all(owner, spender, block_time)
+            || self.is_scoped_operator_at(owner, spender, token_id, block_time)
+            || self.is_approved_at(spender, token_id, block_time)
     }
 
     /// Increase the supply of the token by 1
@@ -141,9 +429,9 @@ impl NFTContractState {
         self.supply -= 1;
     }
 
-    /// Get the next token id
+    /// Get the next token id, past every token id minted so far
     pub fn get_next_token_id(&self) -> u128 {
-        self.supply
+        self.next_token_id
     }
 
     /// Mutates the state by approving `to` to operate on `token_id`.
@@ -154,12 +442,104 @@ impl NFTContractState {
     /// * `approved`: [`Option<Address>`], The new approved NFT controller.
     ///
     /// * `token_id`: [`u128`], The NFT to approve.
-    pub fn _approve(&mut self, approved: Option<Address>, token_id: u128) {
+    ///
+    /// * `expires_at`: [`Option<i64>`], The time the approval expires at, none never expires.
+    pub fn _approve(&mut self, approved: Option<Address>, token_id: u128, expires_at: Option<i64>) {
         if let Some(appr) = approved {
             self.token_approvals.insert(token_id, appr);
         } else {
             self.token_approvals.remove(&token_id);
         }
+
+        match (approved, expires_at) {
+            (Some(_), Some(expires_at)) => {
+                self.token_approval_expirations.insert(token_id, expires_at)
+            }
+            _ => self.token_approval_expirations.remove(&token_id),
+        }
+    }
+
+    /// Mutates the state by making `parent_token_id` the parent of `token_id`,
+    /// so that scoped approvals on the parent cover the child.
+    pub fn _set_parent_token(&mut self, token_id: u128, parent_token_id: u128) {
+        self.parent_tokens.insert(token_id, parent_token_id);
+    }
+
+    /// Adds a token to the list of tokens of `to`.
+    /// Must be called before increasing the owner balance.
+    pub fn _add_token_to_owner_enumeration(&mut self, to: Address, token_id: u128) {
+        let index = self.balance_of(to);
+        self.owned_tokens
+            .insert(OwnerTokenIndex { owner: to, index }, token_id);
+        self.owned_tokens_index.insert(token_id, index);
+    }
+
+    /// Removes a token from the list of tokens of `from`,
+    /// by moving the last token of the list in its place.
+    /// Must be called before decreasing the owner balance.
+    pub fn _remove_token_from_owner_enumeration(&mut self, from: Address, token_id: u128) {
+        let last_index = self.balance_of(from) - 1;
+        let index = self.owned_tokens_index.get(&token_id).unwrap();
+
+        if index != last_index {
+            let last_token_id = self.token_of_owner_by_index(from, last_index);
+            self.owned_tokens
+                .insert(OwnerTokenIndex { owner: from, index }, last_token_id);
+            self.owned_tokens_index.insert(last_token_id, index);
+        }
+
+        self.owned_tokens.remove(&OwnerTokenIndex {
+            owner: from,
+            index: last_index,
+        });
+        self.owned_tokens_index.remove(&token_id);
+    }
+
+    /// Adds a token to the list of all tokens.
+    /// Must be called before increasing the supply.
+    pub fn _add_token_to_all_tokens_enumeration(&mut self, token_id: u128) {
+        let index = self.supply;
+        self.all_tokens.insert(index, token_id);
+        self.all_tokens_index.insert(token_id, index);
+    }
+
+    /// Removes a token from the list of all tokens,
+    /// by moving the last token of the list in its place.
+    /// Must be called before decreasing the supply.
+    pub fn _remove_token_from_all_tokens_enumeration(&mut self, token_id: u128) {
+        let last_index = self.supply - 1;
+        let index = self.all_tokens_index.get(&token_id).unwrap();
+
+        if index != last_index {
+            let last_token_id = self.token_by_index(last_index);
+            self.all_tokens.insert(index, last_token_id);
+            self.all_tokens_index.insert(last_token_id, index);
+        }
+
+        self.all_tokens.remove(&last_index);
+        self.all_tokens_index.remove(&token_id);
+    }
+
+    /// Builds the token enumerations from the owners mapping.
+    /// Expects the enumerations to be empty.
+    pub fn _rebuild_enumerations(&mut self) {
+        let mut owner_indexes: BTreeMap<Address, u128> = BTreeMap::new();
+
+        for (all_index, (token_id, owner)) in self.owners.iter().enumerate() {
+            let index = owner_indexes.entry(owner).or_insert(0);
+            self.owned_tokens.insert(
+                OwnerTokenIndex {
+                    owner,
+                    index: *index,
+                },
+                token_id,
+            );
+            self.owned_tokens_index.insert(token_id, *index);
+            *index += 1;
+
+            self.all_tokens.insert(all_index as u128, token_id);
+            self.all_tokens_index.insert(token_id, all_index as u128);
+        }
     }
 
     /// Increase owner nft balance
@@ -196,9 +576,39 @@ impl NFTContractState {
         );
 
         // clear approvals from the previous owner
-        self._approve(None, token_id);
+        self._approve(None, token_id, None);
         self.owners.insert(token_id, to);
+        self._remove_token_from_owner_enumeration(from, token_id);
         self._decrease_owner_balance(from);
+        self._add_token_to_owner_enumeration(to, token_id);
         self._increase_owner_balance(to);
     }
+
+    /// Mutates the state by destroying `token_id`.
+    /// As opposed to {burn}, this imposes no restrictions on `ctx.sender`.
+    ///
+    /// Throws if `token_id` does not exist.
+    ///
+    /// ### Parameters:
+    ///
+    /// * `token_id`: [`u128`], The NFT to burn
+    pub fn _burn(&mut self, token_id: u128) {
+        let owner = self.owner_of(token_id);
+        // Clear approvals
+        self._approve(None, token_id, None);
+
+        self.owners.remove(&token_id);
+        self._remove_token_from_owner_enumeration(owner, token_id);
+        self._decrease_owner_balance(owner);
+        self.token_uri_details.remove(&token_id);
+        self.frozen_token_uris.remove(&token_id);
+        self.token_royalties.remove(&token_id);
+        self.parent_tokens.remove(&token_id);
+        self._remove_token_from_all_tokens_enumeration(token_id);
+        self.decrease_supply();
+    }
+}
+
+fn is_grant_active(expires_at: Option<i64>, block_time: i64) -> bool {
+    expires_at.map_or(true, |expires_at| block_time < expires_at)
 }
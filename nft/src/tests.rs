@@ -1,13 +1,18 @@
-use utils::tests::{mock_address, mock_contract_context};
+use utils::tests::{mock_address, mock_contract_context, tomorrow_timestamp, yesterday_timestamp};
 
-use crate::state::OperatorApproval;
+use crate::state::{OperatorApproval, RoyaltyInfo};
 use crate::{
     actions::{
-        execute_approve, execute_burn, execute_init, execute_mint, execute_set_approval_for_all,
-        execute_transfer_from,
+        execute_approve, execute_approve_with_expiry, execute_burn, execute_init, execute_mint,
+        execute_set_approval_for_all, execute_set_approval_for_all_with_expiry,
+        execute_set_default_royalty, execute_set_scoped_approval_for_all,
+        execute_set_token_royalty, execute_set_token_uri, execute_transfer_from,
+        execute_update_uri_template,
     },
     msg::{
-        NFTApproveForAllMsg, NFTApproveMsg, NFTBurnMsg, NFTInitMsg, NFTMintMsg, NFTTransferFromMsg,
+        NFTApproveForAllMsg, NFTApproveForAllWithExpiryMsg, NFTApproveMsg, NFTApproveWithExpiryMsg,
+        NFTBurnMsg, NFTInitMsg, NFTMintMsg, NFTScopedApproveForAllMsg, NFTSetTokenUriMsg,
+        NFTTransferFromMsg, NFTUpdateUriTemplateMsg,
     },
 };
 
@@ -32,6 +37,7 @@ fn proper_mint() {
 
     let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
     assert_eq!(state.supply, 1);
+    assert_eq!(state.get_next_token_id(), 2);
 
     assert_eq!(state.owners.get(&1), Some(mock_address(alice)));
 
@@ -445,6 +451,7 @@ fn proper_burn() {
 
     let _ = execute_burn(&mock_contract_context(alice), &mut state, &burn_msg);
     assert_eq!(state.supply, 0);
+    assert_eq!(state.get_next_token_id(), 2);
     assert!(!state.exists(1));
     assert_eq!(state.owners_balance.get(&mock_address(alice)), Some(0));
 }
@@ -466,3 +473,584 @@ fn burn_not_minted_token() {
     let burn_msg = NFTBurnMsg { token_id: 1 };
     let _ = execute_burn(&mock_contract_context(alice), &mut state, &burn_msg);
 }
+
+#[test]
+fn proper_enumeration_after_transfer_and_burn() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for token_id in 1..=3 {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(alice),
+            token_uri: None,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    assert_eq!(
+        state.tokens_of_owner(mock_address(alice), 0, 10),
+        vec![1, 2, 3]
+    );
+    assert_eq!(state.token_by_index(2), 3);
+
+    let transfer_msg = NFTTransferFromMsg {
+        from: mock_address(alice),
+        to: mock_address(bob),
+        token_id: 1,
+    };
+
+    let _ = execute_transfer_from(&mock_contract_context(alice), &mut state, &transfer_msg);
+    assert_eq!(
+        state.tokens_of_owner(mock_address(alice), 0, 10),
+        vec![3, 2]
+    );
+    assert_eq!(state.token_of_owner_by_index(mock_address(bob), 0), 1);
+
+    let burn_msg = NFTBurnMsg { token_id: 2 };
+
+    let _ = execute_burn(&mock_contract_context(alice), &mut state, &burn_msg);
+    assert_eq!(state.tokens_of_owner(mock_address(alice), 0, 10), vec![3]);
+    assert_eq!(state.token_by_index(0), 1);
+    assert_eq!(state.token_by_index(1), 3);
+}
+
+#[test]
+fn proper_tokens_of_owner_pagination() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for token_id in 1..=5 {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(alice),
+            token_uri: None,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    assert_eq!(state.tokens_of_owner(mock_address(alice), 1, 2), vec![2, 3]);
+    assert_eq!(state.tokens_of_owner(mock_address(alice), 4, 10), vec![5]);
+    assert_eq!(state.tokens_of_owner(mock_address(alice), 5, 10), vec![]);
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn token_of_owner_by_index_out_of_bounds() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    let _ = state.token_of_owner_by_index(mock_address(alice), 1);
+}
+
+#[test]
+fn proper_token_uri_resolution() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for (token_id, token_uri) in [(1, None), (2, Some("ipfs://token".to_string()))] {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(alice),
+            token_uri,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    assert_eq!(state.token_uri(1, "name"), "ipfs://some.some/1".to_string());
+    assert_eq!(state.token_uri(2, "name"), "ipfs://token".to_string());
+
+    let update_msg = NFTUpdateUriTemplateMsg {
+        uri_template: "https://meta.name/{name}/{id}.json".to_string(),
+    };
+
+    let _ = execute_update_uri_template(&mock_contract_context(2), &mut state, &update_msg);
+
+    assert_eq!(
+        state.token_uri(1, "name"),
+        "https://meta.name/name/1.json".to_string()
+    );
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 2,
+        token_uri: None,
+        freeze: false,
+    };
+
+    let _ = execute_set_token_uri(&mock_contract_context(alice), &mut state, &set_msg, "other");
+
+    assert_eq!(
+        state.token_uri(2, "other"),
+        "https://meta.name/other/2.json".to_string()
+    );
+}
+
+#[test]
+fn proper_token_uri_freeze() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/{id}".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 1,
+        token_uri: None,
+        freeze: true,
+    };
+
+    let _ = execute_set_token_uri(&mock_contract_context(alice), &mut state, &set_msg, "name");
+
+    let update_msg = NFTUpdateUriTemplateMsg {
+        uri_template: "https://meta.name/{id}".to_string(),
+    };
+
+    let _ = execute_update_uri_template(&mock_contract_context(2), &mut state, &update_msg);
+
+    assert!(state.is_token_uri_frozen(1));
+    assert_eq!(state.token_uri(1, "name"), "ipfs://some.some/1".to_string());
+}
+
+#[test]
+#[should_panic(expected = "Token metadata is frozen")]
+fn set_token_uri_fails_when_frozen() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 1,
+        token_uri: Some("ipfs://token".to_string()),
+        freeze: true,
+    };
+
+    let _ = execute_set_token_uri(&mock_contract_context(alice), &mut state, &set_msg, "name");
+    let _ = execute_set_token_uri(&mock_contract_context(alice), &mut state, &set_msg, "name");
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn set_token_uri_fails_when_not_owner() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 1,
+        token_uri: Some("ipfs://token".to_string()),
+        freeze: false,
+    };
+
+    let _ = execute_set_token_uri(&mock_contract_context(bob), &mut state, &set_msg, "name");
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn set_token_uri_fails_for_the_contract_owner() {
+    let minter = 1u8;
+    let contract_owner = 2u8;
+    let alice = 10u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(contract_owner), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 1,
+        token_uri: None,
+        freeze: true,
+    };
+
+    let _ = execute_set_token_uri(
+        &mock_contract_context(contract_owner),
+        &mut state,
+        &set_msg,
+        "name",
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn set_token_uri_fails_for_the_approved_address() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some/".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let approve_msg = NFTApproveMsg {
+        approved: Some(mock_address(bob)),
+        token_id: 1,
+    };
+
+    let _ = execute_approve(&mock_contract_context(alice), &mut state, &approve_msg);
+
+    let set_msg = NFTSetTokenUriMsg {
+        token_id: 1,
+        token_uri: None,
+        freeze: true,
+    };
+
+    let _ = execute_set_token_uri(&mock_contract_context(bob), &mut state, &set_msg, "name");
+}
+
+#[test]
+fn proper_royalty_info() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let registry = 12u8;
+    let creator = 13u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for token_id in [1, 2] {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(alice),
+            token_uri: None,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    assert_eq!(state.royalty_info(1, 1000), None);
+
+    let default_msg = NFTSetDefaultRoyaltyMsg {
+        royalty: Some(RoyaltyInfo {
+            receiver: mock_address(registry),
+            basis_points: 250,
+        }),
+    };
+
+    let _ = execute_set_default_royalty(&mock_contract_context(2), &mut state, &default_msg);
+
+    let token_msg = NFTSetTokenRoyaltyMsg {
+        token_id: 2,
+        royalty: Some(RoyaltyInfo {
+            receiver: mock_address(creator),
+            basis_points: 1000,
+        }),
+    };
+
+    let _ = execute_set_token_royalty(&mock_contract_context(2), &mut state, &token_msg);
+
+    assert_eq!(
+        state.royalty_info(1, 1000),
+        Some((mock_address(registry), 25))
+    );
+    assert_eq!(
+        state.royalty_info(2, 1000),
+        Some((mock_address(creator), 100))
+    );
+    assert_eq!(
+        state.royalty_info(1, u128::MAX),
+        Some((mock_address(registry), u128::MAX / 10_000 * 250 + 36))
+    );
+
+    let token_msg = NFTSetTokenRoyaltyMsg {
+        token_id: 2,
+        royalty: None,
+    };
+
+    let _ = execute_set_token_royalty(&mock_contract_context(2), &mut state, &token_msg);
+
+    assert_eq!(
+        state.royalty_info(2, 1000),
+        Some((mock_address(registry), 25))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Royalty basis points exceed the sale price")]
+fn set_default_royalty_fails_when_basis_points_exceed_the_price() {
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let default_msg = NFTSetDefaultRoyaltyMsg {
+        royalty: Some(RoyaltyInfo {
+            receiver: mock_address(12),
+            basis_points: 10_001,
+        }),
+    };
+
+    let _ = execute_set_default_royalty(&mock_contract_context(2), &mut state, &default_msg);
+}
+
+#[test]
+fn proper_expiring_approvals() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+    let jack = 12u8;
+    let now = mock_contract_context(alice).block_production_time;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for token_id in [1, 2] {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(alice),
+            token_uri: None,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    let approve_msg = NFTApproveWithExpiryMsg {
+        approved: Some(mock_address(jack)),
+        token_id: 1,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+
+    let _ = execute_approve_with_expiry(&mock_contract_context(alice), &mut state, &approve_msg);
+
+    let approve_for_all_msg = NFTApproveForAllWithExpiryMsg {
+        operator: mock_address(bob),
+        approved: true,
+        expires_at: Some(yesterday_timestamp()),
+    };
+
+    let _ = execute_set_approval_for_all_with_expiry(
+        &mock_contract_context(alice),
+        &mut state,
+        &approve_for_all_msg,
+    );
+
+    assert!(state.is_approved_or_owner(mock_address(jack), 1, now));
+    assert!(!state.is_approved_or_owner(mock_address(jack), 1, tomorrow_timestamp() + 1));
+    assert!(!state.is_approved_or_owner(mock_address(bob), 2, now));
+    assert!(!state.is_approved_for_all(mock_address(alice), mock_address(bob), now));
+
+    // Reapproving without an expiry clears the previous one
+    let approve_msg = NFTApproveMsg {
+        approved: Some(mock_address(jack)),
+        token_id: 1,
+    };
+
+    let _ = execute_approve(&mock_contract_context(alice), &mut state, &approve_msg);
+
+    assert!(state.is_approved_or_owner(mock_address(jack), 1, tomorrow_timestamp() + 1));
+}
+
+#[test]
+fn proper_scoped_approval() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+    let jack = 12u8;
+    let now = mock_contract_context(alice).block_production_time;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    for (token_id, owner) in [(1, alice), (2, alice), (3, alice), (4, bob)] {
+        let mint_msg = NFTMintMsg {
+            token_id,
+            to: mock_address(owner),
+            token_uri: None,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    // 1 -> 2 -> 3 and 1 -> 4, while 4 is owned by bob
+    state._set_parent_token(2, 1);
+    state._set_parent_token(3, 2);
+    state._set_parent_token(4, 1);
+
+    let scoped_msg = NFTScopedApproveForAllMsg {
+        operator: mock_address(jack),
+        token_id: 2,
+        approved: true,
+        expires_at: None,
+    };
+
+    let _ =
+        execute_set_scoped_approval_for_all(&mock_contract_context(alice), &mut state, &scoped_msg);
+
+    assert!(!state.is_approved_or_owner(mock_address(jack), 1, now));
+    assert!(state.is_approved_or_owner(mock_address(jack), 2, now));
+    assert!(state.is_approved_or_owner(mock_address(jack), 3, now));
+    assert!(!state.is_approved_or_owner(mock_address(jack), 4, now));
+
+    let scoped_msg = NFTScopedApproveForAllMsg {
+        operator: mock_address(jack),
+        token_id: 2,
+        approved: false,
+        expires_at: None,
+    };
+
+    let _ =
+        execute_set_scoped_approval_for_all(&mock_contract_context(alice), &mut state, &scoped_msg);
+
+    assert!(!state.is_approved_or_owner(mock_address(jack), 3, now));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn scoped_approval_fails_when_not_owner() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let msg = NFTInitMsg {
+        name: "Cool Token".to_string(),
+        symbol: "CTC".to_string(),
+        uri_template: "ipfs://some.some".to_string(),
+    };
+
+    let mut state = execute_init(&mock_contract_context(2), &msg);
+
+    let mint_msg = NFTMintMsg {
+        token_id: 1,
+        to: mock_address(alice),
+        token_uri: None,
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let scoped_msg = NFTScopedApproveForAllMsg {
+        operator: mock_address(bob),
+        token_id: 1,
+        approved: true,
+        expires_at: None,
+    };
+
+    let _ =
+        execute_set_scoped_approval_for_all(&mock_contract_context(bob), &mut state, &scoped_msg);
+}
@@ -1,18 +1,27 @@
 use contract_version_base::state::ContractVersionBase;
+use nft::state::NFTContractState;
 use pbc_contract_common::{
     avl_tree_map::AvlTreeMap, context::ContractContext, events::EventGroup,
     sorted_vec_map::SortedVecMap,
 };
 
 use crate::{
+    coin_address::{encode_coin_address, is_supported_coin_type},
     msg::{
-        PnsCustomRecordDeleteMsg, PnsCustomRecordMintMsg, PnsCustomRecordUpdateMsg,
-        PnsDomainUpdateExpirationMsg, PnsMintMsg, PnsRecordDeleteAllMsg, PnsRecordDeleteMsg,
-        PnsRecordMintMsg, PnsRecordUpdateMsg,
+        PnsAddRecordCapacityMsg, PnsAddressDeleteMsg, PnsAddressMintMsg, PnsAddressUpdateMsg,
+        PnsBurnMsg, PnsCustomRecordDeleteMsg, PnsCustomRecordMintMsg, PnsCustomRecordUpdateMsg,
+        PnsDomainSetManagerMsg, PnsDomainUpdateExpirationMsg, PnsImportMsg, PnsMintMsg,
+        PnsPruneExpiredRecordsMsg, PnsRecordDeleteAllMsg, PnsRecordDeleteMsg, PnsRecordMintMsg,
+        PnsRecordOperation, PnsRecordRetainMsg, PnsRecordRetention, PnsRecordTarget,
+        PnsRecordUpdateMsg, PnsSetAliasMsg, PnsSetRecordHistorySizeMsg, PnsSetRecordTtlMsg,
+        PnsSetRecordsMsg, PnsSetWildcardMsg, SetRecordsEvent,
     },
+    name,
     state::{
-        Domain, PartisiaNameSystemState, MAX_CUSTOM_RECORDS, MAX_DOMAIN_LEN, MAX_RECORD_DATA_LENGTH,
+        AliasTarget, Domain, PartisiaNameSystemState, RecordLimits, RecordVersion,
+        MAX_RECORD_HISTORY_SIZE,
     },
+    validation::{encode_record_data, validate_record_data},
     ContractError,
 };
 
@@ -25,10 +34,21 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn execute_init(ctx: &ContractContext) -> PartisiaNameSystemState {
     PartisiaNameSystemState {
         domains: AvlTreeMap::new(),
+        domains_by_token_id: AvlTreeMap::new(),
+        subdomains: AvlTreeMap::new(),
+        record_limits: RecordLimits::default(),
+        record_history: AvlTreeMap::new(),
         version: ContractVersionBase::new(CONTRACT_NAME, CONTRACT_VERSION),
     }
 }
 
+/// Updates the state version to the current crate version
+pub fn execute_update_version(state: &mut PartisiaNameSystemState) {
+    state
+        .version
+        .set_contract_version(CONTRACT_NAME, CONTRACT_VERSION);
+}
+
 /// Mint a new token. Can only be executed by minter account.
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
@@ -48,8 +68,8 @@ pub fn execute_mint(
         );
     }
 
-    state.domains.insert(
-        msg.domain.clone(),
+    state._insert_domain(
+        &msg.domain,
         Domain {
             token_id: msg.token_id,
             records: SortedVecMap::new(),
@@ -57,53 +77,199 @@ pub fn execute_mint(
             minted_at: ctx.block_production_time,
             expires_at: msg.expires_at,
             parent_id: msg.parent_id.clone(),
+            manager: None,
+            addresses: SortedVecMap::new(),
+            extra_custom_record_slots: 0,
+            extra_data_length: 0,
+            record_history_size: 0,
+            wildcard: false,
+            alias: None,
+            record_ttls: SortedVecMap::new(),
+            custom_record_ttls: SortedVecMap::new(),
+            address_ttls: SortedVecMap::new(),
         },
     );
 
     vec![]
 }
 
+/// Burn a domain along with its records and subdomains
+/// The tokens of the subdomains are burned with them, so that no token is left without a domain,
+/// burning the token of the domain itself is left to the caller
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_burn(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &mut NFTContractState,
+    msg: &PnsBurnMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+
+    // Deepest subdomains first, so that every parent still exists when its children are removed
+    for subdomain in state.get_descendants(&msg.domain).iter().rev() {
+        if let Some(token_id) = state.get_token_id(subdomain) {
+            if nft.exists(token_id) {
+                nft._burn(token_id);
+            }
+        }
+        state._remove_domain(subdomain);
+    }
+    state._remove_domain(&msg.domain);
+
+    vec![]
+}
+
+/// Import a domain preserving its original mint date and records
+/// The parent domain must be imported before its subdomains
+/// Throws if a record does not pass the validation of a record mint
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_import(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    msg: &PnsImportMsg,
+) -> Vec<EventGroup> {
+    assert!(!state.is_minted(&msg.domain), "{}", ContractError::Minted);
+
+    let domain = build_imported_domain(state, msg);
+    state._insert_domain(&msg.domain, domain);
+
+    vec![]
+}
+
+/// Returns true if the domain has already been imported with the data of the msg
+pub fn is_imported(state: &PartisiaNameSystemState, msg: &PnsImportMsg) -> bool {
+    state
+        .get_domain(&msg.domain)
+        .map_or(false, |domain| domain == build_imported_domain(state, msg))
+}
+
+/// Builds the domain of an import msg, validating its records against the limits
+fn build_imported_domain(state: &PartisiaNameSystemState, msg: &PnsImportMsg) -> Domain {
+    if let Some(parent_id) = &msg.parent_id {
+        assert!(state.is_minted(parent_id), "{}", ContractError::NotFound);
+    }
+
+    let mut domain = Domain {
+        token_id: msg.token_id,
+        records: SortedVecMap::new(),
+        custom_records: SortedVecMap::new(),
+        minted_at: msg.minted_at,
+        expires_at: msg.expires_at,
+        parent_id: msg.parent_id.clone(),
+        manager: None,
+        addresses: SortedVecMap::new(),
+        extra_custom_record_slots: 0,
+        extra_data_length: 0,
+        record_history_size: 0,
+        wildcard: false,
+        alias: None,
+        record_ttls: SortedVecMap::new(),
+        custom_record_ttls: SortedVecMap::new(),
+        address_ttls: SortedVecMap::new(),
+    };
+
+    for record in &msg.records {
+        validate_record_data(&record.class, &record.data);
+        let data = encode_record_data(&record.class, &record.data);
+        assert!(
+            data.len()
+                < state
+                    .record_limits
+                    .get_data_length_limit(&domain, &record.class),
+            "{}",
+            ContractError::RecordDataTooLong
+        );
+
+        domain.records.insert(record.class, data);
+    }
+
+    for record in &msg.custom_records {
+        assert!(
+            record.data.len() < state.record_limits.get_custom_data_length_limit(&domain),
+            "{}",
+            ContractError::RecordDataTooLong
+        );
+        assert!(
+            domain.custom_records.len() < state.record_limits.get_custom_record_slots(&domain),
+            "{}",
+            ContractError::MaxCustomRecords
+        );
+
+        domain
+            .custom_records
+            .insert(record.key.clone(), record.data.clone());
+    }
+
+    domain
+}
+
 /// Mint a new record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the data is not valid for the record class
+/// The content hash record is given in its text form, as in `ipfs://<cid>`
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_record_mint(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsRecordMintMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
         ContractError::DomainExpired
     );
+    validate_record_data(&msg.class, &msg.data);
+    let data = encode_record_data(&msg.class, &msg.data);
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
     assert!(
-        msg.data.clone().len() < MAX_RECORD_DATA_LENGTH,
+        data.len()
+            < state
+                .record_limits
+                .get_data_length_limit(&domain, &msg.class),
         "{}",
         ContractError::RecordDataTooLong
     );
 
-    let mut domain = state.domains.get(&msg.domain).unwrap();
-    domain.mint_record(&msg.class, &msg.data);
+    domain.mint_record(&msg.class, &data);
+    state._push_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.class,
+        record_version(ctx, Some(&data)),
+    );
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
 }
 
 /// Update a record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the data is not valid for the record class
+/// The content hash record is given in its text form, as in `ipfs://<cid>`
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_record_update(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsRecordUpdateMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
         ContractError::DomainExpired
     );
+    validate_record_data(&msg.class, &msg.data);
+    let data = encode_record_data(&msg.class, &msg.data);
 
     let mut domain = state.domains.get(&msg.domain).unwrap();
     assert!(
@@ -111,22 +277,39 @@ pub fn execute_record_update(
         "{}",
         ContractError::NotFound
     );
+    assert!(
+        data.len()
+            < state
+                .record_limits
+                .get_data_length_limit(&domain, &msg.class),
+        "{}",
+        ContractError::RecordDataTooLong
+    );
 
-    domain.update_record_data(&msg.class, &msg.data);
+    domain.update_record_data(&msg.class, &data, ctx.block_production_time);
+    state._push_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.class,
+        record_version(ctx, Some(&data)),
+    );
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
 }
 
 /// Delete a record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_record_delete(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsRecordDeleteMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
@@ -141,6 +324,12 @@ pub fn execute_record_delete(
     );
 
     domain.delete_record(&msg.class);
+    state._push_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.class,
+        record_version(ctx, None),
+    );
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
@@ -156,65 +345,108 @@ pub fn execute_record_delete_all(
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
 
     let mut domain = state.domains.get(&msg.domain).unwrap();
-    domain.records = SortedVecMap::new();
-    domain.custom_records = SortedVecMap::new();
+    domain.retain_records(&[], &[]);
+    domain.delete_addresses();
+    domain.alias = None;
+    state.domains.insert(msg.domain.clone(), domain);
+    state._prune_record_history(&msg.domain);
+
+    vec![]
+}
+
+/// Keep the records of a domain according to the retention, delete the other ones
+/// Does not require the domain to be active
+pub fn execute_record_retain(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    msg: &PnsRecordRetainMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    match &msg.retention {
+        PnsRecordRetention::WipeAll {} => {
+            domain.retain_records(&[], &[]);
+            domain.delete_addresses();
+            domain.alias = None;
+        }
+        PnsRecordRetention::KeepAll {} => {}
+        PnsRecordRetention::KeepOnly {
+            classes,
+            custom_keys,
+            coin_types,
+        } => {
+            domain.retain_records(classes, custom_keys);
+            domain.retain_addresses(coin_types);
+            domain.alias = None;
+        }
+    }
     state.domains.insert(msg.domain.clone(), domain);
+    // The previous values belong to the previous owner, whatever records are kept
+    state._prune_record_history(&msg.domain);
 
     vec![]
 }
 
 /// Mint a new custom record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_custom_record_mint(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsCustomRecordMintMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
         ContractError::DomainExpired
     );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
     assert!(
-        msg.data.len() < MAX_RECORD_DATA_LENGTH,
+        msg.data.len() < state.record_limits.get_custom_data_length_limit(&domain),
         "{}",
         ContractError::RecordDataTooLong
     );
-
-    let mut domain = state.domains.get(&msg.domain).unwrap();
     assert!(
-        domain.custom_records.len() < MAX_CUSTOM_RECORDS,
+        domain.custom_records.len() < state.record_limits.get_custom_record_slots(&domain),
         "{}",
         ContractError::MaxCustomRecords
     );
 
     domain.mint_custom_record(msg.key.as_str(), msg.data.as_slice());
+    state._push_custom_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.key,
+        record_version(ctx, Some(&msg.data)),
+    );
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
 }
 
 /// Update a custom record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_custom_record_update(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsCustomRecordUpdateMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
         ContractError::DomainExpired
     );
-    assert!(
-        msg.data.len() < MAX_RECORD_DATA_LENGTH,
-        "{}",
-        ContractError::RecordDataTooLong
-    );
 
     let mut domain = state.domains.get(&msg.domain).unwrap();
     assert!(
@@ -222,22 +454,40 @@ pub fn execute_custom_record_update(
         "{}",
         ContractError::NotFound
     );
+    assert!(
+        msg.data.len() < state.record_limits.get_custom_data_length_limit(&domain),
+        "{}",
+        ContractError::RecordDataTooLong
+    );
 
-    domain.update_custom_record_data(msg.key.as_str(), msg.data.as_slice());
+    domain.update_custom_record_data(
+        msg.key.as_str(),
+        msg.data.as_slice(),
+        ctx.block_production_time,
+    );
+    state._push_custom_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.key,
+        record_version(ctx, Some(&msg.data)),
+    );
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
 }
 
 /// Delete a custom record for a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
 /// Returns [`Vec<EventGroup>`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn execute_custom_record_delete(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
     msg: &PnsCustomRecordDeleteMsg,
 ) -> Vec<EventGroup> {
     assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
     assert!(
         state.is_active(&msg.domain, ctx.block_production_time),
         "{}",
@@ -252,13 +502,497 @@ pub fn execute_custom_record_delete(
     );
 
     domain.delete_custom_record(&msg.key);
+    state._push_custom_record_version(
+        &msg.domain,
+        domain.record_history_size,
+        &msg.key,
+        record_version(ctx, None),
+    );
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Mint a new wallet address for a domain, for the given SLIP-44 coin type
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the coin type is not supported or the address is not valid for it
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_address_mint(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsAddressMintMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+    let address = encode_address(msg.coin_type, &msg.address);
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.mint_address(msg.coin_type, &address);
+    state._push_address_version(
+        &msg.domain,
+        domain.record_history_size,
+        msg.coin_type,
+        record_version(ctx, Some(&address)),
+    );
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Update the wallet address of a domain for the given SLIP-44 coin type
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the address is not valid for the coin type
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_address_update(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsAddressUpdateMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+    let address = encode_address(msg.coin_type, &msg.address);
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    assert!(
+        domain.is_address_minted(msg.coin_type),
+        "{}",
+        ContractError::NotFound
+    );
+
+    domain.update_address(msg.coin_type, &address, ctx.block_production_time);
+    state._push_address_version(
+        &msg.domain,
+        domain.record_history_size,
+        msg.coin_type,
+        record_version(ctx, Some(&address)),
+    );
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Delete the wallet address of a domain for the given SLIP-44 coin type
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_address_delete(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsAddressDeleteMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    assert!(
+        domain.is_address_minted(msg.coin_type),
+        "{}",
+        ContractError::NotFound
+    );
+
+    domain.delete_address(msg.coin_type);
+    state._push_address_version(
+        &msg.domain,
+        domain.record_history_size,
+        msg.coin_type,
+        record_version(ctx, None),
+    );
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Encodes the text form of an address into its binary form for the coin type
+/// Returns [`Vec<u8>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+fn encode_address(coin_type: u32, address: &str) -> Vec<u8> {
+    assert!(
+        is_supported_coin_type(coin_type),
+        "{}",
+        ContractError::UnsupportedCoinType
+    );
+
+    match encode_coin_address(coin_type, address) {
+        Some(encoded) => encoded,
+        None => panic!("{}", ContractError::InvalidCoinAddress),
+    }
+}
+
+/// Apply a list of record and custom record operations to a domain, in order.
+/// The domain is read and written once, so either every operation is applied or none.
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if an upserted record is not valid, or a deleted record does not exist
+/// Returns [`Vec<EventGroup>`] with a [`SetRecordsEvent`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_set_records(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsSetRecordsMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    let mut summary = SetRecordsEvent {
+        domain: msg.domain.clone(),
+        upserted_records: vec![],
+        deleted_records: vec![],
+        upserted_custom_records: vec![],
+        deleted_custom_records: vec![],
+    };
+
+    for operation in &msg.operations {
+        match operation {
+            PnsRecordOperation::Upsert { class, data } => {
+                validate_record_data(class, data);
+                let data = encode_record_data(class, data);
+                assert!(
+                    data.len() < state.record_limits.get_data_length_limit(&domain, class),
+                    "{}",
+                    ContractError::RecordDataTooLong
+                );
+
+                domain.upsert_record(class, &data, ctx.block_production_time);
+                state._push_record_version(
+                    &msg.domain,
+                    domain.record_history_size,
+                    class,
+                    record_version(ctx, Some(&data)),
+                );
+                summary.upserted_records.push(*class);
+            }
+            PnsRecordOperation::Delete { class } => {
+                assert!(
+                    domain.is_record_minted(class),
+                    "{}",
+                    ContractError::NotFound
+                );
+
+                domain.delete_record(class);
+                state._push_record_version(
+                    &msg.domain,
+                    domain.record_history_size,
+                    class,
+                    record_version(ctx, None),
+                );
+                summary.deleted_records.push(*class);
+            }
+            PnsRecordOperation::UpsertCustom { key, data } => {
+                assert!(
+                    data.len() < state.record_limits.get_custom_data_length_limit(&domain),
+                    "{}",
+                    ContractError::RecordDataTooLong
+                );
+                assert!(
+                    domain.is_custom_record_minted(key)
+                        || domain.custom_records.len()
+                            < state.record_limits.get_custom_record_slots(&domain),
+                    "{}",
+                    ContractError::MaxCustomRecords
+                );
+
+                domain.upsert_custom_record(key, data, ctx.block_production_time);
+                state._push_custom_record_version(
+                    &msg.domain,
+                    domain.record_history_size,
+                    key,
+                    record_version(ctx, Some(data)),
+                );
+                summary.upserted_custom_records.push(key.clone());
+            }
+            PnsRecordOperation::DeleteCustom { key } => {
+                assert!(
+                    domain.is_custom_record_minted(key),
+                    "{}",
+                    ContractError::NotFound
+                );
+
+                domain.delete_custom_record(key);
+                state._push_custom_record_version(
+                    &msg.domain,
+                    domain.record_history_size,
+                    key,
+                    record_version(ctx, None),
+                );
+                summary.deleted_custom_records.push(key.clone());
+            }
+        }
+    }
+
+    state.domains.insert(msg.domain.clone(), domain);
+
+    let mut event_builder = EventGroup::builder();
+    event_builder.return_data(summary);
+
+    vec![event_builder.build()]
+}
+
+/// Set the number of versions kept in the history of every record of a domain
+/// Zero disables the history and removes the kept versions
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_set_record_history_size(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsSetRecordHistorySizeMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+    assert!(
+        msg.size <= MAX_RECORD_HISTORY_SIZE,
+        "{}",
+        ContractError::InvalidRecordHistorySize
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.record_history_size = msg.size;
+    state.domains.insert(msg.domain.clone(), domain);
+    state._trim_record_history(&msg.domain, msg.size);
+
+    vec![]
+}
+
+/// Mark the records of a domain as resolved for its unminted subdomains, or unmark them
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_set_wildcard(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsSetWildcardMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.wildcard = msg.enabled;
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Point the resolution of a domain at a target domain, or remove the alias
+/// Ownership and expiry of both domains stay independent
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the target is the domain itself or is not minted
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_set_alias(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsSetAliasMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+    if let Some(target) = &msg.target {
+        assert!(
+            *target != msg.domain && state.is_minted(target),
+            "{}",
+            ContractError::InvalidAliasTarget
+        );
+    }
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.alias = msg.target.as_ref().map(|target| AliasTarget {
+        domain: target.clone(),
+        token_id: state.get_domain(target).unwrap().token_id,
+    });
     state.domains.insert(msg.domain.clone(), domain);
 
     vec![]
 }
 
+/// Set the caching and expiry hints of a record, custom record or address of a domain
+/// Throws unless `ctx.sender` is allowed to manage the records of the domain
+/// Throws if the targeted record does not exist or the expiry is not in the future
+/// Returns [`Vec<EventGroup>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn execute_set_record_ttl(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsSetRecordTtlMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert_can_manage_records(ctx, state, nft, &msg.domain);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+    assert!(
+        msg.ttl
+            .expires_at
+            .map_or(true, |expires_at| expires_at > ctx.block_production_time),
+        "{}",
+        ContractError::InvalidRecordExpiration
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    match &msg.target {
+        PnsRecordTarget::Record { class } => {
+            assert!(
+                domain.is_record_minted(class),
+                "{}",
+                ContractError::NotFound
+            );
+            domain.set_record_ttl(class, msg.ttl.clone());
+        }
+        PnsRecordTarget::CustomRecord { key } => {
+            assert!(
+                domain.is_custom_record_minted(key),
+                "{}",
+                ContractError::NotFound
+            );
+            domain.set_custom_record_ttl(key, msg.ttl.clone());
+        }
+        PnsRecordTarget::Address { coin_type } => {
+            assert!(
+                domain.is_address_minted(*coin_type),
+                "{}",
+                ContractError::NotFound
+            );
+            domain.set_address_ttl(*coin_type, msg.ttl.clone());
+        }
+    }
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Remove the records, custom records and addresses of a domain past their expiry
+/// Anyone can prune, the records are already hidden from resolution
+pub fn execute_prune_expired_records(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    msg: &PnsPruneExpiredRecordsMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.prune_expired_records(ctx.block_production_time);
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Add purchased record capacity to a domain, extra custom record slots
+/// and extra data length for every record of the domain
+/// The payment is handled by the contract owning the state
+pub fn execute_add_record_capacity(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    msg: &PnsAddRecordCapacityMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+    assert!(
+        state.is_active(&msg.domain, ctx.block_production_time),
+        "{}",
+        ContractError::DomainExpired
+    );
+
+    let mut domain = state.domains.get(&msg.domain).unwrap();
+    domain.extra_custom_record_slots = domain
+        .extra_custom_record_slots
+        .saturating_add(msg.extra_custom_record_slots);
+    domain.extra_data_length = domain
+        .extra_data_length
+        .saturating_add(msg.extra_data_length);
+    state.domains.insert(msg.domain.clone(), domain);
+
+    vec![]
+}
+
+/// Set or clear the manager of a domain, which is allowed to manage its records
+/// without being able to transfer the domain
+/// Throws unless `ctx.sender` is the owner of the domain
+pub fn execute_set_manager(
+    ctx: &ContractContext,
+    state: &mut PartisiaNameSystemState,
+    nft: &NFTContractState,
+    msg: &PnsDomainSetManagerMsg,
+) -> Vec<EventGroup> {
+    assert!(state.is_minted(&msg.domain), "{}", ContractError::NotFound);
+
+    let token_id = state.get_token_id(&msg.domain).unwrap();
+    assert!(
+        nft.owner_of(token_id) == ctx.sender,
+        "{}",
+        ContractError::Unauthorized
+    );
+
+    state._set_manager(&msg.domain, msg.manager);
+
+    vec![]
+}
+
+/// Asserts that `ctx.sender` is allowed to manage the records of a domain.
+/// The owner of the domain token, its approved address, the operators of the owner
+/// and the manager of the domain are allowed
+pub fn assert_can_manage_records(
+    ctx: &ContractContext,
+    state: &PartisiaNameSystemState,
+    nft: &NFTContractState,
+    domain: &str,
+) {
+    let token_id = state.get_token_id(domain).unwrap();
+
+    assert!(
+        nft.is_approved_or_owner(ctx.sender, token_id, ctx.block_production_time)
+            || state.is_manager(domain, &ctx.sender),
+        "{}",
+        ContractError::Unauthorized
+    );
+}
+
 ///## Description
 /// Update the expiration date for a domain
+/// Subdomains expiring after the new expiration date are brought back to it
 pub fn execute_update_expiration(
     ctx: &ContractContext,
     state: &mut PartisiaNameSystemState,
@@ -270,27 +1004,71 @@ pub fn execute_update_expiration(
     domain.expires_at = msg.expires_at;
     state.domains.insert(msg.domain.clone(), domain);
 
+    if let Some(expires_at) = msg.expires_at {
+        for subdomain_name in state.get_descendants(&msg.domain) {
+            let mut subdomain = state.domains.get(&subdomain_name).unwrap();
+            if subdomain.expires_at.map_or(false, |date| date > expires_at) {
+                subdomain.expires_at = Some(expires_at);
+                state.domains.insert(subdomain_name, subdomain);
+            }
+        }
+    }
+
     vec![]
 }
 
-/// Validate the domain name
+/// Validate the domain name against the label rules of [`crate::name`]
 /// Returns [`()`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn validate_domain(domain: &str) {
     assert!(
-        domain.len() <= MAX_DOMAIN_LEN,
+        name::is_valid_name(domain),
         "{}",
         ContractError::InvalidDomain
     )
 }
 
-/// Validate the domain name with parent
+/// Validate the domain name is exactly one label under its parent
 /// Returns [`()`] if operation was successful,
 /// otherwise panics with error message defined in [`ContractError`]
 pub fn validate_domain_with_parent(domain: &str, parent: &str) {
     assert!(
-        parent.len() < domain.len() && domain.starts_with(parent),
+        name::is_child_name(domain, parent),
         "{}",
         ContractError::InvalidDomainWithParent
     )
 }
+
+/// Validate the record limits configured for every domain
+/// Returns [`()`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn validate_record_limits(record_limits: &RecordLimits) {
+    assert!(
+        record_limits.is_valid(),
+        "{}",
+        ContractError::InvalidRecordLimits
+    )
+}
+
+/// Validate a domain minted without a parent is not under a minted domain,
+/// so that only the owners of a domain mint the names under it
+/// Returns [`()`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn validate_root_domain(state: &PartisiaNameSystemState, domain: &str) {
+    assert!(
+        !name::get_ancestor_names(domain)
+            .into_iter()
+            .any(|ancestor| state.is_minted(ancestor)),
+        "{}",
+        ContractError::InvalidRootDomain
+    )
+}
+
+/// Version of a record written by `ctx.sender` in the current block, none data for a deletion
+fn record_version(ctx: &ContractContext, data: Option<&[u8]>) -> RecordVersion {
+    RecordVersion {
+        data: data.map(|data| data.to_vec()),
+        written_at: ctx.block_production_time,
+        writer: ctx.sender,
+    }
+}
@@ -0,0 +1,209 @@
+//! Encoding of the multi-chain wallet addresses, keyed by their SLIP-44 coin type.
+//!
+//! Addresses are stored in their binary form, so that their length is checked per chain
+//! and a typo in the text form is caught by the checksum of the chain, if any:
+//! - Bitcoin: the output script, P2PKH and P2SH for base58 addresses, witness programs for bech32 ones
+//! - Ethereum: the 20 bytes of the account, a mixed case address must match its EIP-55 checksum
+//!   and addresses are decoded with it
+//! - Solana: the 32 bytes of the public key
+//! - Partisia: the 21 bytes of the address, including the address type
+
+use bech32::{hrp, segwit, Fe32};
+use sha3::{Digest, Keccak256};
+
+use crate::validation::is_valid_partisia_address;
+
+pub const COIN_TYPE_BITCOIN: u32 = 0;
+pub const COIN_TYPE_ETHEREUM: u32 = 60;
+pub const COIN_TYPE_SOLANA: u32 = 501;
+pub const COIN_TYPE_PARTISIA: u32 = 3757;
+
+pub const ETHEREUM_ADDRESS_LENGTH: usize = 20;
+pub const SOLANA_ADDRESS_LENGTH: usize = 32;
+pub const PARTISIA_ADDRESS_LENGTH: usize = 21;
+
+const BITCOIN_P2PKH_VERSION: u8 = 0x00;
+const BITCOIN_P2SH_VERSION: u8 = 0x05;
+
+// Bitcoin script opcodes
+const OP_0: u8 = 0x00;
+const OP_1: u8 = 0x51;
+const OP_16: u8 = 0x60;
+const OP_DUP: u8 = 0x76;
+const OP_HASH160: u8 = 0xa9;
+const OP_EQUAL: u8 = 0x87;
+const OP_EQUALVERIFY: u8 = 0x88;
+const OP_CHECKSIG: u8 = 0xac;
+const HASH160_LENGTH: u8 = 20;
+
+/// Returns true if the coin type has an encoding
+pub fn is_supported_coin_type(coin_type: u32) -> bool {
+    matches!(
+        coin_type,
+        COIN_TYPE_BITCOIN | COIN_TYPE_ETHEREUM | COIN_TYPE_SOLANA | COIN_TYPE_PARTISIA
+    )
+}
+
+/// Encodes the text form of an address into its binary form.
+/// Returns none if the coin type is not supported or the address is not valid for the chain
+pub fn encode_coin_address(coin_type: u32, address: &str) -> Option<Vec<u8>> {
+    match coin_type {
+        COIN_TYPE_BITCOIN => encode_bitcoin_address(address),
+        COIN_TYPE_ETHEREUM => encode_ethereum_address(address),
+        COIN_TYPE_SOLANA => bs58::decode(address)
+            .into_vec()
+            .ok()
+            .filter(|bytes| bytes.len() == SOLANA_ADDRESS_LENGTH),
+        COIN_TYPE_PARTISIA => is_valid_partisia_address(address)
+            .then(|| hex_decode(address))
+            .flatten(),
+        _ => None,
+    }
+}
+
+/// Decodes the binary form of an address into its text form.
+/// Returns none if the coin type is not supported or the bytes are not valid for the chain
+pub fn decode_coin_address(coin_type: u32, bytes: &[u8]) -> Option<String> {
+    match coin_type {
+        COIN_TYPE_BITCOIN => decode_bitcoin_address(bytes),
+        COIN_TYPE_ETHEREUM => {
+            (bytes.len() == ETHEREUM_ADDRESS_LENGTH).then(|| ethereum_checksum_address(bytes))
+        }
+        COIN_TYPE_SOLANA => {
+            (bytes.len() == SOLANA_ADDRESS_LENGTH).then(|| bs58::encode(bytes).into_string())
+        }
+        COIN_TYPE_PARTISIA => (bytes.len() == PARTISIA_ADDRESS_LENGTH).then(|| hex_encode(bytes)),
+        _ => None,
+    }
+}
+
+fn encode_bitcoin_address(address: &str) -> Option<Vec<u8>> {
+    if let Ok((hrp, version, program)) = segwit::decode(address) {
+        if hrp != hrp::BC {
+            return None;
+        }
+
+        let version = version.to_u8();
+        let version_op = if version == 0 {
+            OP_0
+        } else {
+            OP_1 + version - 1
+        };
+        let mut script = vec![version_op, program.len() as u8];
+        script.extend(program);
+
+        return Some(script);
+    }
+
+    let payload = bs58::decode(address).with_check(None).into_vec().ok()?;
+    let (version, hash) = payload.split_first()?;
+    if hash.len() != HASH160_LENGTH as usize {
+        return None;
+    }
+
+    match *version {
+        BITCOIN_P2PKH_VERSION => Some(
+            [
+                &[OP_DUP, OP_HASH160, HASH160_LENGTH],
+                hash,
+                &[OP_EQUALVERIFY, OP_CHECKSIG],
+            ]
+            .concat(),
+        ),
+        BITCOIN_P2SH_VERSION => Some([&[OP_HASH160, HASH160_LENGTH], hash, &[OP_EQUAL]].concat()),
+        _ => None,
+    }
+}
+
+fn decode_bitcoin_address(script: &[u8]) -> Option<String> {
+    match script {
+        [OP_DUP, OP_HASH160, HASH160_LENGTH, hash @ .., OP_EQUALVERIFY, OP_CHECKSIG]
+            if hash.len() == HASH160_LENGTH as usize =>
+        {
+            Some(
+                bs58::encode([&[BITCOIN_P2PKH_VERSION], hash].concat())
+                    .with_check()
+                    .into_string(),
+            )
+        }
+        [OP_HASH160, HASH160_LENGTH, hash @ .., OP_EQUAL]
+            if hash.len() == HASH160_LENGTH as usize =>
+        {
+            Some(
+                bs58::encode([&[BITCOIN_P2SH_VERSION], hash].concat())
+                    .with_check()
+                    .into_string(),
+            )
+        }
+        [version_op, length, program @ ..] if *length as usize == program.len() => {
+            let version = match *version_op {
+                OP_0 => 0,
+                OP_1..=OP_16 => version_op - OP_1 + 1,
+                _ => return None,
+            };
+            let version = Fe32::try_from(version).ok()?;
+
+            segwit::encode(hrp::BC, version, program).ok()
+        }
+        _ => None,
+    }
+}
+
+/// An all lowercase or all uppercase address has no checksum,
+/// a mixed case address must be the EIP-55 checksum address of its bytes
+fn encode_ethereum_address(address: &str) -> Option<Vec<u8>> {
+    let hex = address.strip_prefix("0x")?;
+    if hex.len() != ETHEREUM_ADDRESS_LENGTH * 2 {
+        return None;
+    }
+
+    let bytes = hex_decode(hex)?;
+    let is_checksummed =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if is_checksummed && ethereum_checksum_address(&bytes) != address {
+        return None;
+    }
+
+    Some(bytes)
+}
+
+/// Returns the EIP-55 text form of an address, the letters of its hex encoding are uppercased
+/// where the matching nibble of the keccak256 hash of the lowercase hex encoding is 8 or more
+fn ethereum_checksum_address(bytes: &[u8]) -> String {
+    let hex = hex_encode(bytes);
+    let hash = Keccak256::digest(hex.as_bytes());
+
+    let checksummed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => u8::from_str_radix(std::str::from_utf8(&[*high, *low]).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
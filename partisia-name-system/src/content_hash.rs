@@ -0,0 +1,302 @@
+//! Encoding of the content hash record, pointing a domain at a decentralized website.
+//!
+//! The record is stored in the compact binary form of ENSIP-7, the multicodec of the
+//! protocol followed by the identifier of the content:
+//! - `ipfs://<cid>`: CIDv0 (`Qm...`) or CIDv1 (base32, base36 or base58btc multibase),
+//!   stored as a CIDv1
+//! - `ipns://<name>`: libp2p-key CIDv1, or a base58btc peer id, stored as a CIDv1
+//! - `ar://<tx id>`: the base64url encoded 32 bytes of an Arweave transaction
+//!
+//! Content hashes are decoded to their canonical text form, base32 for IPFS, base36 for IPNS.
+
+/// Multicodec of the IPFS namespace
+pub const IPFS_NAMESPACE: u64 = 0xe3;
+/// Multicodec of the IPNS namespace
+pub const IPNS_NAMESPACE: u64 = 0xe5;
+/// Multicodec of the Arweave namespace
+pub const ARWEAVE_NAMESPACE: u64 = 0xb29910;
+
+/// Content codecs allowed for IPFS, dag-pb, raw and dag-cbor
+pub const IPFS_CODECS: [u64; 3] = [DAG_PB_CODEC, 0x55, 0x71];
+/// Content codec of the IPNS names
+pub const LIBP2P_KEY_CODEC: u64 = 0x72;
+
+pub const MAX_DIGEST_LENGTH: usize = 64;
+pub const ARWEAVE_TX_ID_LENGTH: usize = 32;
+
+const DAG_PB_CODEC: u64 = 0x70;
+const SHA2_256_CODE: u64 = 0x12;
+const SHA2_256_LENGTH: usize = 32;
+const CID_V1: u64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE36_ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes the text form of a content hash into its binary form.
+/// Returns none if the protocol is not supported or the identifier is not valid
+pub fn encode_content_hash(text: &str) -> Option<Vec<u8>> {
+    let (scheme, identifier) = text.split_once("://")?;
+
+    let (namespace, content) = match scheme {
+        "ipfs" => {
+            let cid = parse_cid(identifier)?;
+            (IPFS_NAMESPACE, cid)
+        }
+        "ipns" => {
+            // Peer ids are bare base58btc multihashes, as in `12D3Koo...` or `Qm...`
+            let cid = if identifier.starts_with('1') || identifier.starts_with('Q') {
+                let multihash = bs58::decode(identifier).into_vec().ok()?;
+                is_valid_multihash(&multihash).then(|| {
+                    [
+                        encode_varint(CID_V1),
+                        encode_varint(LIBP2P_KEY_CODEC),
+                        multihash,
+                    ]
+                    .concat()
+                })?
+            } else {
+                parse_cid(identifier)?
+            };
+            (IPNS_NAMESPACE, cid)
+        }
+        "ar" => {
+            let tx_id = base64_url_decode(identifier)?;
+            (tx_id.len() == ARWEAVE_TX_ID_LENGTH).then_some((ARWEAVE_NAMESPACE, tx_id))?
+        }
+        _ => return None,
+    };
+
+    let content_hash = [encode_varint(namespace), content].concat();
+    is_valid_content_hash(&content_hash).then_some(content_hash)
+}
+
+/// Decodes the binary form of a content hash into its canonical text form.
+/// Returns none if the bytes are not a valid content hash
+pub fn decode_content_hash(bytes: &[u8]) -> Option<String> {
+    if !is_valid_content_hash(bytes) {
+        return None;
+    }
+
+    let (namespace, content) = read_varint(bytes)?;
+    match namespace {
+        IPFS_NAMESPACE => Some(format!("ipfs://b{}", base32_encode(content))),
+        IPNS_NAMESPACE => Some(format!(
+            "ipns://k{}",
+            base_x_encode(content, BASE36_ALPHABET)
+        )),
+        ARWEAVE_NAMESPACE => Some(format!("ar://{}", base64_url_encode(content))),
+        _ => None,
+    }
+}
+
+/// Returns true if the bytes are a content hash of a supported protocol,
+/// with a CIDv1 of an allowed codec for IPFS and IPNS
+pub fn is_valid_content_hash(bytes: &[u8]) -> bool {
+    match read_varint(bytes) {
+        Some((IPFS_NAMESPACE, cid)) => {
+            read_cid(cid).map_or(false, |codec| IPFS_CODECS.contains(&codec))
+        }
+        Some((IPNS_NAMESPACE, cid)) => read_cid(cid) == Some(LIBP2P_KEY_CODEC),
+        Some((ARWEAVE_NAMESPACE, tx_id)) => tx_id.len() == ARWEAVE_TX_ID_LENGTH,
+        _ => false,
+    }
+}
+
+/// Parses the text form of a CID into the bytes of a CIDv1, upgrading a CIDv0
+fn parse_cid(text: &str) -> Option<Vec<u8>> {
+    // A CIDv0 is the base58btc sha2-256 multihash of a dag-pb node
+    if text.len() == 46 && text.starts_with("Qm") {
+        let multihash = bs58::decode(text).into_vec().ok()?;
+        return is_valid_multihash(&multihash).then(|| {
+            [
+                encode_varint(CID_V1),
+                encode_varint(DAG_PB_CODEC),
+                multihash,
+            ]
+            .concat()
+        });
+    }
+
+    // The first character is the multibase prefix
+    let cid = match text.chars().next()? {
+        'b' => base32_decode(&text[1..])?,
+        'k' => base_x_decode(&text[1..], BASE36_ALPHABET)?,
+        'z' => bs58::decode(&text[1..]).into_vec().ok()?,
+        _ => return None,
+    };
+
+    read_cid(&cid).map(|_| cid)
+}
+
+/// Reads a CIDv1 and returns its content codec
+fn read_cid(bytes: &[u8]) -> Option<u64> {
+    let (version, rest) = read_varint(bytes)?;
+    let (codec, multihash) = read_varint(rest)?;
+
+    (version == CID_V1 && is_valid_multihash(multihash)).then_some(codec)
+}
+
+/// A multihash is the code of the hash function, the length of the digest and the digest
+fn is_valid_multihash(bytes: &[u8]) -> bool {
+    let digest = read_varint(bytes)
+        .and_then(|(code, rest)| read_varint(rest).map(|(length, digest)| (code, length, digest)));
+
+    match digest {
+        Some((SHA2_256_CODE, length, digest)) => {
+            length as usize == SHA2_256_LENGTH && digest.len() == SHA2_256_LENGTH
+        }
+        Some((_, length, digest)) => {
+            length as usize == digest.len() && digest.len() <= MAX_DIGEST_LENGTH
+        }
+        None => false,
+    }
+}
+
+/// Reads an unsigned LEB128 varint, returns the value and the remaining bytes.
+/// Non minimal encodings are rejected
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return (i == 0 || *byte != 0).then_some((value, &bytes[i + 1..]));
+        }
+    }
+
+    None
+}
+
+fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+
+    bytes
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    base_2n_decode(text, BASE32_ALPHABET, 5)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    base_2n_encode(bytes, BASE32_ALPHABET, 5)
+}
+
+fn base64_url_decode(text: &str) -> Option<Vec<u8>> {
+    base_2n_decode(text, BASE64_URL_ALPHABET, 6)
+}
+
+fn base64_url_encode(bytes: &[u8]) -> String {
+    base_2n_encode(bytes, BASE64_URL_ALPHABET, 6)
+}
+
+/// Decodes unpadded RFC 4648 text, where every digit holds `bits` bits
+fn base_2n_decode(text: &str, alphabet: &[u8], bits: u32) -> Option<Vec<u8>> {
+    let values: Vec<u8> = text
+        .bytes()
+        .map(|c| alphabet.iter().position(|a| *a == c).map(|p| p as u8))
+        .collect::<Option<_>>()?;
+
+    convert_bits(&values, bits, 8, false)
+}
+
+/// Encodes the bytes into unpadded RFC 4648 text, where every digit holds `bits` bits
+fn base_2n_encode(bytes: &[u8], alphabet: &[u8], bits: u32) -> String {
+    convert_bits(bytes, 8, bits, true)
+        .unwrap_or_default()
+        .iter()
+        .map(|value| alphabet[*value as usize] as char)
+        .collect()
+}
+
+/// Decodes a number written with the digits of the alphabet, as done by base36
+fn base_x_decode(text: &str, alphabet: &[u8]) -> Option<Vec<u8>> {
+    let radix = alphabet.len() as u32;
+
+    // Big endian base 256 digits of the number
+    let mut bytes: Vec<u8> = vec![];
+    for c in text.bytes() {
+        let mut carry = alphabet.iter().position(|a| *a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += (*byte as u32) * radix;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Every leading zero digit stands for a leading zero byte
+    let zeros = text.bytes().take_while(|c| *c == alphabet[0]).count();
+    let mut decoded = vec![0; zeros];
+    decoded.extend(bytes);
+
+    Some(decoded)
+}
+
+/// Encodes the bytes as a number written with the digits of the alphabet
+fn base_x_encode(bytes: &[u8], alphabet: &[u8]) -> String {
+    let radix = alphabet.len() as u32;
+
+    // Big endian base radix digits of the number
+    let mut digits: Vec<u8> = vec![];
+    for byte in bytes {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut().rev() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % radix) as u8;
+            carry /= radix;
+        }
+        while carry > 0 {
+            digits.insert(0, (carry % radix) as u8);
+            carry /= radix;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    vec![0; zeros]
+        .into_iter()
+        .chain(digits)
+        .map(|digit| alphabet[digit as usize] as char)
+        .collect()
+}
+
+/// Regroups the bits of the values, from `from` bits to `to` bits per value
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let max_value = (1 << to) - 1;
+    let max_acc = (1 << (from + to - 1)) - 1;
+
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut converted = vec![];
+    for value in data {
+        let value = *value as u32;
+        if value >> from != 0 {
+            return None;
+        }
+
+        acc = ((acc << from) | value) & max_acc;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            converted.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            converted.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return None;
+    }
+
+    Some(converted)
+}
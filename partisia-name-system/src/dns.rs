@@ -0,0 +1,362 @@
+//! DNS resource records, for gateways serving the domains to traditional DNS.
+//!
+//! The records are stored as their RFC 1035 RDATA and given in text form:
+//! - `A`: up to [`MAX_DNS_ADDRESSES`] IPv4 addresses, comma separated as in `192.0.2.1,192.0.2.2`
+//! - `AAAA`: up to [`MAX_DNS_ADDRESSES`] IPv6 addresses, comma separated as in `2001:db8::1`
+//! - `CNAME`: a hostname, as in `target.example.com`
+//! - `TXT`: a single character string of up to 255 bytes
+//! - `MX`: a preference and an exchange hostname, as in `10 mail.example.com`
+//!
+//! The default record limits have room for the longest RDATA of every class,
+//! see [`dns_data_length_limits`].
+//!
+//! A domain is exported as resource records of the IN class, either in wire format
+//! or as zone file text. Records past their expiry are left out, and the TTL is the
+//! TTL hint of the record, [`DEFAULT_DNS_TTL`] when it has none.
+//! A domain with a CNAME record only exports the CNAME record, as RFC 1034 forbids other data.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::state::{Domain, RecordClass, RecordClassLimit};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_MX: u16 = 15;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const CLASS_IN: u16 = 1;
+
+/// TTL in seconds of the records without a TTL hint
+pub const DEFAULT_DNS_TTL: u32 = 3600;
+pub const MAX_HOSTNAME_LEN: usize = 253;
+pub const MAX_LABEL_LEN: usize = 63;
+pub const MAX_TXT_LEN: usize = 255;
+/// Number of addresses of an A or AAAA record fitting in its default data length limit
+pub const MAX_DNS_ADDRESSES: usize = 16;
+
+/// Record classes exported to DNS, in export order
+pub const DNS_RECORD_CLASSES: [RecordClass; 5] = [
+    RecordClass::DnsA {},
+    RecordClass::DnsAaaa {},
+    RecordClass::DnsCname {},
+    RecordClass::DnsMx {},
+    RecordClass::DnsTxt {},
+];
+
+const IPV4_LEN: usize = 4;
+const IPV6_LEN: usize = 16;
+/// Length of the wire format of the longest hostname, with its length bytes and root label
+const MAX_NAME_LEN: usize = MAX_HOSTNAME_LEN + 2;
+const PREFERENCE_LEN: usize = 2;
+
+/// Returns the data length limits of the DNS record classes.
+/// Record data must be shorter than its limit, so every limit is one byte
+/// over the longest RDATA of its class
+pub fn dns_data_length_limits() -> Vec<RecordClassLimit> {
+    [
+        (RecordClass::DnsA {}, MAX_DNS_ADDRESSES * IPV4_LEN),
+        (RecordClass::DnsAaaa {}, MAX_DNS_ADDRESSES * IPV6_LEN),
+        (RecordClass::DnsCname {}, MAX_NAME_LEN),
+        (RecordClass::DnsMx {}, PREFERENCE_LEN + MAX_NAME_LEN),
+        (RecordClass::DnsTxt {}, 1 + MAX_TXT_LEN),
+    ]
+    .into_iter()
+    .map(|(class, max_rdata_len)| RecordClassLimit {
+        class,
+        data_length_limit: max_rdata_len as u32 + 1,
+    })
+    .collect()
+}
+
+/// Returns the DNS type of a record class, none if the class is not a DNS record
+pub fn dns_record_type(class: &RecordClass) -> Option<u16> {
+    match class {
+        RecordClass::DnsA {} => Some(TYPE_A),
+        RecordClass::DnsAaaa {} => Some(TYPE_AAAA),
+        RecordClass::DnsCname {} => Some(TYPE_CNAME),
+        RecordClass::DnsTxt {} => Some(TYPE_TXT),
+        RecordClass::DnsMx {} => Some(TYPE_MX),
+        _ => None,
+    }
+}
+
+/// Encodes the text form of a DNS record into its RDATA.
+/// Returns none if the class is not a DNS record or the text is not valid for it
+pub fn encode_dns_record(class: &RecordClass, text: &str) -> Option<Vec<u8>> {
+    match class {
+        RecordClass::DnsA {} => text
+            .split(',')
+            .map(|ip| parse_ipv4(ip).map(|ip| ip.octets().to_vec()))
+            .collect::<Option<Vec<_>>>()
+            .map(|ips| ips.concat()),
+        RecordClass::DnsAaaa {} => text
+            .split(',')
+            .map(|ip| parse_ipv6(ip).map(|ip| ip.octets().to_vec()))
+            .collect::<Option<Vec<_>>>()
+            .map(|ips| ips.concat()),
+        RecordClass::DnsCname {} => encode_name(text),
+        RecordClass::DnsTxt {} => {
+            is_valid_txt(text).then(|| [vec![text.len() as u8], text.as_bytes().to_vec()].concat())
+        }
+        RecordClass::DnsMx {} => {
+            let (preference, exchange) = text.split_once(' ')?;
+            let preference = parse_preference(preference)?;
+            let exchange = encode_name(exchange)?;
+
+            Some([preference.to_be_bytes().to_vec(), exchange].concat())
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the RDATA of a DNS record into its text form.
+/// Returns none if the class is not a DNS record or the bytes are not valid for it
+pub fn decode_dns_record(class: &RecordClass, rdata: &[u8]) -> Option<String> {
+    let texts = split_rdata(class, rdata)?
+        .iter()
+        .map(|rdata| decode_rdata(class, rdata))
+        .collect::<Option<Vec<String>>>()?;
+
+    Some(texts.join(","))
+}
+
+/// An IPv4 address in dotted decimal form, without leading zeros
+pub fn is_valid_ipv4(text: &str) -> bool {
+    parse_ipv4(text).is_some()
+}
+
+/// An IPv6 address in any of the RFC 4291 text forms, without zone
+pub fn is_valid_ipv6(text: &str) -> bool {
+    parse_ipv6(text).is_some()
+}
+
+/// A hostname has up to 253 characters, in labels of 1 to 63 letters, digits and hyphens
+/// that do not start nor end with a hyphen. A single trailing dot is allowed
+pub fn is_valid_hostname(text: &str) -> bool {
+    let name = text.strip_suffix('.').unwrap_or(text);
+
+    !name.is_empty()
+        && name.len() <= MAX_HOSTNAME_LEN
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= MAX_LABEL_LEN
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
+/// A TXT record is a single non empty character string of up to 255 bytes,
+/// without control characters
+pub fn is_valid_txt(text: &str) -> bool {
+    !text.is_empty() && text.len() <= MAX_TXT_LEN && !text.chars().any(|c| c.is_control())
+}
+
+/// Exports the DNS records of a domain as RFC 1035 resource records in wire format,
+/// concatenated without name compression.
+/// Returns none if the owner name is not a valid hostname
+pub fn export_wire_format(owner: &str, domain: &Domain, unix_millis_now: i64) -> Option<Vec<u8>> {
+    let name = encode_name(owner)?;
+
+    let mut wire = vec![];
+    for (class, ttl, rdata) in resource_records(domain, unix_millis_now) {
+        wire.extend_from_slice(&name);
+        wire.extend_from_slice(&dns_record_type(&class)?.to_be_bytes());
+        wire.extend_from_slice(&CLASS_IN.to_be_bytes());
+        wire.extend_from_slice(&ttl.to_be_bytes());
+        wire.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        wire.extend_from_slice(&rdata);
+    }
+
+    Some(wire)
+}
+
+/// Exports the DNS records of a domain as RFC 1035 zone file text, one record per line
+/// with the owner name fully qualified.
+/// Returns none if the owner name is not a valid hostname
+pub fn export_zone_file(owner: &str, domain: &Domain, unix_millis_now: i64) -> Option<String> {
+    if !is_valid_hostname(owner) {
+        return None;
+    }
+    let owner = fully_qualified(owner);
+
+    let mut zone = String::new();
+    for (class, ttl, rdata) in resource_records(domain, unix_millis_now) {
+        let (type_name, text) = match class {
+            RecordClass::DnsA {} => ("A", decode_rdata(&class, &rdata)?),
+            RecordClass::DnsAaaa {} => ("AAAA", decode_rdata(&class, &rdata)?),
+            RecordClass::DnsCname {} => ("CNAME", format!("{}.", decode_rdata(&class, &rdata)?)),
+            RecordClass::DnsTxt {} => ("TXT", quote_txt(rdata.get(1..)?)),
+            RecordClass::DnsMx {} => ("MX", format!("{}.", decode_rdata(&class, &rdata)?)),
+            _ => return None,
+        };
+
+        zone.push_str(&format!("{} {} IN {} {}\n", owner, ttl, type_name, text));
+    }
+
+    Some(zone)
+}
+
+/// Returns the class, TTL and RDATA of every resource record of the domain,
+/// one per address for the A and AAAA records
+fn resource_records(domain: &Domain, unix_millis_now: i64) -> Vec<(RecordClass, u32, Vec<u8>)> {
+    let is_resolved = |class: &RecordClass| {
+        domain.is_record_minted(class) && !domain.is_record_expired(class, unix_millis_now)
+    };
+
+    let classes: Vec<RecordClass> = if is_resolved(&RecordClass::DnsCname {}) {
+        vec![RecordClass::DnsCname {}]
+    } else {
+        DNS_RECORD_CLASSES
+            .iter()
+            .filter(|class| is_resolved(class))
+            .copied()
+            .collect()
+    };
+
+    let mut records = vec![];
+    for class in classes {
+        let ttl = domain
+            .get_record_ttl(&class)
+            .and_then(|ttl| ttl.ttl)
+            .unwrap_or(DEFAULT_DNS_TTL);
+        let rdatas = domain
+            .get_record(&class)
+            .and_then(|data| split_rdata(&class, data))
+            .unwrap_or_default();
+
+        for rdata in rdatas {
+            records.push((class, ttl, rdata));
+        }
+    }
+
+    records
+}
+
+/// Splits the stored data of a record into the RDATA of its resource records
+fn split_rdata(class: &RecordClass, data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let address_len = match class {
+        RecordClass::DnsA {} => IPV4_LEN,
+        RecordClass::DnsAaaa {} => IPV6_LEN,
+        _ => return Some(vec![data.to_vec()]),
+    };
+
+    let chunks = data.chunks_exact(address_len);
+    (!data.is_empty() && chunks.remainder().is_empty())
+        .then(|| chunks.map(|chunk| chunk.to_vec()).collect())
+}
+
+/// Decodes the RDATA of a single resource record into its text form
+fn decode_rdata(class: &RecordClass, rdata: &[u8]) -> Option<String> {
+    match class {
+        RecordClass::DnsA {} => {
+            let octets: [u8; IPV4_LEN] = rdata.try_into().ok()?;
+            Some(Ipv4Addr::from(octets).to_string())
+        }
+        RecordClass::DnsAaaa {} => {
+            let octets: [u8; IPV6_LEN] = rdata.try_into().ok()?;
+            Some(Ipv6Addr::from(octets).to_string())
+        }
+        RecordClass::DnsCname {} => decode_name(rdata),
+        RecordClass::DnsTxt {} => {
+            let (length, text) = rdata.split_first()?;
+            let text = std::str::from_utf8(text).ok()?;
+            (*length as usize == text.len() && is_valid_txt(text)).then(|| text.to_string())
+        }
+        RecordClass::DnsMx {} => {
+            if rdata.len() < 2 {
+                return None;
+            }
+            let (preference, exchange) = rdata.split_at(2);
+            let preference = u16::from_be_bytes([preference[0], preference[1]]);
+            Some(format!("{} {}", preference, decode_name(exchange)?))
+        }
+        _ => None,
+    }
+}
+
+fn parse_ipv4(text: &str) -> Option<Ipv4Addr> {
+    text.parse().ok()
+}
+
+fn parse_ipv6(text: &str) -> Option<Ipv6Addr> {
+    text.parse().ok()
+}
+
+/// A preference is a decimal 16 bit number, without sign nor leading zeros
+fn parse_preference(text: &str) -> Option<u16> {
+    let is_canonical = !text.is_empty()
+        && text.chars().all(|c| c.is_ascii_digit())
+        && (text == "0" || !text.starts_with('0'));
+
+    if !is_canonical {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+/// Encodes a hostname into a lowercase wire format name, length prefixed labels
+/// ending with the root label
+fn encode_name(text: &str) -> Option<Vec<u8>> {
+    if !is_valid_hostname(text) {
+        return None;
+    }
+
+    let mut name = vec![];
+    for label in text.strip_suffix('.').unwrap_or(text).split('.') {
+        name.push(label.len() as u8);
+        name.extend(label.bytes().map(|byte| byte.to_ascii_lowercase()));
+    }
+    name.push(0);
+
+    Some(name)
+}
+
+/// Decodes a wire format name, without the trailing dot
+fn decode_name(bytes: &[u8]) -> Option<String> {
+    let mut labels = vec![];
+    let mut rest = bytes;
+    loop {
+        let (length, tail) = rest.split_first()?;
+        let length = *length as usize;
+        if length == 0 {
+            break;
+        }
+        if tail.len() < length {
+            return None;
+        }
+
+        labels.push(std::str::from_utf8(&tail[..length]).ok()?);
+        rest = &tail[length..];
+    }
+
+    let name = labels.join(".");
+    (rest.len() == 1 && is_valid_hostname(&name)).then_some(name)
+}
+
+fn fully_qualified(name: &str) -> String {
+    format!(
+        "{}.",
+        name.strip_suffix('.').unwrap_or(name).to_ascii_lowercase()
+    )
+}
+
+/// Quotes a character string for a zone file, escaping quotes, backslashes
+/// and the bytes outside of printable ASCII
+fn quote_txt(bytes: &[u8]) -> String {
+    let mut quoted = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'"' | b'\\' => {
+                quoted.push('\\');
+                quoted.push(*byte as char);
+            }
+            0x20..=0x7e => quoted.push(*byte as char),
+            _ => quoted.push_str(&format!("\\{:03}", byte)),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
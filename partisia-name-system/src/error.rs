@@ -27,9 +27,69 @@ pub enum ContractError {
     #[error("The specified domain is not valid")]
     InvalidDomain,
 
+    #[error("The specified domain is under a minted domain and must be minted as its subdomain")]
+    InvalidRootDomain,
+
     #[error("The record data is too long")]
     RecordDataTooLong,
 
     #[error("The specified domain is expired")]
     DomainExpired,
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("The wallet record is not a valid address")]
+    InvalidWalletRecord,
+
+    #[error("The email record is not a valid email address")]
+    InvalidEmailRecord,
+
+    #[error("The uri record is not valid or its scheme is not allowed")]
+    InvalidUriRecord,
+
+    #[error("The twitter record is not a valid handle")]
+    InvalidTwitterRecord,
+
+    #[error("The discord record is not a valid username")]
+    InvalidDiscordRecord,
+
+    #[error("The avatar record is not a valid uri nor nft reference")]
+    InvalidAvatarRecord,
+
+    #[error("The content hash record is not a valid ipfs, ipns or arweave identifier")]
+    InvalidContentHashRecord,
+
+    #[error("The A record is not a list of valid IPv4 addresses")]
+    InvalidDnsARecord,
+
+    #[error("The AAAA record is not a list of valid IPv6 addresses")]
+    InvalidDnsAaaaRecord,
+
+    #[error("The CNAME record is not a valid hostname")]
+    InvalidDnsCnameRecord,
+
+    #[error("The TXT record is empty, longer than 255 bytes or has control characters")]
+    InvalidDnsTxtRecord,
+
+    #[error("The MX record is not a preference followed by a valid hostname")]
+    InvalidDnsMxRecord,
+
+    #[error("The coin type is not supported")]
+    UnsupportedCoinType,
+
+    #[error("The address is not valid for the coin type")]
+    InvalidCoinAddress,
+
+    #[error("The record history size is over the maximum")]
+    InvalidRecordHistorySize,
+
+    #[error("The record limits must be above zero and not over their maximum")]
+    InvalidRecordLimits,
+
+    #[error("The record expiration must be in the future")]
+    InvalidRecordExpiration,
+
+    #[error("The alias target must be another minted domain")]
+    InvalidAliasTarget,
 }
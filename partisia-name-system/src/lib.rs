@@ -1,9 +1,14 @@
 #![allow(unused_variables)]
 
 pub mod actions;
+pub mod coin_address;
+pub mod content_hash;
+pub mod dns;
 mod error;
 pub mod msg;
+pub mod name;
 pub mod state;
+pub mod validation;
 
 pub use crate::error::ContractError;
 
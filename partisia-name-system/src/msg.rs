@@ -1,7 +1,8 @@
 use create_type_spec_derive::CreateTypeSpec;
+use pbc_contract_common::address::Address;
 use read_write_rpc_derive::ReadWriteRPC;
 
-use crate::state::RecordClass;
+use crate::state::{RecordClass, RecordTtl};
 
 /// This structure describes fields for PNS mint msg
 #[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
@@ -15,6 +16,44 @@ pub struct PnsMintMsg {
     pub parent_id: Option<String>,
 }
 
+/// This structure describes fields for PNS burn msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsBurnMsg {
+    pub domain: String,
+}
+
+/// This structure describes a record entry of an imported domain
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsRecordEntry {
+    /// Class type
+    pub class: RecordClass,
+    /// Data
+    pub data: Vec<u8>,
+}
+
+/// This structure describes a custom record entry of an imported domain
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsCustomRecordEntry {
+    pub key: String,
+    pub data: Vec<u8>,
+}
+
+/// This structure describes fields for PNS import msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsImportMsg {
+    pub domain: String,
+    /// NFT token id
+    pub token_id: u128,
+    /// optional parent
+    pub parent_id: Option<String>,
+    /// Unix millis timestamp
+    pub minted_at: i64,
+    /// Unix millis timestamp
+    pub expires_at: Option<i64>,
+    pub records: Vec<PnsRecordEntry>,
+    pub custom_records: Vec<PnsCustomRecordEntry>,
+}
+
 /// This structure describes fields for PNS Record Mint Msg
 #[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
 pub struct PnsRecordMintMsg {
@@ -65,6 +104,144 @@ pub struct PnsCustomRecordDeleteMsg {
     pub key: String,
 }
 
+/// This structure describes fields for the Address Mint Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsAddressMintMsg {
+    pub domain: String,
+    /// SLIP-44 coin type
+    pub coin_type: u32,
+    /// Text form of the address, as shown by the wallets of the chain
+    pub address: String,
+}
+
+/// This structure describes fields for the Address Update Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsAddressUpdateMsg {
+    pub domain: String,
+    /// SLIP-44 coin type
+    pub coin_type: u32,
+    /// Text form of the address, as shown by the wallets of the chain
+    pub address: String,
+}
+
+/// This structure describes fields for the Address Delete Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsAddressDeleteMsg {
+    pub domain: String,
+    /// SLIP-44 coin type
+    pub coin_type: u32,
+}
+
+/// This structure describes fields for the Add Record Capacity Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsAddRecordCapacityMsg {
+    pub domain: String,
+    pub extra_custom_record_slots: u32,
+    pub extra_data_length: u32,
+}
+
+/// This enum describes an operation of the Set Records Msg
+#[repr(u8)]
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub enum PnsRecordOperation {
+    /// Mints the record, or updates it when it exists
+    #[discriminant(0)]
+    Upsert { class: RecordClass, data: Vec<u8> },
+    #[discriminant(1)]
+    Delete { class: RecordClass },
+    /// Mints the custom record, or updates it when it exists
+    #[discriminant(2)]
+    UpsertCustom { key: String, data: Vec<u8> },
+    #[discriminant(3)]
+    DeleteCustom { key: String },
+}
+
+/// This structure describes fields for the Set Records Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsSetRecordsMsg {
+    pub domain: String,
+    /// Operations applied in order
+    pub operations: Vec<PnsRecordOperation>,
+}
+
+/// This enum describes which records a domain keeps
+#[repr(u8)]
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub enum PnsRecordRetention {
+    /// Deletes every record, custom record and address
+    #[discriminant(0)]
+    WipeAll {},
+    #[discriminant(1)]
+    KeepAll {},
+    /// Keeps the records of the classes, the custom records of the keys
+    /// and the addresses of the SLIP-44 coin types, deletes everything else including the alias
+    #[discriminant(2)]
+    KeepOnly {
+        classes: Vec<RecordClass>,
+        custom_keys: Vec<String>,
+        coin_types: Vec<u32>,
+    },
+}
+
+/// This structure describes fields for the Record Retain Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsRecordRetainMsg {
+    pub domain: String,
+    pub retention: PnsRecordRetention,
+}
+
+/// This structure describes fields for the Set Record History Size Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsSetRecordHistorySizeMsg {
+    pub domain: String,
+    /// Number of versions kept for every record and address, zero disables the history
+    pub size: u32,
+}
+
+/// This enum describes the record targeted by the Set Record Ttl Msg
+#[repr(u8)]
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub enum PnsRecordTarget {
+    #[discriminant(0)]
+    Record { class: RecordClass },
+    #[discriminant(1)]
+    CustomRecord { key: String },
+    /// SLIP-44 coin type
+    #[discriminant(2)]
+    Address { coin_type: u32 },
+}
+
+/// This structure describes fields for the Set Record Ttl Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsSetRecordTtlMsg {
+    pub domain: String,
+    pub target: PnsRecordTarget,
+    /// Removes the hints when both are none
+    pub ttl: RecordTtl,
+}
+
+/// This structure describes fields for the Set Wildcard Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsSetWildcardMsg {
+    pub domain: String,
+    /// Resolves the records of the domain for its unminted subdomains
+    pub enabled: bool,
+}
+
+/// This structure describes fields for the Set Alias Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsSetAliasMsg {
+    pub domain: String,
+    /// Domain resolved in place of the domain, removes the alias when none
+    pub target: Option<String>,
+}
+
+/// This structure describes fields for the Prune Expired Records Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsPruneExpiredRecordsMsg {
+    pub domain: String,
+}
+
 /// This structure describes fields for the Record Delete All Msg
 #[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
 pub struct PnsRecordDeleteAllMsg {
@@ -77,3 +254,24 @@ pub struct PnsDomainUpdateExpirationMsg {
     pub domain: String,
     pub expires_at: Option<i64>,
 }
+
+/// This structure describes fields for the Domain Set Manager Msg
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct PnsDomainSetManagerMsg {
+    pub domain: String,
+    pub manager: Option<Address>,
+}
+
+// Events structs
+
+/// Struct for set records event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct SetRecordsEvent {
+    pub domain: String,
+    pub upserted_records: Vec<RecordClass>,
+    pub deleted_records: Vec<RecordClass>,
+    pub upserted_custom_records: Vec<String>,
+    pub deleted_custom_records: Vec<String>,
+}
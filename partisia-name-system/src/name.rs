@@ -0,0 +1,103 @@
+//! Domain names, parsed as dot separated labels in DNS order.
+//!
+//! The leftmost label is the most specific one, so `sub.mpc.name` is the child of `mpc.name`,
+//! which is itself the child of `name`. A subdomain is exactly one label more than its parent.
+//!
+//! A label has 1 to [`MAX_LABEL_LEN`] characters out of an allowlist: lowercase ASCII letters,
+//! digits and hyphens, precomposed lowercase Latin letters such as `ñ`, and single code point
+//! emoji. Everything else is refused, notably invisible and bidirectional controls,
+//! dot lookalikes such as `。` and letters of other scripts that mimic Latin ones,
+//! as the Cyrillic `а`. A label does not start nor end with a hyphen.
+//! A name has up to [`MAX_DOMAIN_LEN`] characters, dots included.
+//!
+//! Names were previously ordered left to right, a subdomain being its parent followed by
+//! a suffix as in `mpc.name.sub`. [`migrate_legacy_name`] gives the name of such a subdomain
+//! in DNS order, which the contract state migration applies from the root domains down.
+
+use crate::state::MAX_DOMAIN_LEN;
+
+/// Maximum number of characters of a label
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// Returns the labels of a name, most specific first, or none when a label is invalid
+pub fn parse_labels(name: &str) -> Option<Vec<&str>> {
+    let labels: Vec<&str> = name.split('.').collect();
+
+    labels
+        .iter()
+        .all(|label| is_valid_label(label))
+        .then_some(labels)
+}
+
+/// A label has 1 to [`MAX_LABEL_LEN`] allowed characters and does not start nor end with a hyphen
+pub fn is_valid_label(label: &str) -> bool {
+    let length = label.chars().count();
+
+    (1..=MAX_LABEL_LEN).contains(&length)
+        && label.chars().all(is_valid_label_char)
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+}
+
+/// A name has up to [`MAX_DOMAIN_LEN`] characters in valid labels
+pub fn is_valid_name(name: &str) -> bool {
+    name.chars().count() <= MAX_DOMAIN_LEN && parse_labels(name).is_some()
+}
+
+/// Returns the name of the parent, the name without its first label,
+/// or none for a single label name
+pub fn get_parent_name(name: &str) -> Option<&str> {
+    name.split_once('.').map(|(_, parent)| parent)
+}
+
+/// Returns the names of the possible ancestors of a name, nearest first
+pub fn get_ancestor_names(name: &str) -> Vec<&str> {
+    std::iter::successors(get_parent_name(name), |name| get_parent_name(name)).collect()
+}
+
+/// A name is a child of a parent when it is exactly one valid label under it
+pub fn is_child_name(name: &str, parent: &str) -> bool {
+    name.split_once('.').map_or(false, |(label, rest)| {
+        rest == parent && is_valid_label(label)
+    })
+}
+
+/// Returns the DNS order name of a legacy subdomain, given the legacy name of its parent
+/// and the already migrated one. The labels the legacy name adds to its parent are reversed
+/// and put in front of the migrated parent, `mpc.name.sub.deep` under `mpc.name.sub` becoming
+/// `deep.sub.mpc.name` once `mpc.name.sub` is migrated to `sub.mpc.name`.
+/// Returns none when the legacy name is not its parent followed by a dot and more labels
+pub fn migrate_legacy_name(
+    legacy_name: &str,
+    legacy_parent: &str,
+    migrated_parent: &str,
+) -> Option<String> {
+    let suffix = legacy_name
+        .strip_prefix(legacy_parent)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .filter(|suffix| !suffix.is_empty())?;
+
+    let mut labels: Vec<&str> = suffix.split('.').rev().collect();
+    labels.push(migrated_parent);
+
+    Some(labels.join("."))
+}
+
+/// Lowercase letters of the Latin-1 Supplement and Latin Extended-A and B blocks
+const LATIN_LETTERS: std::ops::RangeInclusive<char> = '\u{00DF}'..='\u{024F}';
+/// Miscellaneous symbols, dingbats, and the pictographs, emoticons and transport symbols
+const EMOJI_RANGES: [std::ops::RangeInclusive<char>; 4] = [
+    '\u{2600}'..='\u{27BF}',
+    '\u{1F300}'..='\u{1F6FF}',
+    '\u{1F900}'..='\u{1F9FF}',
+    '\u{1FA70}'..='\u{1FAFF}',
+];
+
+fn is_valid_label_char(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+    } else {
+        (LATIN_LETTERS.contains(&c) && c.is_lowercase())
+            || EMOJI_RANGES.iter().any(|range| range.contains(&c))
+    }
+}
@@ -1,14 +1,33 @@
 use contract_version_base::state::ContractVersionBase;
 use create_type_spec_derive::CreateTypeSpec;
-use pbc_contract_common::{avl_tree_map::AvlTreeMap, sorted_vec_map::SortedVecMap};
+use pbc_contract_common::{
+    address::Address,
+    avl_tree_map::AvlTreeMap,
+    sorted_vec_map::{SortedVecMap, SortedVecSet},
+};
 use read_write_rpc_derive::ReadWriteRPC;
 use read_write_state_derive::ReadWriteState;
 
-use crate::ContractError;
+use crate::{
+    coin_address::decode_coin_address,
+    content_hash::decode_content_hash,
+    dns::{decode_dns_record, dns_data_length_limits},
+    name, ContractError,
+};
 
+/// Default record data length limit, the data must be shorter than it
 pub const MAX_RECORD_DATA_LENGTH: usize = 64;
 pub const MAX_DOMAIN_LEN: usize = 32;
+/// Default number of custom record slots of a domain
 pub const MAX_CUSTOM_RECORDS: usize = 10;
+/// Highest record data length limit that can be configured, for any record class
+pub const MAX_RECORD_DATA_LENGTH_LIMIT: u32 = 1024;
+/// Highest number of custom record slots that can be configured
+pub const MAX_CUSTOM_RECORD_SLOTS_LIMIT: u32 = 100;
+/// Maximum number of aliases followed when resolving a name
+pub const MAX_ALIAS_DEPTH: usize = 8;
+/// Maximum number of versions kept in the history of a record
+pub const MAX_RECORD_HISTORY_SIZE: u32 = 10;
 
 /// This structure describes Partisia Name System state
 #[repr(C)]
@@ -16,6 +35,54 @@ pub const MAX_CUSTOM_RECORDS: usize = 10;
 pub struct PartisiaNameSystemState {
     pub version: ContractVersionBase,
     pub domains: AvlTreeMap<String, Domain>,
+    /// token id inverse lookup
+    pub domains_by_token_id: AvlTreeMap<u128, String>,
+    /// parent inverse lookup
+    pub subdomains: AvlTreeMap<String, SortedVecSet<String>>,
+    pub record_limits: RecordLimits,
+    /// Record versions of the domains keeping a history
+    pub record_history: AvlTreeMap<String, RecordHistory>,
+}
+
+/// Version of a record, written by a record action
+#[repr(C)]
+#[derive(ReadWriteState, ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordVersion {
+    /// Data written, none when the record was deleted
+    pub data: Option<Vec<u8>>,
+    /// Unix millis timestamp
+    pub written_at: i64,
+    pub writer: Address,
+}
+
+/// Latest versions of the records and addresses of a domain, oldest first
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordHistory {
+    pub records: SortedVecMap<RecordClass, Vec<RecordVersion>>,
+    pub custom_records: SortedVecMap<String, Vec<RecordVersion>>,
+    /// Versions of the addresses keyed by coin type
+    pub addresses: SortedVecMap<u32, Vec<RecordVersion>>,
+}
+
+/// Limits of the records of every domain, a domain can extend them with extra capacity
+#[repr(C)]
+#[derive(ReadWriteState, ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordLimits {
+    /// Record data must be shorter than this length, unless its class has its own limit
+    pub data_length_limit: u32,
+    /// Data length limits of specific record classes
+    pub class_data_length_limits: Vec<RecordClassLimit>,
+    /// Number of custom records of a domain
+    pub custom_record_slots: u32,
+}
+
+#[repr(C)]
+#[derive(ReadWriteState, ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordClassLimit {
+    pub class: RecordClass,
+    /// Record data of the class must be shorter than this length
+    pub data_length_limit: u32,
 }
 
 #[repr(C)]
@@ -28,6 +95,51 @@ pub struct Domain {
     pub expires_at: Option<i64>,
     pub records: SortedVecMap<RecordClass, Vec<u8>>,
     pub custom_records: SortedVecMap<String, Vec<u8>>,
+    /// Address allowed to manage the records on behalf of the owner
+    pub manager: Option<Address>,
+    /// Binary encoded wallet addresses by SLIP-44 coin type
+    pub addresses: SortedVecMap<u32, Vec<u8>>,
+    /// Purchased custom record slots, on top of the default ones
+    pub extra_custom_record_slots: u32,
+    /// Purchased record data length, on top of the limit of every record
+    pub extra_data_length: u32,
+    /// Number of versions kept in the history of every record, none are kept when zero
+    pub record_history_size: u32,
+    /// Records of the domain are resolved for its unminted subdomains
+    pub wildcard: bool,
+    /// Domain whose records are resolved in place of the records of this domain
+    pub alias: Option<AliasTarget>,
+    pub record_ttls: SortedVecMap<RecordClass, RecordTtl>,
+    pub custom_record_ttls: SortedVecMap<String, RecordTtl>,
+    pub address_ttls: SortedVecMap<u32, RecordTtl>,
+}
+
+/// Target of an alias, the token of the domain when the alias was set
+/// tells it apart from a later domain minted with the same name
+#[repr(C)]
+#[derive(ReadWriteState, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct AliasTarget {
+    pub domain: String,
+    pub token_id: u128,
+}
+
+/// Domains followed when resolving a name through its aliases
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AliasResolution {
+    /// Domain whose records are resolved, none when the chain is broken
+    pub target: Option<String>,
+    /// Followed domains, starting with the domain answering for the name
+    pub chain: Vec<String>,
+}
+
+/// Caching and expiry hints of a record
+#[repr(C)]
+#[derive(ReadWriteState, ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct RecordTtl {
+    /// Seconds the record may be cached by off-chain resolvers
+    pub ttl: Option<u32>,
+    /// Unix millis timestamp, the record is not resolved after it
+    pub expires_at: Option<i64>,
 }
 
 #[repr(u8)]
@@ -59,6 +171,76 @@ pub enum RecordClass {
     Custom4 {},
     #[discriminant(11)]
     Custom5 {},
+    /// Stored in the binary form defined in [`crate::content_hash`]
+    #[discriminant(12)]
+    ContentHash {},
+    /// DNS records, stored as their RDATA as defined in [`crate::dns`]
+    #[discriminant(13)]
+    DnsA {},
+    #[discriminant(14)]
+    DnsAaaa {},
+    #[discriminant(15)]
+    DnsCname {},
+    #[discriminant(16)]
+    DnsTxt {},
+    #[discriminant(17)]
+    DnsMx {},
+}
+
+impl Default for RecordLimits {
+    fn default() -> Self {
+        RecordLimits {
+            data_length_limit: MAX_RECORD_DATA_LENGTH as u32,
+            class_data_length_limits: dns_data_length_limits(),
+            custom_record_slots: MAX_CUSTOM_RECORDS as u32,
+        }
+    }
+}
+
+impl RecordLimits {
+    /// Checks that every data length limit is above zero and none of the limits
+    /// is over its maximum, see [`MAX_RECORD_DATA_LENGTH_LIMIT`] and [`MAX_CUSTOM_RECORD_SLOTS_LIMIT`]
+    pub fn is_valid(&self) -> bool {
+        let is_valid_length = |limit: u32| limit > 0 && limit <= MAX_RECORD_DATA_LENGTH_LIMIT;
+
+        is_valid_length(self.data_length_limit)
+            && self
+                .class_data_length_limits
+                .iter()
+                .all(|limit| is_valid_length(limit.data_length_limit))
+            && self.custom_record_slots <= MAX_CUSTOM_RECORD_SLOTS_LIMIT
+    }
+
+    /// Returns the data length limit of a record of the domain, including its extra length
+    pub fn get_data_length_limit(&self, domain: &Domain, class: &RecordClass) -> usize {
+        let limit = self
+            .class_data_length_limits
+            .iter()
+            .find(|limit| limit.class == *class)
+            .map_or(self.data_length_limit, |limit| limit.data_length_limit);
+
+        limit.saturating_add(domain.extra_data_length) as usize
+    }
+
+    /// Returns the data length limit of a custom record of the domain, including its extra length
+    pub fn get_custom_data_length_limit(&self, domain: &Domain) -> usize {
+        self.data_length_limit
+            .saturating_add(domain.extra_data_length) as usize
+    }
+
+    /// Returns the number of custom record slots of the domain, including its extra slots
+    pub fn get_custom_record_slots(&self, domain: &Domain) -> usize {
+        self.custom_record_slots
+            .saturating_add(domain.extra_custom_record_slots) as usize
+    }
+}
+
+impl RecordTtl {
+    /// Checks if the record is past its expiry
+    pub fn is_expired(&self, unix_millis_now: i64) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at < unix_millis_now)
+    }
 }
 
 impl Domain {
@@ -82,8 +264,55 @@ impl Domain {
         self.custom_records.contains_key(key)
     }
 
+    /// Get binary encoded address given coin type
+    pub fn get_address(&self, coin_type: u32) -> Option<&Vec<u8>> {
+        self.addresses.get(&coin_type)
+    }
+
+    /// Existence of address given coin type
+    pub fn is_address_minted(&self, coin_type: u32) -> bool {
+        self.addresses.contains_key(&coin_type)
+    }
+
+    /// Get caching and expiry hints of record given class
+    pub fn get_record_ttl(&self, class: &RecordClass) -> Option<&RecordTtl> {
+        self.record_ttls.get(class)
+    }
+
+    /// Get caching and expiry hints of custom record given key
+    pub fn get_custom_record_ttl(&self, key: &str) -> Option<&RecordTtl> {
+        self.custom_record_ttls.get(key)
+    }
+
+    /// Get caching and expiry hints of address given coin type
+    pub fn get_address_ttl(&self, coin_type: u32) -> Option<&RecordTtl> {
+        self.address_ttls.get(&coin_type)
+    }
+
+    /// Checks if the record of the given class is past its expiry
+    pub fn is_record_expired(&self, class: &RecordClass, unix_millis_now: i64) -> bool {
+        self.record_ttls
+            .get(class)
+            .map_or(false, |ttl| ttl.is_expired(unix_millis_now))
+    }
+
+    /// Checks if the custom record of the given key is past its expiry
+    pub fn is_custom_record_expired(&self, key: &str, unix_millis_now: i64) -> bool {
+        self.custom_record_ttls
+            .get(key)
+            .map_or(false, |ttl| ttl.is_expired(unix_millis_now))
+    }
+
+    /// Checks if the address of the given coin type is past its expiry
+    pub fn is_address_expired(&self, coin_type: u32, unix_millis_now: i64) -> bool {
+        self.address_ttls
+            .get(&coin_type)
+            .map_or(false, |ttl| ttl.is_expired(unix_millis_now))
+    }
+
     /// Checks if domain is active
-    /// Opposite of expired
+    /// Opposite of expired, only its own expiry is checked,
+    /// see [`PartisiaNameSystemState::is_active`] for its parents
     pub fn is_active(&self, unix_millis_now: i64) -> bool {
         match self.expires_at {
             Some(expires_at) => expires_at >= unix_millis_now,
@@ -102,8 +331,8 @@ impl Domain {
         self.records.insert(*class, data.to_owned());
     }
 
-    /// Update data of a record
-    pub fn update_record_data(&mut self, class: &RecordClass, data: &[u8]) {
+    /// Update data of a record, the new data does not keep an expiry the record is past
+    pub fn update_record_data(&mut self, class: &RecordClass, data: &[u8], unix_millis_now: i64) {
         assert!(
             self.is_record_minted(class),
             "{}",
@@ -111,6 +340,7 @@ impl Domain {
         );
 
         self.records.insert(*class, data.to_owned());
+        clear_expired_ttl(&mut self.record_ttls, *class, unix_millis_now);
     }
 
     /// Remove a record
@@ -123,11 +353,19 @@ impl Domain {
 
         if self.records.contains_key(class) {
             self.records.remove_entry(class);
+            self.record_ttls.remove_entry(class);
         } else {
             panic!("{}", ContractError::NotFound);
         }
     }
 
+    /// Mints the record, or updates its data when it exists,
+    /// the new data does not keep an expiry the record is past
+    pub fn upsert_record(&mut self, class: &RecordClass, data: &[u8], unix_millis_now: i64) {
+        self.records.insert(*class, data.to_owned());
+        clear_expired_ttl(&mut self.record_ttls, *class, unix_millis_now);
+    }
+
     /// Mints custom record for token
     pub fn mint_custom_record(&mut self, key: &str, data: &[u8]) {
         assert!(
@@ -139,8 +377,8 @@ impl Domain {
         self.custom_records.insert(key.to_owned(), data.to_owned());
     }
 
-    /// Update data of a custom record
-    pub fn update_custom_record_data(&mut self, key: &str, data: &[u8]) {
+    /// Update data of a custom record, the new data does not keep an expiry the record is past
+    pub fn update_custom_record_data(&mut self, key: &str, data: &[u8], unix_millis_now: i64) {
         assert!(
             self.is_custom_record_minted(key),
             "{}",
@@ -148,6 +386,22 @@ impl Domain {
         );
 
         self.custom_records.insert(key.to_owned(), data.to_owned());
+        clear_expired_ttl(
+            &mut self.custom_record_ttls,
+            key.to_owned(),
+            unix_millis_now,
+        );
+    }
+
+    /// Mints the custom record, or updates its data when it exists,
+    /// the new data does not keep an expiry the record is past
+    pub fn upsert_custom_record(&mut self, key: &str, data: &[u8], unix_millis_now: i64) {
+        self.custom_records.insert(key.to_owned(), data.to_owned());
+        clear_expired_ttl(
+            &mut self.custom_record_ttls,
+            key.to_owned(),
+            unix_millis_now,
+        );
     }
 
     /// Remove a custom record
@@ -160,10 +414,160 @@ impl Domain {
 
         if self.custom_records.contains_key(key) {
             self.custom_records.remove_entry(key);
+            self.custom_record_ttls.remove_entry(key);
         } else {
             panic!("{}", ContractError::NotFound);
         }
     }
+
+    /// Keeps only the records of the given classes and the custom records of the given keys
+    pub fn retain_records(&mut self, classes: &[RecordClass], custom_keys: &[String]) {
+        let mut records = SortedVecMap::new();
+        for class in classes {
+            if let Some(data) = self.records.get(class) {
+                records.insert(*class, data.clone());
+            }
+        }
+
+        let mut custom_records = SortedVecMap::new();
+        for key in custom_keys {
+            if let Some(data) = self.custom_records.get(key) {
+                custom_records.insert(key.clone(), data.clone());
+            }
+        }
+
+        self.records = records;
+        self.custom_records = custom_records;
+        self.record_ttls = retain_ttls(&self.record_ttls, |class| classes.contains(class));
+        self.custom_record_ttls =
+            retain_ttls(&self.custom_record_ttls, |key| custom_keys.contains(key));
+    }
+
+    /// Mints binary encoded address for coin type
+    pub fn mint_address(&mut self, coin_type: u32, address: &[u8]) {
+        assert!(
+            !self.is_address_minted(coin_type),
+            "{}",
+            ContractError::RecordMinted
+        );
+
+        self.addresses.insert(coin_type, address.to_owned());
+    }
+
+    /// Update binary encoded address of a coin type,
+    /// the new address does not keep an expiry the previous one is past
+    pub fn update_address(&mut self, coin_type: u32, address: &[u8], unix_millis_now: i64) {
+        assert!(
+            self.is_address_minted(coin_type),
+            "{}",
+            ContractError::RecordNotMinted
+        );
+
+        self.addresses.insert(coin_type, address.to_owned());
+        clear_expired_ttl(&mut self.address_ttls, coin_type, unix_millis_now);
+    }
+
+    /// Remove the address of a coin type
+    pub fn delete_address(&mut self, coin_type: u32) {
+        assert!(
+            self.is_address_minted(coin_type),
+            "{}",
+            ContractError::RecordNotMinted
+        );
+
+        self.addresses.remove_entry(&coin_type);
+        self.address_ttls.remove_entry(&coin_type);
+    }
+
+    /// Remove every address
+    pub fn delete_addresses(&mut self) {
+        self.addresses = SortedVecMap::new();
+        self.address_ttls = SortedVecMap::new();
+    }
+
+    /// Keeps only the addresses of the given coin types
+    pub fn retain_addresses(&mut self, coin_types: &[u32]) {
+        let mut addresses = SortedVecMap::new();
+        for coin_type in coin_types {
+            if let Some(address) = self.addresses.get(coin_type) {
+                addresses.insert(*coin_type, address.clone());
+            }
+        }
+
+        self.addresses = addresses;
+        self.address_ttls = retain_ttls(&self.address_ttls, |coin_type| {
+            coin_types.contains(coin_type)
+        });
+    }
+
+    /// Sets the caching and expiry hints of a record, removes them when both are none
+    pub fn set_record_ttl(&mut self, class: &RecordClass, ttl: RecordTtl) {
+        assert!(
+            self.is_record_minted(class),
+            "{}",
+            ContractError::RecordNotMinted
+        );
+
+        set_ttl(&mut self.record_ttls, *class, ttl);
+    }
+
+    /// Sets the caching and expiry hints of a custom record, removes them when both are none
+    pub fn set_custom_record_ttl(&mut self, key: &str, ttl: RecordTtl) {
+        assert!(
+            self.is_custom_record_minted(key),
+            "{}",
+            ContractError::RecordNotMinted
+        );
+
+        set_ttl(&mut self.custom_record_ttls, key.to_owned(), ttl);
+    }
+
+    /// Sets the caching and expiry hints of an address, removes them when both are none
+    pub fn set_address_ttl(&mut self, coin_type: u32, ttl: RecordTtl) {
+        assert!(
+            self.is_address_minted(coin_type),
+            "{}",
+            ContractError::RecordNotMinted
+        );
+
+        set_ttl(&mut self.address_ttls, coin_type, ttl);
+    }
+
+    /// Removes the records, custom records and addresses past their expiry
+    pub fn prune_expired_records(&mut self, unix_millis_now: i64) {
+        let expired_classes: Vec<RecordClass> = self
+            .record_ttls
+            .iter()
+            .filter(|(_, ttl)| ttl.is_expired(unix_millis_now))
+            .map(|(class, _)| *class)
+            .collect();
+        for class in expired_classes {
+            self.records.remove_entry(&class);
+            self.record_ttls.remove_entry(&class);
+        }
+
+        let expired_keys: Vec<String> = self
+            .custom_record_ttls
+            .iter()
+            .filter(|(_, ttl)| ttl.is_expired(unix_millis_now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired_keys {
+            self.custom_records.remove_entry(&key);
+            self.custom_record_ttls.remove_entry(&key);
+        }
+
+        let expired_coin_types: Vec<u32> = self
+            .address_ttls
+            .iter()
+            .filter(|(_, ttl)| ttl.is_expired(unix_millis_now))
+            .map(|(coin_type, _)| *coin_type)
+            .collect();
+        for coin_type in expired_coin_types {
+            self.addresses.remove_entry(&coin_type);
+            self.address_ttls.remove_entry(&coin_type);
+        }
+    }
 }
 
 impl PartisiaNameSystemState {
@@ -186,10 +590,11 @@ impl PartisiaNameSystemState {
         }
     }
 
+    /// Returns domain name and info given token id
     pub fn get_domain_by_token_id(&self, token_id: u128) -> Option<(String, Domain)> {
-        self.domains
-            .iter()
-            .find(|(_, domain)| domain.token_id == token_id)
+        self.domains_by_token_id
+            .get(&token_id)
+            .and_then(|name| self.get_domain(&name).map(|domain| (name, domain)))
     }
 
     /// Returns parent info by domain
@@ -238,6 +643,239 @@ impl PartisiaNameSystemState {
         }
     }
 
+    /// Returns a page of the direct subdomains of a domain, sorted by name
+    pub fn get_subdomains(&self, domain_name: &str, offset: u32, limit: u32) -> Vec<String> {
+        self.subdomains
+            .get(&domain_name.to_owned())
+            .map(|subdomains| {
+                subdomains
+                    .iter()
+                    .skip(offset as usize)
+                    .take(limit as usize)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of direct subdomains of a domain
+    pub fn get_subdomain_count(&self, domain_name: &str) -> u32 {
+        self.subdomains
+            .get(&domain_name.to_owned())
+            .map_or(0, |subdomains| subdomains.len() as u32)
+    }
+
+    /// Get all the subdomains of a domain, at any depth
+    pub fn get_descendants(&self, domain_name: &str) -> Vec<String> {
+        let mut descendants: Vec<String> = vec![];
+        let mut pending = vec![domain_name.to_owned()];
+
+        while let Some(current) = pending.pop() {
+            if let Some(subdomains) = self.subdomains.get(&current) {
+                for subdomain in subdomains.iter() {
+                    descendants.push(subdomain.clone());
+                    pending.push(subdomain.clone());
+                }
+            }
+        }
+
+        descendants
+    }
+
+    /// Returns the domain resolving the given name, following the aliases
+    /// from the domain answering for the name
+    pub fn get_resolved_domain(&self, domain_name: &str, unix_millis_now: i64) -> Option<Domain> {
+        self.resolve_alias(domain_name, unix_millis_now)
+            .target
+            .and_then(|target| self.get_domain(&target))
+    }
+
+    /// Returns the name of the domain answering for the given name, the domain itself
+    /// when it is minted, otherwise its nearest active wildcard ancestor.
+    /// None answers for a minted domain that is not active, or whose root is not active
+    pub fn get_answering_domain_name(
+        &self,
+        domain_name: &str,
+        unix_millis_now: i64,
+    ) -> Option<String> {
+        if self.is_minted(domain_name) {
+            return self
+                .is_active(domain_name, unix_millis_now)
+                .then(|| domain_name.to_string());
+        }
+
+        self.get_wildcard_ancestor(domain_name, unix_millis_now)
+            .map(|(name, _)| name)
+    }
+
+    /// Returns the nearest active wildcard ancestor of an unminted domain name,
+    /// walking up the parents of its nearest minted ancestor
+    pub fn get_wildcard_ancestor(
+        &self,
+        domain_name: &str,
+        unix_millis_now: i64,
+    ) -> Option<(String, Domain)> {
+        let closest_name = name::get_ancestor_names(domain_name)
+            .into_iter()
+            .find(|ancestor| self.is_minted(ancestor))?;
+        let closest = self.get_domain(closest_name)?;
+
+        std::iter::once(closest)
+            .chain(self.get_parents(closest_name))
+            .filter(|domain| domain.wildcard)
+            .filter_map(|domain| self.get_domain_by_token_id(domain.token_id))
+            .find(|(name, _)| self.is_active(name, unix_millis_now))
+    }
+
+    /// Follows the aliases of active domains from the domain answering for the name.
+    /// The target is none when the name does not resolve, an alias target is not minted,
+    /// is not active or was burned and minted again with another token,
+    /// the chain loops or it is longer than [`MAX_ALIAS_DEPTH`] aliases.
+    /// A domain is only active when its root is, see [`PartisiaNameSystemState::is_active`]
+    pub fn resolve_alias(&self, domain_name: &str, unix_millis_now: i64) -> AliasResolution {
+        let mut chain: Vec<String> = vec![];
+        let mut current = self
+            .get_answering_domain_name(domain_name, unix_millis_now)
+            .map(|name| (name, None));
+
+        while let Some((name, token_id)) = current {
+            if chain.contains(&name) || chain.len() > MAX_ALIAS_DEPTH {
+                break;
+            }
+
+            let domain = self
+                .get_domain(&name)
+                .filter(|domain| token_id.map_or(true, |token_id| domain.token_id == token_id));
+            chain.push(name.clone());
+
+            match domain {
+                Some(domain) if self.is_active(&name, unix_millis_now) => match domain.alias {
+                    Some(alias) => current = Some((alias.domain, Some(alias.token_id))),
+                    None => {
+                        return AliasResolution {
+                            target: Some(name),
+                            chain,
+                        }
+                    }
+                },
+                _ => break,
+            }
+        }
+
+        AliasResolution {
+            target: None,
+            chain,
+        }
+    }
+
+    /// Says if records can be resolved for the name, directly, through a wildcard ancestor
+    /// or through aliases
+    pub fn is_resolvable(&self, domain_name: &str, unix_millis_now: i64) -> bool {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .is_some()
+    }
+
+    /// Returns the data of a record of a domain, none if it is past its expiry
+    pub fn resolve_record(
+        &self,
+        domain_name: &str,
+        class: &RecordClass,
+        unix_millis_now: i64,
+    ) -> Option<Vec<u8>> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| {
+                if domain.is_record_expired(class, unix_millis_now) {
+                    return None;
+                }
+                domain.get_record(class).cloned()
+            })
+    }
+
+    /// Returns the data of a custom record of a domain, none if it is past its expiry
+    pub fn resolve_custom_record(
+        &self,
+        domain_name: &str,
+        key: &str,
+        unix_millis_now: i64,
+    ) -> Option<Vec<u8>> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| {
+                if domain.is_custom_record_expired(key, unix_millis_now) {
+                    return None;
+                }
+                domain.get_custom_record(key).cloned()
+            })
+    }
+
+    /// Returns the text form of the address of a domain for the given coin type,
+    /// none if it is past its expiry
+    pub fn resolve_address(
+        &self,
+        domain_name: &str,
+        coin_type: u32,
+        unix_millis_now: i64,
+    ) -> Option<String> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| {
+                if domain.is_address_expired(coin_type, unix_millis_now) {
+                    return None;
+                }
+                domain
+                    .get_address(coin_type)
+                    .and_then(|address| decode_coin_address(coin_type, address))
+            })
+    }
+
+    /// Returns the text form of the content hash record of a domain, none if it is past its expiry
+    pub fn resolve_content_hash(&self, domain_name: &str, unix_millis_now: i64) -> Option<String> {
+        self.resolve_record(domain_name, &RecordClass::ContentHash {}, unix_millis_now)
+            .and_then(|content_hash| decode_content_hash(&content_hash))
+    }
+
+    /// Returns the text form of a DNS record of a domain, none if it is past its expiry
+    pub fn resolve_dns_record(
+        &self,
+        domain_name: &str,
+        class: &RecordClass,
+        unix_millis_now: i64,
+    ) -> Option<String> {
+        self.resolve_record(domain_name, class, unix_millis_now)
+            .and_then(|rdata| decode_dns_record(class, &rdata))
+    }
+
+    /// Returns the seconds a record of a domain may be cached
+    pub fn get_record_ttl_hint(
+        &self,
+        domain_name: &str,
+        class: &RecordClass,
+        unix_millis_now: i64,
+    ) -> Option<u32> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| domain.get_record_ttl(class).and_then(|ttl| ttl.ttl))
+    }
+
+    /// Returns the seconds a custom record of a domain may be cached
+    pub fn get_custom_record_ttl_hint(
+        &self,
+        domain_name: &str,
+        key: &str,
+        unix_millis_now: i64,
+    ) -> Option<u32> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| domain.get_custom_record_ttl(key).and_then(|ttl| ttl.ttl))
+    }
+
+    /// Returns the seconds an address of a domain may be cached
+    pub fn get_address_ttl_hint(
+        &self,
+        domain_name: &str,
+        coin_type: u32,
+        unix_millis_now: i64,
+    ) -> Option<u32> {
+        self.get_resolved_domain(domain_name, unix_millis_now)
+            .and_then(|domain| domain.get_address_ttl(coin_type).and_then(|ttl| ttl.ttl))
+    }
+
     /// Says is token id minted or not
     pub fn is_minted(&self, domain_name: &str) -> bool {
         self.domains.contains_key(&domain_name.to_owned())
@@ -249,4 +887,236 @@ impl PartisiaNameSystemState {
             .get(&domain_name.to_owned())
             .map(|d| d.token_id)
     }
+
+    /// Returns true if the address is the manager of the domain
+    pub fn is_manager(&self, domain_name: &str, address: &Address) -> bool {
+        self.get_domain(domain_name)
+            .map_or(false, |domain| domain.manager == Some(*address))
+    }
+
+    /// Sets or clears the manager of a domain
+    pub fn _set_manager(&mut self, domain_name: &str, manager: Option<Address>) {
+        if let Some(mut domain) = self.get_domain(domain_name) {
+            domain.manager = manager;
+            self.domains.insert(domain_name.to_owned(), domain);
+        }
+    }
+
+    /// Returns the latest versions of a record of a domain, oldest first
+    pub fn get_record_history(&self, domain_name: &str, class: &RecordClass) -> Vec<RecordVersion> {
+        self.record_history
+            .get(&domain_name.to_owned())
+            .and_then(|history| history.records.get(class).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Returns the latest versions of a custom record of a domain, oldest first
+    pub fn get_custom_record_history(&self, domain_name: &str, key: &str) -> Vec<RecordVersion> {
+        self.record_history
+            .get(&domain_name.to_owned())
+            .and_then(|history| history.custom_records.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Returns the latest versions of an address of a domain, oldest first
+    pub fn get_address_history(&self, domain_name: &str, coin_type: u32) -> Vec<RecordVersion> {
+        self.record_history
+            .get(&domain_name.to_owned())
+            .and_then(|history| history.addresses.get(&coin_type).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Adds a version to the history of a record, dropping the oldest ones over the history size
+    pub fn _push_record_version(
+        &mut self,
+        domain_name: &str,
+        history_size: u32,
+        class: &RecordClass,
+        version: RecordVersion,
+    ) {
+        if history_size == 0 {
+            return;
+        }
+
+        let mut history = self.get_or_create_record_history(domain_name);
+        let mut versions = history.records.get(class).cloned().unwrap_or_default();
+        push_version(&mut versions, version, history_size);
+        history.records.insert(*class, versions);
+        self.record_history.insert(domain_name.to_owned(), history);
+    }
+
+    /// Adds a version to the history of a custom record, dropping the oldest ones over the history size
+    pub fn _push_custom_record_version(
+        &mut self,
+        domain_name: &str,
+        history_size: u32,
+        key: &str,
+        version: RecordVersion,
+    ) {
+        if history_size == 0 {
+            return;
+        }
+
+        let mut history = self.get_or_create_record_history(domain_name);
+        let mut versions = history.custom_records.get(key).cloned().unwrap_or_default();
+        push_version(&mut versions, version, history_size);
+        history.custom_records.insert(key.to_owned(), versions);
+        self.record_history.insert(domain_name.to_owned(), history);
+    }
+
+    /// Adds a version to the history of an address, dropping the oldest ones over the history size
+    pub fn _push_address_version(
+        &mut self,
+        domain_name: &str,
+        history_size: u32,
+        coin_type: u32,
+        version: RecordVersion,
+    ) {
+        if history_size == 0 {
+            return;
+        }
+
+        let mut history = self.get_or_create_record_history(domain_name);
+        let mut versions = history
+            .addresses
+            .get(&coin_type)
+            .cloned()
+            .unwrap_or_default();
+        push_version(&mut versions, version, history_size);
+        history.addresses.insert(coin_type, versions);
+        self.record_history.insert(domain_name.to_owned(), history);
+    }
+
+    /// Drops the oldest versions of every record and address of a domain over the history size
+    pub fn _trim_record_history(&mut self, domain_name: &str, history_size: u32) {
+        if history_size == 0 {
+            self._prune_record_history(domain_name);
+            return;
+        }
+
+        if let Some(history) = self.record_history.get(&domain_name.to_owned()) {
+            let mut trimmed = RecordHistory {
+                records: SortedVecMap::new(),
+                custom_records: SortedVecMap::new(),
+                addresses: SortedVecMap::new(),
+            };
+            for (class, versions) in history.records.iter() {
+                let mut versions = versions.clone();
+                trim_versions(&mut versions, history_size);
+                trimmed.records.insert(*class, versions);
+            }
+            for (key, versions) in history.custom_records.iter() {
+                let mut versions = versions.clone();
+                trim_versions(&mut versions, history_size);
+                trimmed.custom_records.insert(key.clone(), versions);
+            }
+            for (coin_type, versions) in history.addresses.iter() {
+                let mut versions = versions.clone();
+                trim_versions(&mut versions, history_size);
+                trimmed.addresses.insert(*coin_type, versions);
+            }
+            self.record_history.insert(domain_name.to_owned(), trimmed);
+        }
+    }
+
+    /// Removes the whole record history of a domain
+    pub fn _prune_record_history(&mut self, domain_name: &str) {
+        self.record_history.remove(&domain_name.to_owned());
+    }
+
+    fn get_or_create_record_history(&self, domain_name: &str) -> RecordHistory {
+        self.record_history
+            .get(&domain_name.to_owned())
+            .unwrap_or_else(|| RecordHistory {
+                records: SortedVecMap::new(),
+                custom_records: SortedVecMap::new(),
+                addresses: SortedVecMap::new(),
+            })
+    }
+
+    /// Inserts a new domain and indexes its token id and parent
+    pub fn _insert_domain(&mut self, domain_name: &str, domain: Domain) {
+        if let Some(parent_id) = &domain.parent_id {
+            let mut subdomains = self
+                .subdomains
+                .get(parent_id)
+                .unwrap_or_else(SortedVecSet::new);
+            subdomains.insert(domain_name.to_owned());
+            self.subdomains.insert(parent_id.clone(), subdomains);
+        }
+
+        self.domains_by_token_id
+            .insert(domain.token_id, domain_name.to_owned());
+        self.domains.insert(domain_name.to_owned(), domain);
+    }
+
+    /// Removes a domain along with its token id and parent indexes
+    pub fn _remove_domain(&mut self, domain_name: &str) {
+        if let Some(domain) = self.get_domain(domain_name) {
+            if let Some(parent_id) = &domain.parent_id {
+                if let Some(mut subdomains) = self.subdomains.get(parent_id) {
+                    subdomains.remove(&domain_name.to_owned());
+                    if subdomains.is_empty() {
+                        self.subdomains.remove(parent_id);
+                    } else {
+                        self.subdomains.insert(parent_id.clone(), subdomains);
+                    }
+                }
+            }
+
+            self.subdomains.remove(&domain_name.to_owned());
+            self.record_history.remove(&domain_name.to_owned());
+            self.domains_by_token_id.remove(&domain.token_id);
+            self.domains.remove(&domain_name.to_owned());
+        }
+    }
+}
+
+fn push_version(versions: &mut Vec<RecordVersion>, version: RecordVersion, history_size: u32) {
+    versions.push(version);
+    trim_versions(versions, history_size);
+}
+
+fn trim_versions(versions: &mut Vec<RecordVersion>, history_size: u32) {
+    let excess = versions.len().saturating_sub(history_size as usize);
+    versions.drain(..excess);
+}
+
+fn set_ttl<K: Ord>(ttls: &mut SortedVecMap<K, RecordTtl>, key: K, ttl: RecordTtl) {
+    if ttl.ttl.is_none() && ttl.expires_at.is_none() {
+        ttls.remove_entry(&key);
+    } else {
+        ttls.insert(key, ttl);
+    }
+}
+
+/// Drops the expiry of a ttl past it, keeping its ttl hint
+fn clear_expired_ttl<K: Ord>(ttls: &mut SortedVecMap<K, RecordTtl>, key: K, unix_millis_now: i64) {
+    let ttl = match ttls.get(&key) {
+        Some(ttl) if ttl.is_expired(unix_millis_now) => ttl.ttl,
+        _ => return,
+    };
+
+    set_ttl(
+        ttls,
+        key,
+        RecordTtl {
+            ttl,
+            expires_at: None,
+        },
+    );
+}
+
+fn retain_ttls<K: Ord + Clone>(
+    ttls: &SortedVecMap<K, RecordTtl>,
+    keep: impl Fn(&K) -> bool,
+) -> SortedVecMap<K, RecordTtl> {
+    let mut retained = SortedVecMap::new();
+    for (key, ttl) in ttls.iter() {
+        if keep(key) {
+            retained.insert(key.clone(), ttl.clone());
+        }
+    }
+
+    retained
 }
@@ -1,21 +1,80 @@
 use crate::{
     actions::{
-        execute_custom_record_delete, execute_custom_record_mint, execute_custom_record_update,
-        execute_init, execute_mint, execute_record_delete, execute_record_delete_all,
-        execute_record_mint, execute_record_update, execute_update_expiration,
+        execute_add_record_capacity, execute_address_delete, execute_address_mint,
+        execute_address_update, execute_burn, execute_custom_record_delete,
+        execute_custom_record_mint, execute_custom_record_update, execute_import, execute_init,
+        execute_mint, execute_prune_expired_records, execute_record_delete,
+        execute_record_delete_all, execute_record_mint, execute_record_retain,
+        execute_record_update, execute_set_alias, execute_set_manager,
+        execute_set_record_history_size, execute_set_record_ttl, execute_set_records,
+        execute_set_wildcard, execute_update_expiration, validate_domain,
+        validate_domain_with_parent, validate_root_domain,
+    },
+    coin_address::{
+        decode_coin_address, encode_coin_address, COIN_TYPE_BITCOIN, COIN_TYPE_ETHEREUM,
+        COIN_TYPE_PARTISIA, COIN_TYPE_SOLANA,
+    },
+    content_hash::{decode_content_hash, encode_content_hash},
+    dns::{
+        decode_dns_record, encode_dns_record, export_wire_format, export_zone_file,
+        is_valid_hostname, is_valid_ipv4, is_valid_ipv6, MAX_DNS_ADDRESSES, MAX_TXT_LEN,
     },
     msg::{
-        PnsCustomRecordDeleteMsg, PnsCustomRecordMintMsg, PnsCustomRecordUpdateMsg,
-        PnsDomainUpdateExpirationMsg, PnsMintMsg, PnsRecordDeleteAllMsg, PnsRecordDeleteMsg,
-        PnsRecordMintMsg, PnsRecordUpdateMsg,
+        PnsAddRecordCapacityMsg, PnsAddressDeleteMsg, PnsAddressMintMsg, PnsAddressUpdateMsg,
+        PnsBurnMsg, PnsCustomRecordDeleteMsg, PnsCustomRecordEntry, PnsCustomRecordMintMsg,
+        PnsCustomRecordUpdateMsg, PnsDomainSetManagerMsg, PnsDomainUpdateExpirationMsg,
+        PnsImportMsg, PnsMintMsg, PnsPruneExpiredRecordsMsg, PnsRecordDeleteAllMsg,
+        PnsRecordDeleteMsg, PnsRecordEntry, PnsRecordMintMsg, PnsRecordOperation,
+        PnsRecordRetainMsg, PnsRecordRetention, PnsRecordTarget, PnsRecordUpdateMsg,
+        PnsSetAliasMsg, PnsSetRecordHistorySizeMsg, PnsSetRecordTtlMsg, PnsSetRecordsMsg,
+        PnsSetWildcardMsg,
+    },
+    name::{
+        get_ancestor_names, get_parent_name, is_child_name, is_valid_label, is_valid_name,
+        migrate_legacy_name, parse_labels,
+    },
+    state::{
+        RecordClass, RecordClassLimit, RecordLimits, RecordTtl, MAX_ALIAS_DEPTH,
+        MAX_CUSTOM_RECORDS, MAX_CUSTOM_RECORD_SLOTS_LIMIT, MAX_RECORD_DATA_LENGTH_LIMIT,
+        MAX_RECORD_HISTORY_SIZE,
+    },
+    validation::{
+        is_valid_avatar, is_valid_discord_username, is_valid_email, is_valid_twitter_handle,
+        is_valid_uri, is_valid_wallet, validate_record_data,
     },
-    state::{RecordClass, MAX_CUSTOM_RECORDS},
 };
 
+use nft::{actions as nft_actions, msg as nft_msg, state::NFTContractState};
 use utils::tests::{
-    mock_contract_context, string_to_bytes, tomorrow_timestamp, yesterday_timestamp,
+    mock_address, mock_contract_context, string_to_bytes, tomorrow_timestamp, unix_epoch_now,
+    yesterday_timestamp,
 };
 
+fn mock_nft_state(owner: u8, token_ids: &[u128]) -> NFTContractState {
+    let mut nft = nft_actions::execute_init(
+        &mock_contract_context(2),
+        &nft_msg::NFTInitMsg {
+            name: "Meta Names".to_string(),
+            symbol: "mpc".to_string(),
+            uri_template: "metanames.io".to_string(),
+        },
+    );
+
+    for token_id in token_ids {
+        nft_actions::execute_mint(
+            &mock_contract_context(2),
+            &mut nft,
+            &nft_msg::NFTMintMsg {
+                to: mock_address(owner),
+                token_id: *token_id,
+                token_uri: None,
+            },
+        );
+    }
+
+    nft
+}
+
 #[test]
 fn proper_mint() {
     let minter = 1u8;
@@ -37,6 +96,145 @@ fn proper_mint() {
     assert_eq!(num_token_id, 1);
 }
 
+#[test]
+fn expiration_update_propagates_to_subdomains() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let new_expiration = tomorrow_timestamp();
+    let later = new_expiration + 1000;
+    for (token_id, domain, parent_id, expires_at) in [
+        (1, "name", None, Some(later)),
+        (2, "a.name", Some("name"), Some(later)),
+        (3, "b.a.name", Some("a.name"), Some(later)),
+        (4, "c.name", Some("name"), Some(1000)),
+        (5, "d.name", Some("name"), None),
+    ] {
+        let mint_msg = PnsMintMsg {
+            token_id,
+            domain: domain.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            expires_at,
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    let update_msg = PnsDomainUpdateExpirationMsg {
+        domain: "name".to_string(),
+        expires_at: Some(new_expiration),
+    };
+    let _ = execute_update_expiration(&mock_contract_context(minter), &mut state, &update_msg);
+
+    let expiration = |domain: &str| state.get_domain(domain).unwrap().expires_at;
+    assert_eq!(expiration("name"), Some(new_expiration));
+    assert_eq!(expiration("a.name"), Some(new_expiration));
+    assert_eq!(expiration("b.a.name"), Some(new_expiration));
+    assert_eq!(expiration("c.name"), Some(1000));
+    assert_eq!(expiration("d.name"), None);
+}
+
+#[test]
+fn proper_domain_by_token_id_index() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let mint_msg = PnsMintMsg {
+        token_id: 1,
+        domain: "name".to_string(),
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let (domain_name, domain) = state.get_domain_by_token_id(1).unwrap();
+    assert_eq!(domain_name, "name".to_string());
+    assert_eq!(domain.token_id, 1);
+
+    let burn_msg = PnsBurnMsg {
+        domain: "name".to_string(),
+    };
+
+    let mut nft = mock_nft_state(minter, &[1]);
+    let _ = execute_burn(
+        &mock_contract_context(minter),
+        &mut state,
+        &mut nft,
+        &burn_msg,
+    );
+
+    assert_eq!(state.get_domain_by_token_id(1), None);
+    assert_eq!(state.get_domain("name"), None);
+}
+
+#[test]
+fn proper_subdomains_listing_and_cascading_burn() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    for (token_id, domain, parent_id) in [
+        (1, "name", None),
+        (2, "b.name", Some("name")),
+        (3, "a.name", Some("name")),
+        (4, "c.a.name", Some("a.name")),
+    ] {
+        let mint_msg = PnsMintMsg {
+            token_id,
+            domain: domain.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            expires_at: Some(tomorrow_timestamp()),
+        };
+
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    assert_eq!(state.get_subdomain_count("name"), 2);
+    assert_eq!(
+        state.get_subdomains("name", 0, 10),
+        vec!["a.name".to_string(), "b.name".to_string()]
+    );
+    assert_eq!(
+        state.get_subdomains("name", 1, 1),
+        vec!["b.name".to_string()]
+    );
+    assert_eq!(state.get_subdomains("name", 2, 10), Vec::<String>::new());
+    assert_eq!(
+        state.get_descendants("name"),
+        vec![
+            "a.name".to_string(),
+            "b.name".to_string(),
+            "c.a.name".to_string()
+        ]
+    );
+
+    let burn_msg = PnsBurnMsg {
+        domain: "a.name".to_string(),
+    };
+
+    let mut nft = mock_nft_state(minter, &[1, 2, 3, 4]);
+    let _ = execute_burn(
+        &mock_contract_context(minter),
+        &mut state,
+        &mut nft,
+        &burn_msg,
+    );
+
+    assert_eq!(state.get_domain("c.a.name"), None);
+    assert_eq!(state.get_domain_by_token_id(4), None);
+    assert!(!nft.exists(4));
+    assert!(nft.exists(3));
+    assert!(nft.exists(2));
+    assert_eq!(state.get_subdomain_count("a.name"), 0);
+    assert_eq!(
+        state.get_subdomains("name", 0, 10),
+        vec!["b.name".to_string()]
+    );
+}
+
 #[test]
 fn proper_mint_with_parent() {
     let minter = 1u8;
@@ -111,6 +309,7 @@ fn when_parent_domain_is_expired_subdomain_record_mint_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(minter, &[1, 2]);
 
     let mint_msg = PnsMintMsg {
         token_id: 1,
@@ -147,7 +346,12 @@ fn when_parent_domain_is_expired_subdomain_record_mint_fails() {
         data: string_to_bytes("value"),
     };
 
-    let _ = execute_record_mint(&mock_contract_context(minter), &mut state, &record_mint_msg);
+    let _ = execute_record_mint(
+        &mock_contract_context(minter),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 }
 
 #[test]
@@ -214,12 +418,115 @@ fn mint_fails_when_parent_does_not_exist() {
     let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
 }
 
+#[test]
+fn proper_import() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let import_msg = PnsImportMsg {
+        domain: "name".to_string(),
+        token_id: 7,
+        parent_id: None,
+        minted_at: 1000,
+        expires_at: Some(2000),
+        records: vec![PnsRecordEntry {
+            class: RecordClass::Wallet {},
+            data: string_to_bytes("000000000000000000000000000000000000000001"),
+        }],
+        custom_records: vec![PnsCustomRecordEntry {
+            key: "key".to_string(),
+            data: string_to_bytes("value"),
+        }],
+    };
+    let _ = execute_import(&mock_contract_context(minter), &mut state, &import_msg);
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(domain.token_id, 7);
+    assert_eq!(domain.minted_at, 1000);
+    assert_eq!(domain.expires_at, Some(2000));
+    assert_eq!(
+        *domain.get_record(&RecordClass::Wallet {}).unwrap(),
+        string_to_bytes("000000000000000000000000000000000000000001")
+    );
+    assert_eq!(
+        *domain.get_custom_record("key").unwrap(),
+        string_to_bytes("value")
+    );
+}
+
+#[test]
+#[should_panic(expected = "The wallet record is not a valid address")]
+fn import_fails_when_record_data_is_not_valid() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let import_msg = PnsImportMsg {
+        domain: "name".to_string(),
+        token_id: 7,
+        parent_id: None,
+        minted_at: 1000,
+        expires_at: None,
+        records: vec![PnsRecordEntry {
+            class: RecordClass::Wallet {},
+            data: string_to_bytes("wallet"),
+        }],
+        custom_records: vec![],
+    };
+    let _ = execute_import(&mock_contract_context(minter), &mut state, &import_msg);
+}
+
+#[test]
+#[should_panic(expected = "The maximum amount of custom records has been reached")]
+fn import_fails_over_custom_record_slots() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let import_msg = PnsImportMsg {
+        domain: "name".to_string(),
+        token_id: 7,
+        parent_id: None,
+        minted_at: 1000,
+        expires_at: None,
+        records: vec![],
+        custom_records: (0..=MAX_CUSTOM_RECORDS)
+            .map(|index| PnsCustomRecordEntry {
+                key: format!("key{}", index),
+                data: string_to_bytes("value"),
+            })
+            .collect(),
+    };
+    let _ = execute_import(&mock_contract_context(minter), &mut state, &import_msg);
+}
+
+#[test]
+#[should_panic(expected = "Not found")]
+fn import_fails_when_parent_is_not_imported() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let import_msg = PnsImportMsg {
+        domain: "sub.name".to_string(),
+        token_id: 7,
+        parent_id: Some("name".to_string()),
+        minted_at: 1000,
+        expires_at: None,
+        records: vec![],
+        custom_records: vec![],
+    };
+    let _ = execute_import(&mock_contract_context(minter), &mut state, &import_msg);
+}
+
 #[test]
 fn proper_record_mint() {
     let minter = 1u8;
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let domain = &"name".to_string();
     let mint_msg = PnsMintMsg {
@@ -236,7 +543,12 @@ fn proper_record_mint() {
         class: record_class,
         data: string_to_bytes("data"),
     };
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint_msg);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 
     let domain = state.get_domain(domain).unwrap();
     let record = domain.get_record(&record_class).unwrap();
@@ -250,6 +562,7 @@ fn when_token_not_present_record_mint_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -266,7 +579,12 @@ fn when_token_not_present_record_mint_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -276,6 +594,7 @@ fn record_already_minted_on_record_mint() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -289,11 +608,21 @@ fn record_already_minted_on_record_mint() {
     let record_mint = PnsRecordMintMsg {
         domain: "name".to_string(),
         class: RecordClass::Wallet {},
-        data: string_to_bytes("some data"),
+        data: string_to_bytes("000000000000000000000000000000000000000001"),
     };
 
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -302,6 +631,7 @@ fn proper_record_update() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let domain = &"name".to_string();
     let mint_msg = PnsMintMsg {
@@ -318,23 +648,29 @@ fn proper_record_update() {
         class: record_class,
         data: string_to_bytes("data"),
     };
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint_msg);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 
     let record_update_msg = PnsRecordUpdateMsg {
         domain: domain.clone(),
         class: record_class,
-        data: string_to_bytes("new data"),
+        data: string_to_bytes("new_data"),
     };
 
     let _ = execute_record_update(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_update_msg,
     );
 
     let domain = state.get_domain(domain).unwrap();
     let record = domain.get_record(&record_class).unwrap();
-    assert_eq!(*record, string_to_bytes("new data"));
+    assert_eq!(*record, string_to_bytes("new_data"));
 }
 
 #[test]
@@ -344,6 +680,7 @@ fn when_record_does_not_exist_record_update_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let record_update_msg = PnsRecordUpdateMsg {
         domain: "name".to_string(),
@@ -354,6 +691,7 @@ fn when_record_does_not_exist_record_update_fails() {
     let _ = execute_record_update(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_update_msg,
     );
 }
@@ -365,6 +703,7 @@ fn when_domain_is_expired_record_mint_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -381,7 +720,12 @@ fn when_domain_is_expired_record_mint_actions_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -391,6 +735,7 @@ fn when_domain_is_expired_record_update_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -407,7 +752,12 @@ fn when_domain_is_expired_record_update_actions_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_record_update(&mock_contract_context(alice), &mut state, &record_update);
+    let _ = execute_record_update(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_update,
+    );
 }
 
 #[test]
@@ -417,6 +767,7 @@ fn when_domain_is_expired_record_delete_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -432,7 +783,12 @@ fn when_domain_is_expired_record_delete_actions_fails() {
         class: RecordClass::Wallet {},
     };
 
-    let _ = execute_record_delete(&mock_contract_context(alice), &mut state, &record_delete);
+    let _ = execute_record_delete(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_delete,
+    );
 }
 
 #[test]
@@ -471,6 +827,7 @@ fn proper_custom_record_mint() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let domain = &"name".to_string();
     let mint_msg = PnsMintMsg {
@@ -487,7 +844,12 @@ fn proper_custom_record_mint() {
         key: record_key.clone(),
         data: string_to_bytes("data"),
     };
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint_msg);
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 
     let domain = state.get_domain(domain).unwrap();
     let record = domain.get_custom_record(&record_key).unwrap();
@@ -501,6 +863,7 @@ fn when_token_not_present_custom_record_mint_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -517,7 +880,12 @@ fn when_token_not_present_custom_record_mint_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -527,6 +895,7 @@ fn record_already_minted_on_custom_record_mint() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -543,8 +912,18 @@ fn record_already_minted_on_custom_record_mint() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -554,6 +933,7 @@ fn when_too_many_custom_record_exists_mint_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -570,7 +950,12 @@ fn when_too_many_custom_record_exists_mint_fails() {
             key: format!("key-{}", i),
             data: string_to_bytes("some data"),
         };
-        let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+        let _ = execute_custom_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint,
+        );
     }
 }
 
@@ -580,6 +965,7 @@ fn proper_custom_record_update() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let domain = &"name".to_string();
     let mint_msg = PnsMintMsg {
@@ -596,7 +982,12 @@ fn proper_custom_record_update() {
         key: record_key.clone(),
         data: string_to_bytes("data"),
     };
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint_msg);
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 
     let record_update_msg = PnsCustomRecordUpdateMsg {
         domain: domain.clone(),
@@ -607,6 +998,7 @@ fn proper_custom_record_update() {
     let _ = execute_custom_record_update(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_update_msg,
     );
 
@@ -622,6 +1014,7 @@ fn when_custom_record_does_not_exist_record_update_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let record_update_msg = PnsCustomRecordUpdateMsg {
         domain: "name".to_string(),
@@ -632,6 +1025,7 @@ fn when_custom_record_does_not_exist_record_update_fails() {
     let _ = execute_custom_record_update(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_update_msg,
     );
 }
@@ -643,6 +1037,7 @@ fn when_domain_is_expired_custom_record_mint_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -659,7 +1054,12 @@ fn when_domain_is_expired_custom_record_mint_actions_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_custom_record_mint(&mock_contract_context(alice), &mut state, &record_mint);
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint,
+    );
 }
 
 #[test]
@@ -669,6 +1069,7 @@ fn when_domain_is_expired_custom_record_update_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -685,7 +1086,12 @@ fn when_domain_is_expired_custom_record_update_actions_fails() {
         data: string_to_bytes("some data"),
     };
 
-    let _ = execute_custom_record_update(&mock_contract_context(alice), &mut state, &record_update);
+    let _ = execute_custom_record_update(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_update,
+    );
 }
 
 #[test]
@@ -695,6 +1101,7 @@ fn when_domain_is_expired_custom_record_delete_actions_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let mint_msg = PnsMintMsg {
         domain: "name".to_string(),
@@ -710,7 +1117,12 @@ fn when_domain_is_expired_custom_record_delete_actions_fails() {
         key: "key".to_string(),
     };
 
-    let _ = execute_custom_record_delete(&mock_contract_context(alice), &mut state, &record_delete);
+    let _ = execute_custom_record_delete(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_delete,
+    );
 }
 
 #[test]
@@ -750,6 +1162,7 @@ fn proper_record_delete() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let domain = &"name".to_string();
     let mint_msg = PnsMintMsg {
@@ -766,7 +1179,12 @@ fn proper_record_delete() {
         class: record_class,
         data: string_to_bytes("data"),
     };
-    let _ = execute_record_mint(&mock_contract_context(alice), &mut state, &record_mint_msg);
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
 
     let record_delete_msg = PnsRecordDeleteMsg {
         domain: domain.clone(),
@@ -776,6 +1194,7 @@ fn proper_record_delete() {
     let _ = execute_record_delete(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_delete_msg,
     );
 
@@ -792,6 +1211,7 @@ fn when_record_does_not_exist_record_delete_fails() {
     let alice = 10u8;
 
     let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
 
     let record_delete_msg = PnsRecordDeleteMsg {
         domain: "name".to_string(),
@@ -801,6 +1221,2159 @@ fn when_record_does_not_exist_record_delete_fails() {
     let _ = execute_record_delete(
         &mock_contract_context(alice),
         &mut state,
+        &nft,
         &record_delete_msg,
     );
 }
+
+#[test]
+fn proper_manager_record_mint() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_manager_msg = PnsDomainSetManagerMsg {
+        domain: "name".to_string(),
+        manager: Some(mock_address(bob)),
+    };
+    let _ = execute_set_manager(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_manager_msg,
+    );
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Wallet {},
+        data: string_to_bytes("000000000000000000000000000000000000000001"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(bob),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(domain.manager, Some(mock_address(bob)));
+    assert_eq!(
+        *domain.get_record(&RecordClass::Wallet {}).unwrap(),
+        string_to_bytes("000000000000000000000000000000000000000001")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn record_mint_fails_when_not_owner_nor_manager() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Wallet {},
+        data: string_to_bytes("data"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(bob),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn set_manager_fails_when_not_owner() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_manager_msg = PnsDomainSetManagerMsg {
+        domain: "name".to_string(),
+        manager: Some(mock_address(bob)),
+    };
+    let _ = execute_set_manager(
+        &mock_contract_context(bob),
+        &mut state,
+        &nft,
+        &set_manager_msg,
+    );
+}
+
+#[test]
+fn proper_record_data_validation() {
+    assert!(is_valid_wallet(
+        "000000000000000000000000000000000000000001"
+    ));
+    assert!(is_valid_wallet(
+        "02a1b2c3d4e5f60718293a4b5c6d7e8f9012345678"
+    ));
+    assert!(is_valid_wallet(
+        "60:0x71C7656EC7ab88b098defB751B7401B5f6d8976F"
+    ));
+    assert!(!is_valid_wallet("0000000000000000000000000000000000000001"));
+    assert!(!is_valid_wallet(
+        "090000000000000000000000000000000000000001"
+    ));
+    assert!(!is_valid_wallet(
+        "eth:0x71C7656EC7ab88b098defB751B7401B5f6d8976F"
+    ));
+    assert!(!is_valid_wallet("60:"));
+    assert!(!is_valid_wallet(
+        "60:0x71C7656EC7ab88b098defB751B7401B5f6d8976"
+    ));
+    assert!(!is_valid_wallet("0:abc123"));
+    assert!(!is_valid_wallet(
+        "999:0x71C7656EC7ab88b098defB751B7401B5f6d8976F"
+    ));
+
+    assert!(is_valid_email("alice@metanames.app"));
+    assert!(is_valid_email("alice.bob+mpc@mail.metanames.app"));
+    assert!(!is_valid_email("alice"));
+    assert!(!is_valid_email("alice@metanames"));
+    assert!(!is_valid_email(".alice@metanames.app"));
+    assert!(!is_valid_email("alice@-metanames.app"));
+
+    assert!(is_valid_uri("https://metanames.app"));
+    assert!(is_valid_uri(
+        "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+    ));
+    assert!(!is_valid_uri("javascript://alert(1)"));
+    assert!(!is_valid_uri("https://"));
+    assert!(!is_valid_uri("https://meta names.app"));
+
+    assert!(is_valid_twitter_handle("meta_names"));
+    assert!(!is_valid_twitter_handle("@meta_names"));
+    assert!(!is_valid_twitter_handle("a_very_long_handle"));
+
+    assert!(is_valid_discord_username("meta.names"));
+    assert!(!is_valid_discord_username("Meta"));
+    assert!(!is_valid_discord_username("meta..names"));
+    assert!(!is_valid_discord_username("m"));
+
+    assert!(is_valid_avatar("https://metanames.app/avatar.png"));
+    assert!(is_valid_avatar(
+        "02a1b2c3d4e5f60718293a4b5c6d7e8f9012345678/42"
+    ));
+    assert!(!is_valid_avatar(
+        "00a1b2c3d4e5f60718293a4b5c6d7e8f9012345678/42"
+    ));
+    assert!(!is_valid_avatar("avatar.png"));
+
+    validate_record_data(&RecordClass::Bio {}, &string_to_bytes("any bio"));
+}
+
+#[test]
+#[should_panic(expected = "The wallet record is not a valid address")]
+fn record_mint_fails_when_wallet_is_not_valid() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Wallet {},
+        data: string_to_bytes("not a wallet"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+}
+
+#[test]
+#[should_panic(expected = "The uri record is not valid or its scheme is not allowed")]
+fn record_update_fails_when_uri_scheme_is_not_allowed() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Uri {},
+        data: string_to_bytes("https://metanames.app"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let record_update_msg = PnsRecordUpdateMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Uri {},
+        data: string_to_bytes("ftp://metanames.app"),
+    };
+    let _ = execute_record_update(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_update_msg,
+    );
+}
+
+#[test]
+fn proper_coin_address_encoding() {
+    let addresses = [
+        (COIN_TYPE_BITCOIN, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+        (COIN_TYPE_BITCOIN, "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"),
+        (
+            COIN_TYPE_BITCOIN,
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+        ),
+        (
+            COIN_TYPE_BITCOIN,
+            "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297",
+        ),
+        (
+            COIN_TYPE_ETHEREUM,
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+        ),
+        (
+            COIN_TYPE_SOLANA,
+            "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T",
+        ),
+        (
+            COIN_TYPE_PARTISIA,
+            "000000000000000000000000000000000000000001",
+        ),
+    ];
+
+    for (coin_type, address) in addresses {
+        let encoded = encode_coin_address(coin_type, address).unwrap();
+        assert_eq!(
+            decode_coin_address(coin_type, &encoded),
+            Some(address.to_string())
+        );
+    }
+
+    // P2PKH output script
+    assert_eq!(
+        encode_coin_address(COIN_TYPE_BITCOIN, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+        Some(vec![
+            0x76, 0xa9, 0x14, 0x62, 0xe9, 0x07, 0xb1, 0x5c, 0xbf, 0x27, 0xd5, 0x42, 0x53, 0x99,
+            0xeb, 0xf6, 0xf0, 0xfb, 0x50, 0xeb, 0xb8, 0x8f, 0x18, 0x88, 0xac
+        ])
+    );
+
+    // Ethereum addresses without a checksum are decoded with it
+    let encoded = encode_coin_address(
+        COIN_TYPE_ETHEREUM,
+        "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+    )
+    .unwrap();
+    assert_eq!(
+        decode_coin_address(COIN_TYPE_ETHEREUM, &encoded),
+        Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string())
+    );
+
+    // Bad checksums
+    assert!(encode_coin_address(COIN_TYPE_BITCOIN, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").is_none());
+    assert!(encode_coin_address(
+        COIN_TYPE_ETHEREUM,
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"
+    )
+    .is_none());
+    assert!(encode_coin_address(
+        COIN_TYPE_BITCOIN,
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdp"
+    )
+    .is_none());
+
+    // Bad lengths
+    assert!(encode_coin_address(
+        COIN_TYPE_ETHEREUM,
+        "0x52908400098527886e0f7030069857d2e4169e"
+    )
+    .is_none());
+    assert!(encode_coin_address(COIN_TYPE_SOLANA, "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4").is_none());
+    assert!(decode_coin_address(COIN_TYPE_PARTISIA, &[0; 20]).is_none());
+
+    // Unsupported coin type
+    assert!(encode_coin_address(2, "LM2WMpR1Rp6j3Sa59cMXMs1SPzj9eXpGc1").is_none());
+}
+
+#[test]
+fn proper_address_mint_update_delete() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let eth_address = "0x52908400098527886E0F7030069857D2E4169EE7";
+    let new_eth_address = "0xde0B295669a9FD93d5F28D9Ec85E40f4cb697BAe";
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let address_mint_msg = PnsAddressMintMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+        address: eth_address.to_string(),
+    };
+    let _ = execute_address_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_mint_msg,
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(domain.get_address(COIN_TYPE_ETHEREUM).unwrap().len(), 20);
+    assert_eq!(
+        state.resolve_address("name", COIN_TYPE_ETHEREUM, unix_epoch_now()),
+        Some(eth_address.to_string())
+    );
+    assert_eq!(
+        state.resolve_address("name", COIN_TYPE_BITCOIN, unix_epoch_now()),
+        None
+    );
+
+    let address_update_msg = PnsAddressUpdateMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+        address: new_eth_address.to_string(),
+    };
+    let _ = execute_address_update(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_update_msg,
+    );
+    assert_eq!(
+        state.resolve_address("name", COIN_TYPE_ETHEREUM, unix_epoch_now()),
+        Some(new_eth_address.to_string())
+    );
+
+    let address_delete_msg = PnsAddressDeleteMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+    };
+    let _ = execute_address_delete(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_delete_msg,
+    );
+    assert_eq!(
+        state.resolve_address("name", COIN_TYPE_ETHEREUM, unix_epoch_now()),
+        None
+    );
+}
+
+#[test]
+#[should_panic(expected = "The address is not valid for the coin type")]
+fn address_mint_fails_when_address_is_not_valid() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let address_mint_msg = PnsAddressMintMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_SOLANA,
+        address: "0x52908400098527886e0f7030069857d2e4169ee7".to_string(),
+    };
+    let _ = execute_address_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_mint_msg,
+    );
+}
+
+#[test]
+#[should_panic(expected = "The coin type is not supported")]
+fn address_mint_fails_when_coin_type_is_not_supported() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let address_mint_msg = PnsAddressMintMsg {
+        domain: "name".to_string(),
+        coin_type: 2,
+        address: "LM2WMpR1Rp6j3Sa59cMXMs1SPzj9eXpGc1".to_string(),
+    };
+    let _ = execute_address_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_mint_msg,
+    );
+}
+
+#[test]
+fn proper_content_hash_encoding() {
+    // CIDv0 are stored as CIDv1 and resolved in base32
+    let ipfs_hash = encode_content_hash("ipfs://QmRAQB6YaCyidP37UdDnjFY5vQuiBrcqdyoW1CuDgwxkD4");
+    assert_eq!(
+        ipfs_hash,
+        Some(vec![
+            0xe3, 0x01, 0x01, 0x70, 0x12, 0x20, 0x29, 0xf2, 0xd1, 0x7b, 0xe6, 0x13, 0x90, 0x79,
+            0xdc, 0x48, 0x69, 0x6d, 0x1f, 0x58, 0x2a, 0x85, 0x30, 0xeb, 0x98, 0x05, 0xb5, 0x61,
+            0xed, 0xa5, 0x17, 0xe2, 0x2a, 0x89, 0x2c, 0x7e, 0x3f, 0x1f
+        ])
+    );
+    assert_eq!(
+        decode_content_hash(&ipfs_hash.unwrap()),
+        Some("ipfs://bafybeibj6lixxzqtsb45ysdjnupvqkufgdvzqbnvmhw2kf7cfkesy7r7d4".to_string())
+    );
+
+    let content_hashes = [
+        "ipfs://bafybeibj6lixxzqtsb45ysdjnupvqkufgdvzqbnvmhw2kf7cfkesy7r7d4",
+        "ipns://k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8",
+        "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U",
+    ];
+    for content_hash in content_hashes {
+        let encoded = encode_content_hash(content_hash).unwrap();
+        assert_eq!(
+            decode_content_hash(&encoded),
+            Some(content_hash.to_string())
+        );
+    }
+
+    assert!(encode_content_hash("https://metanames.app").is_none());
+    assert!(encode_content_hash("ipfs://QmRAQB6YaCyidP37UdDnjFY5vQuiBrcqdyoW1CuDgwx").is_none());
+    // Trailing bits of the arweave transaction id are not zero
+    assert!(encode_content_hash("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_V").is_none());
+    // An IPNS name is not an IPFS content
+    assert!(encode_content_hash(
+        "ipfs://k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8"
+    )
+    .is_none());
+    assert!(decode_content_hash(&[0xe3, 0x01, 0x01, 0x70]).is_none());
+}
+
+#[test]
+fn proper_content_hash_record_mint() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let content_hash = "ipfs://bafybeibj6lixxzqtsb45ysdjnupvqkufgdvzqbnvmhw2kf7cfkesy7r7d4";
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::ContentHash {},
+        data: string_to_bytes(content_hash),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    let record = domain.get_record(&RecordClass::ContentHash {}).unwrap();
+    assert_eq!(record.len(), 38);
+    assert_eq!(
+        state.resolve_content_hash("name", unix_epoch_now()),
+        Some(content_hash.to_string())
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "The content hash record is not a valid ipfs, ipns or arweave identifier"
+)]
+fn record_mint_fails_when_content_hash_is_not_valid() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::ContentHash {},
+        data: string_to_bytes("https://metanames.app"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+}
+
+#[test]
+fn proper_record_limits_validation() {
+    let class_limit = |data_length_limit| RecordClassLimit {
+        class: RecordClass::Bio {},
+        data_length_limit,
+    };
+
+    assert!(RecordLimits::default().is_valid());
+    assert!(RecordLimits {
+        data_length_limit: MAX_RECORD_DATA_LENGTH_LIMIT,
+        class_data_length_limits: vec![class_limit(MAX_RECORD_DATA_LENGTH_LIMIT)],
+        custom_record_slots: MAX_CUSTOM_RECORD_SLOTS_LIMIT,
+    }
+    .is_valid());
+
+    for invalid_limits in [
+        RecordLimits {
+            data_length_limit: 0,
+            ..RecordLimits::default()
+        },
+        RecordLimits {
+            data_length_limit: MAX_RECORD_DATA_LENGTH_LIMIT + 1,
+            ..RecordLimits::default()
+        },
+        RecordLimits {
+            class_data_length_limits: vec![class_limit(0)],
+            ..RecordLimits::default()
+        },
+        RecordLimits {
+            class_data_length_limits: vec![class_limit(MAX_RECORD_DATA_LENGTH_LIMIT + 1)],
+            ..RecordLimits::default()
+        },
+        RecordLimits {
+            custom_record_slots: MAX_CUSTOM_RECORD_SLOTS_LIMIT + 1,
+            ..RecordLimits::default()
+        },
+    ] {
+        assert!(!invalid_limits.is_valid());
+    }
+}
+
+#[test]
+fn proper_record_limits_with_extra_capacity() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+    state.record_limits = RecordLimits {
+        data_length_limit: 16,
+        class_data_length_limits: vec![RecordClassLimit {
+            class: RecordClass::Bio {},
+            data_length_limit: 128,
+        }],
+        custom_record_slots: 1,
+    };
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        state
+            .record_limits
+            .get_data_length_limit(&domain, &RecordClass::Bio {}),
+        128
+    );
+    assert_eq!(
+        state
+            .record_limits
+            .get_data_length_limit(&domain, &RecordClass::Twitter {}),
+        16
+    );
+    assert_eq!(state.record_limits.get_custom_record_slots(&domain), 1);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: vec![b'a'; 100],
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let capacity_msg = PnsAddRecordCapacityMsg {
+        domain: "name".to_string(),
+        extra_custom_record_slots: 2,
+        extra_data_length: 8,
+    };
+    let _ = execute_add_record_capacity(&mock_contract_context(minter), &mut state, &capacity_msg);
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        state.record_limits.get_custom_data_length_limit(&domain),
+        24
+    );
+    assert_eq!(state.record_limits.get_custom_record_slots(&domain), 3);
+
+    for i in 0..3 {
+        let record_mint = PnsCustomRecordMintMsg {
+            domain: "name".to_string(),
+            key: format!("key-{}", i),
+            data: vec![b'a'; 20],
+        };
+        let _ = execute_custom_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint,
+        );
+    }
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(domain.custom_records.len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "The record data is too long")]
+fn record_mint_fails_when_data_is_longer_than_class_limit() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+    state.record_limits.class_data_length_limits = vec![RecordClassLimit {
+        class: RecordClass::Bio {},
+        data_length_limit: 8,
+    }];
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("a longer bio"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+}
+
+#[test]
+fn proper_set_records() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Twitter {},
+        data: string_to_bytes("meta_names"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let custom_record_mint_msg = PnsCustomRecordMintMsg {
+        domain: "name".to_string(),
+        key: "key".to_string(),
+        data: string_to_bytes("data"),
+    };
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &custom_record_mint_msg,
+    );
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Bio {},
+                data: string_to_bytes("bio"),
+            },
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Bio {},
+                data: string_to_bytes("new bio"),
+            },
+            PnsRecordOperation::Delete {
+                class: RecordClass::Twitter {},
+            },
+            PnsRecordOperation::UpsertCustom {
+                key: "other-key".to_string(),
+                data: string_to_bytes("other data"),
+            },
+            PnsRecordOperation::DeleteCustom {
+                key: "key".to_string(),
+            },
+        ],
+    };
+    let events = execute_set_records(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_records_msg,
+    );
+    assert_eq!(events.len(), 1);
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        domain.get_record(&RecordClass::Bio {}),
+        Some(&string_to_bytes("new bio"))
+    );
+    assert!(!domain.is_record_minted(&RecordClass::Twitter {}));
+    assert_eq!(
+        domain.get_custom_record("other-key"),
+        Some(&string_to_bytes("other data"))
+    );
+    assert!(!domain.is_custom_record_minted("key"));
+}
+
+#[test]
+#[should_panic(expected = "Not found")]
+fn set_records_fails_when_deleted_record_does_not_exist() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Bio {},
+                data: string_to_bytes("bio"),
+            },
+            PnsRecordOperation::Delete {
+                class: RecordClass::Twitter {},
+            },
+        ],
+    };
+    let _ = execute_set_records(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_records_msg,
+    );
+}
+
+#[test]
+fn proper_record_history() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("first"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+    assert_eq!(
+        state.get_record_history("name", &RecordClass::Bio {}),
+        vec![]
+    );
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: 2,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+
+    for data in ["second", "third"] {
+        let record_update_msg = PnsRecordUpdateMsg {
+            domain: "name".to_string(),
+            class: RecordClass::Bio {},
+            data: string_to_bytes(data),
+        };
+        let _ = execute_record_update(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_update_msg,
+        );
+    }
+
+    let record_delete_msg = PnsRecordDeleteMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+    };
+    let _ = execute_record_delete(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_delete_msg,
+    );
+
+    let history = state.get_record_history("name", &RecordClass::Bio {});
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].data, Some(string_to_bytes("third")));
+    assert_eq!(history[0].writer, mock_address(alice));
+    assert_eq!(history[1].data, None);
+
+    let custom_record_mint_msg = PnsCustomRecordMintMsg {
+        domain: "name".to_string(),
+        key: "key".to_string(),
+        data: string_to_bytes("value"),
+    };
+    let _ = execute_custom_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &custom_record_mint_msg,
+    );
+    assert_eq!(state.get_custom_record_history("name", "key").len(), 1);
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: 1,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+    let history = state.get_record_history("name", &RecordClass::Bio {});
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].data, None);
+
+    let record_retain_msg = PnsRecordRetainMsg {
+        domain: "name".to_string(),
+        retention: PnsRecordRetention::KeepAll {},
+    };
+    let _ = execute_record_retain(
+        &mock_contract_context(minter),
+        &mut state,
+        &record_retain_msg,
+    );
+    assert_eq!(
+        state.get_record_history("name", &RecordClass::Bio {}),
+        vec![]
+    );
+    assert_eq!(state.get_custom_record_history("name", "key"), vec![]);
+}
+
+#[test]
+fn record_retain_keeps_only_the_listed_records_and_addresses() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    for class in [RecordClass::Bio {}, RecordClass::Twitter {}] {
+        let record_mint_msg = PnsRecordMintMsg {
+            domain: "name".to_string(),
+            class,
+            data: string_to_bytes("meta_names"),
+        };
+        let _ = execute_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint_msg,
+        );
+    }
+
+    for (coin_type, address) in [
+        (COIN_TYPE_BITCOIN, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+        (
+            COIN_TYPE_ETHEREUM,
+            "0x52908400098527886e0f7030069857d2e4169ee7",
+        ),
+    ] {
+        let address_mint_msg = PnsAddressMintMsg {
+            domain: "name".to_string(),
+            coin_type,
+            address: address.to_string(),
+        };
+        let _ = execute_address_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &address_mint_msg,
+        );
+    }
+
+    let record_retain_msg = PnsRecordRetainMsg {
+        domain: "name".to_string(),
+        retention: PnsRecordRetention::KeepOnly {
+            classes: vec![RecordClass::Twitter {}],
+            custom_keys: vec![],
+            coin_types: vec![COIN_TYPE_ETHEREUM],
+        },
+    };
+    let _ = execute_record_retain(
+        &mock_contract_context(minter),
+        &mut state,
+        &record_retain_msg,
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    assert!(domain.is_record_minted(&RecordClass::Twitter {}));
+    assert!(!domain.is_record_minted(&RecordClass::Bio {}));
+    assert!(domain.is_address_minted(COIN_TYPE_ETHEREUM));
+    assert!(!domain.is_address_minted(COIN_TYPE_BITCOIN));
+}
+
+#[test]
+fn proper_address_history() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let eth_address = "0x52908400098527886E0F7030069857D2E4169EE7";
+    let new_eth_address = "0xde0B295669a9FD93d5F28D9Ec85E40f4cb697BAe";
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: 2,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+
+    let address_mint_msg = PnsAddressMintMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+        address: eth_address.to_string(),
+    };
+    let _ = execute_address_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_mint_msg,
+    );
+    assert_eq!(
+        state.get_address_history("name", COIN_TYPE_ETHEREUM).len(),
+        1
+    );
+
+    let address_update_msg = PnsAddressUpdateMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+        address: new_eth_address.to_string(),
+    };
+    let _ = execute_address_update(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_update_msg,
+    );
+
+    let address_delete_msg = PnsAddressDeleteMsg {
+        domain: "name".to_string(),
+        coin_type: COIN_TYPE_ETHEREUM,
+    };
+    let _ = execute_address_delete(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &address_delete_msg,
+    );
+
+    let history = state.get_address_history("name", COIN_TYPE_ETHEREUM);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history[0].data,
+        encode_coin_address(COIN_TYPE_ETHEREUM, new_eth_address)
+    );
+    assert_eq!(history[0].writer, mock_address(alice));
+    assert_eq!(history[1].data, None);
+    assert_eq!(state.get_address_history("name", COIN_TYPE_BITCOIN), vec![]);
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: 1,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+    let history = state.get_address_history("name", COIN_TYPE_ETHEREUM);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].data, None);
+}
+
+#[test]
+fn record_history_is_pruned_on_burn() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let mut nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: 5,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("bio"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+    assert_eq!(
+        state.get_record_history("name", &RecordClass::Bio {}).len(),
+        1
+    );
+
+    let burn_msg = PnsBurnMsg {
+        domain: "name".to_string(),
+    };
+    let _ = execute_burn(
+        &mock_contract_context(minter),
+        &mut state,
+        &mut nft,
+        &burn_msg,
+    );
+
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    assert_eq!(
+        state.get_record_history("name", &RecordClass::Bio {}),
+        vec![]
+    );
+}
+
+#[test]
+#[should_panic(expected = "The record history size is over the maximum")]
+fn set_record_history_size_fails_over_maximum() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let history_size_msg = PnsSetRecordHistorySizeMsg {
+        domain: "name".to_string(),
+        size: MAX_RECORD_HISTORY_SIZE + 1,
+    };
+    let _ = execute_set_record_history_size(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &history_size_msg,
+    );
+}
+
+#[test]
+fn proper_record_ttl_and_expiry() {
+    let minter = 1u8;
+    let alice = 10u8;
+    let bob = 11u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Bio {},
+                data: string_to_bytes("bio"),
+            },
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Custom {},
+                data: string_to_bytes("custom"),
+            },
+            PnsRecordOperation::UpsertCustom {
+                key: "key".to_string(),
+                data: string_to_bytes("value"),
+            },
+        ],
+    };
+    let _ = execute_set_records(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_records_msg,
+    );
+
+    let record_expires_at = unix_epoch_now() + 60;
+    for target in [
+        PnsRecordTarget::Record {
+            class: RecordClass::Custom {},
+        },
+        PnsRecordTarget::CustomRecord {
+            key: "key".to_string(),
+        },
+    ] {
+        let ttl_msg = PnsSetRecordTtlMsg {
+            domain: "name".to_string(),
+            target,
+            ttl: RecordTtl {
+                ttl: Some(300),
+                expires_at: Some(record_expires_at),
+            },
+        };
+        let _ = execute_set_record_ttl(&mock_contract_context(alice), &mut state, &nft, &ttl_msg);
+    }
+
+    assert_eq!(
+        state.get_record_ttl_hint("name", &RecordClass::Custom {}, unix_epoch_now()),
+        Some(300)
+    );
+    assert_eq!(
+        state.resolve_record("name", &RecordClass::Custom {}, unix_epoch_now()),
+        Some(string_to_bytes("custom"))
+    );
+    assert_eq!(
+        state.resolve_record("name", &RecordClass::Custom {}, record_expires_at + 1),
+        None
+    );
+    assert_eq!(
+        state.resolve_custom_record("name", "key", record_expires_at + 1),
+        None
+    );
+    assert_eq!(
+        state.resolve_record("name", &RecordClass::Bio {}, record_expires_at + 1),
+        Some(string_to_bytes("bio"))
+    );
+
+    let mut prune_ctx = mock_contract_context(bob);
+    prune_ctx.block_production_time = record_expires_at + 1;
+    let _ = execute_prune_expired_records(
+        &prune_ctx,
+        &mut state,
+        &PnsPruneExpiredRecordsMsg {
+            domain: "name".to_string(),
+        },
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    assert!(!domain.is_record_minted(&RecordClass::Custom {}));
+    assert!(!domain.is_custom_record_minted("key"));
+    assert!(domain.is_record_minted(&RecordClass::Bio {}));
+    assert_eq!(domain.get_record_ttl(&RecordClass::Custom {}), None);
+}
+
+#[test]
+fn updating_an_expired_record_clears_its_expiration() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![
+            PnsRecordOperation::Upsert {
+                class: RecordClass::Bio {},
+                data: string_to_bytes("bio"),
+            },
+            PnsRecordOperation::UpsertCustom {
+                key: "key".to_string(),
+                data: string_to_bytes("value"),
+            },
+        ],
+    };
+    let _ = execute_set_records(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_records_msg,
+    );
+
+    let record_expires_at = unix_epoch_now() + 60;
+    for target in [
+        PnsRecordTarget::Record {
+            class: RecordClass::Bio {},
+        },
+        PnsRecordTarget::CustomRecord {
+            key: "key".to_string(),
+        },
+    ] {
+        let ttl_msg = PnsSetRecordTtlMsg {
+            domain: "name".to_string(),
+            target,
+            ttl: RecordTtl {
+                ttl: Some(300),
+                expires_at: Some(record_expires_at),
+            },
+        };
+        let _ = execute_set_record_ttl(&mock_contract_context(alice), &mut state, &nft, &ttl_msg);
+    }
+
+    let mut update_ctx = mock_contract_context(alice);
+    update_ctx.block_production_time = record_expires_at + 1;
+    let record_update_msg = PnsRecordUpdateMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("new bio"),
+    };
+    let _ = execute_record_update(&update_ctx, &mut state, &nft, &record_update_msg);
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![PnsRecordOperation::UpsertCustom {
+            key: "key".to_string(),
+            data: string_to_bytes("new value"),
+        }],
+    };
+    let _ = execute_set_records(&update_ctx, &mut state, &nft, &set_records_msg);
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        domain.get_record_ttl(&RecordClass::Bio {}),
+        Some(&RecordTtl {
+            ttl: Some(300),
+            expires_at: None,
+        })
+    );
+    assert_eq!(
+        domain.get_custom_record_ttl("key"),
+        Some(&RecordTtl {
+            ttl: Some(300),
+            expires_at: None,
+        })
+    );
+
+    let _ = execute_prune_expired_records(
+        &update_ctx,
+        &mut state,
+        &PnsPruneExpiredRecordsMsg {
+            domain: "name".to_string(),
+        },
+    );
+    assert_eq!(
+        state.resolve_record("name", &RecordClass::Bio {}, record_expires_at + 1),
+        Some(string_to_bytes("new bio"))
+    );
+    assert_eq!(
+        state.resolve_custom_record("name", "key", record_expires_at + 1),
+        Some(string_to_bytes("new value"))
+    );
+}
+
+#[test]
+#[should_panic(expected = "The record expiration must be in the future")]
+fn set_record_ttl_fails_when_expiration_is_in_the_past() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("bio"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let ttl_msg = PnsSetRecordTtlMsg {
+        domain: "name".to_string(),
+        target: PnsRecordTarget::Record {
+            class: RecordClass::Bio {},
+        },
+        ttl: RecordTtl {
+            ttl: None,
+            expires_at: Some(yesterday_timestamp()),
+        },
+    };
+    let _ = execute_set_record_ttl(&mock_contract_context(alice), &mut state, &nft, &ttl_msg);
+}
+
+#[test]
+fn proper_dns_record_encoding() {
+    assert!(is_valid_ipv4("192.0.2.1"));
+    assert!(!is_valid_ipv4("192.0.2.01"));
+    assert!(!is_valid_ipv4("192.0.2"));
+    assert!(is_valid_ipv6("2001:db8::1"));
+    assert!(!is_valid_ipv6("2001:db8::g"));
+    assert!(is_valid_hostname("mail.example.com."));
+    assert!(!is_valid_hostname("-mail.example.com"));
+    assert!(!is_valid_hostname("mail..example.com"));
+    assert!(!is_valid_hostname(&"a".repeat(64)));
+
+    let a = encode_dns_record(&RecordClass::DnsA {}, "192.0.2.1,192.0.2.2").unwrap();
+    assert_eq!(a, vec![192, 0, 2, 1, 192, 0, 2, 2]);
+    assert_eq!(
+        decode_dns_record(&RecordClass::DnsA {}, &a),
+        Some("192.0.2.1,192.0.2.2".to_string())
+    );
+
+    let mx = encode_dns_record(&RecordClass::DnsMx {}, "10 Mail.Example.com").unwrap();
+    assert_eq!(
+        decode_dns_record(&RecordClass::DnsMx {}, &mx),
+        Some("10 mail.example.com".to_string())
+    );
+    assert_eq!(
+        encode_dns_record(&RecordClass::DnsMx {}, "010 mail.example.com"),
+        None
+    );
+    assert_eq!(
+        encode_dns_record(&RecordClass::DnsTxt {}, &"a".repeat(256)),
+        None
+    );
+    assert_eq!(
+        encode_dns_record(&RecordClass::DnsCname {}, "not_a_host.com"),
+        None
+    );
+}
+
+#[test]
+fn proper_dns_export() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let set_records_msg = PnsSetRecordsMsg {
+        domain: "name".to_string(),
+        operations: vec![
+            PnsRecordOperation::Upsert {
+                class: RecordClass::DnsA {},
+                data: string_to_bytes("192.0.2.1"),
+            },
+            PnsRecordOperation::Upsert {
+                class: RecordClass::DnsMx {},
+                data: string_to_bytes("10 mail.example.com"),
+            },
+            PnsRecordOperation::Upsert {
+                class: RecordClass::DnsTxt {},
+                data: string_to_bytes("v=spf1 -all"),
+            },
+        ],
+    };
+    let _ = execute_set_records(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &set_records_msg,
+    );
+
+    let ttl_msg = PnsSetRecordTtlMsg {
+        domain: "name".to_string(),
+        target: PnsRecordTarget::Record {
+            class: RecordClass::DnsA {},
+        },
+        ttl: RecordTtl {
+            ttl: Some(60),
+            expires_at: None,
+        },
+    };
+    let _ = execute_set_record_ttl(&mock_contract_context(alice), &mut state, &nft, &ttl_msg);
+
+    assert_eq!(
+        state.resolve_dns_record("name", &RecordClass::DnsA {}, unix_epoch_now()),
+        Some("192.0.2.1".to_string())
+    );
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        export_zone_file("name.mpc", &domain, unix_epoch_now()),
+        Some(
+            "name.mpc. 60 IN A 192.0.2.1\n\
+             name.mpc. 3600 IN MX 10 mail.example.com.\n\
+             name.mpc. 3600 IN TXT \"v=spf1 -all\"\n"
+                .to_string()
+        )
+    );
+
+    let wire = export_wire_format("name.mpc", &domain, unix_epoch_now()).unwrap();
+    assert_eq!(
+        wire[..24],
+        [
+            4, b'n', b'a', b'm', b'e', 3, b'm', b'p', b'c', 0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 192,
+            0, 2, 1
+        ]
+    );
+    assert_eq!(
+        export_zone_file("-name.mpc", &domain, unix_epoch_now()),
+        None
+    );
+}
+
+#[test]
+#[should_panic(expected = "The A record is not a list of valid IPv4 addresses")]
+fn record_mint_fails_when_dns_a_record_is_not_an_ipv4_address() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "name".to_string(),
+        class: RecordClass::DnsA {},
+        data: string_to_bytes("256.0.0.1"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+}
+
+#[test]
+fn proper_longest_dns_records_mint() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let hostname = [
+        "a".repeat(63),
+        "b".repeat(63),
+        "c".repeat(63),
+        "d".repeat(61),
+    ]
+    .join(".");
+    let ipv6_addresses = vec!["2001:db8::1"; MAX_DNS_ADDRESSES].join(",");
+    let records = [
+        (RecordClass::DnsTxt {}, "t".repeat(MAX_TXT_LEN)),
+        (RecordClass::DnsAaaa {}, ipv6_addresses),
+        (RecordClass::DnsCname {}, hostname.clone()),
+        (RecordClass::DnsMx {}, format!("10 {}", hostname)),
+    ];
+    for (class, text) in &records {
+        let record_mint_msg = PnsRecordMintMsg {
+            domain: "name".to_string(),
+            class: *class,
+            data: string_to_bytes(text),
+        };
+        let _ = execute_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint_msg,
+        );
+    }
+
+    let domain = state.get_domain("name").unwrap();
+    assert_eq!(
+        domain.get_record(&RecordClass::DnsTxt {}).unwrap().len(),
+        1 + MAX_TXT_LEN
+    );
+    assert_eq!(
+        state.resolve_dns_record("name", &RecordClass::DnsTxt {}, unix_epoch_now()),
+        Some("t".repeat(MAX_TXT_LEN))
+    );
+    assert_eq!(
+        state.resolve_dns_record("name", &RecordClass::DnsCname {}, unix_epoch_now()),
+        Some(hostname)
+    );
+}
+
+#[test]
+fn proper_wildcard_resolution() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1, 2]);
+
+    let mint_msgs = [
+        PnsMintMsg {
+            domain: "project".to_string(),
+            token_id: 1,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        },
+        PnsMintMsg {
+            domain: "team.project".to_string(),
+            token_id: 2,
+            parent_id: Some("project".to_string()),
+            expires_at: None,
+        },
+    ];
+    for mint_msg in &mint_msgs {
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, mint_msg);
+
+        let record_mint_msg = PnsRecordMintMsg {
+            domain: mint_msg.domain.clone(),
+            class: RecordClass::Bio {},
+            data: string_to_bytes(&mint_msg.domain),
+        };
+        let _ = execute_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint_msg,
+        );
+    }
+
+    let now = unix_epoch_now();
+    assert!(!state.is_resolvable("any.project", now));
+
+    let wildcard_msg = PnsSetWildcardMsg {
+        domain: "project".to_string(),
+        enabled: true,
+    };
+    let _ = execute_set_wildcard(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &wildcard_msg,
+    );
+
+    assert_eq!(
+        state.resolve_record("any.project", &RecordClass::Bio {}, now),
+        Some(string_to_bytes("project"))
+    );
+    assert_eq!(
+        state.resolve_record("any.team.project", &RecordClass::Bio {}, now),
+        Some(string_to_bytes("project"))
+    );
+    assert_eq!(
+        state.resolve_record("team.project", &RecordClass::Bio {}, now),
+        Some(string_to_bytes("team.project"))
+    );
+    assert!(!state.is_resolvable("any.other", now));
+    assert!(!state.is_resolvable("any.project", tomorrow_timestamp() + 1));
+
+    let wildcard_msg = PnsSetWildcardMsg {
+        domain: "project".to_string(),
+        enabled: false,
+    };
+    let _ = execute_set_wildcard(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &wildcard_msg,
+    );
+    assert_eq!(
+        state.resolve_record("any.project", &RecordClass::Bio {}, now),
+        None
+    );
+}
+
+#[test]
+fn proper_alias_resolution() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1, 2, 3]);
+
+    for (token_id, domain) in [(1, "old"), (2, "new"), (3, "newest")] {
+        let mint_msg = PnsMintMsg {
+            domain: domain.to_string(),
+            token_id,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        };
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+        let record_mint_msg = PnsRecordMintMsg {
+            domain: domain.to_string(),
+            class: RecordClass::Bio {},
+            data: string_to_bytes(domain),
+        };
+        let _ = execute_record_mint(
+            &mock_contract_context(alice),
+            &mut state,
+            &nft,
+            &record_mint_msg,
+        );
+    }
+
+    for (domain, target) in [("old", "new"), ("new", "newest")] {
+        let alias_msg = PnsSetAliasMsg {
+            domain: domain.to_string(),
+            target: Some(target.to_string()),
+        };
+        let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+    }
+
+    let now = unix_epoch_now();
+    let resolution = state.resolve_alias("old", now);
+    assert_eq!(resolution.target, Some("newest".to_string()));
+    assert_eq!(resolution.chain, vec!["old", "new", "newest"]);
+    assert_eq!(
+        state.resolve_record("old", &RecordClass::Bio {}, now),
+        Some(string_to_bytes("newest"))
+    );
+    assert_eq!(state.get_domain("old").unwrap().token_id, 1);
+
+    // Closing the loop breaks the resolution of every domain of the chain
+    let alias_msg = PnsSetAliasMsg {
+        domain: "newest".to_string(),
+        target: Some("old".to_string()),
+    };
+    let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+
+    let resolution = state.resolve_alias("old", now);
+    assert_eq!(resolution.target, None);
+    assert_eq!(resolution.chain, vec!["old", "new", "newest"]);
+    assert!(!state.is_resolvable("new", now));
+
+    let alias_msg = PnsSetAliasMsg {
+        domain: "newest".to_string(),
+        target: None,
+    };
+    let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+    assert_eq!(
+        state.resolve_alias("old", now).target,
+        Some("newest".to_string())
+    );
+}
+
+#[test]
+fn alias_resolution_stops_at_maximum_depth() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let token_ids: Vec<u128> = (1..=MAX_ALIAS_DEPTH as u128 + 2).collect();
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &token_ids);
+
+    for token_id in &token_ids {
+        let mint_msg = PnsMintMsg {
+            domain: format!("name{}", token_id),
+            token_id: *token_id,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        };
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+    for token_id in &token_ids[1..] {
+        let alias_msg = PnsSetAliasMsg {
+            domain: format!("name{}", token_id - 1),
+            target: Some(format!("name{}", token_id)),
+        };
+        let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+    }
+
+    let now = unix_epoch_now();
+    assert_eq!(state.resolve_alias("name1", now).target, None);
+    assert_eq!(
+        state.resolve_alias("name2", now).target,
+        Some(format!("name{}", MAX_ALIAS_DEPTH + 2))
+    );
+}
+
+#[test]
+fn alias_resolution_breaks_when_target_is_minted_again() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let mut nft = mock_nft_state(alice, &[1, 2]);
+
+    for (token_id, domain) in [(1, "old"), (2, "new")] {
+        let mint_msg = PnsMintMsg {
+            domain: domain.to_string(),
+            token_id,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        };
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+    }
+
+    let alias_msg = PnsSetAliasMsg {
+        domain: "old".to_string(),
+        target: Some("new".to_string()),
+    };
+    let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+
+    let burn_msg = PnsBurnMsg {
+        domain: "new".to_string(),
+    };
+    let _ = execute_burn(
+        &mock_contract_context(minter),
+        &mut state,
+        &mut nft,
+        &burn_msg,
+    );
+
+    let mint_msg = PnsMintMsg {
+        domain: "new".to_string(),
+        token_id: 3,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let now = unix_epoch_now();
+    let resolution = state.resolve_alias("old", now);
+    assert_eq!(resolution.target, None);
+    assert_eq!(resolution.chain, vec!["old", "new"]);
+    assert!(!state.is_resolvable("old", now));
+    assert!(state.is_resolvable("new", now));
+}
+
+#[test]
+fn resolution_fails_when_root_domain_is_expired() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1, 2, 3, 4]);
+
+    let mint_msgs = [
+        PnsMintMsg {
+            domain: "project".to_string(),
+            token_id: 1,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        },
+        PnsMintMsg {
+            domain: "team.project".to_string(),
+            token_id: 2,
+            parent_id: Some("project".to_string()),
+            expires_at: None,
+        },
+        PnsMintMsg {
+            domain: "other".to_string(),
+            token_id: 3,
+            parent_id: None,
+            expires_at: None,
+        },
+        PnsMintMsg {
+            domain: "source".to_string(),
+            token_id: 4,
+            parent_id: None,
+            expires_at: Some(tomorrow_timestamp()),
+        },
+    ];
+    for mint_msg in &mint_msgs {
+        let _ = execute_mint(&mock_contract_context(minter), &mut state, mint_msg);
+    }
+
+    let record_mint_msg = PnsRecordMintMsg {
+        domain: "team.project".to_string(),
+        class: RecordClass::Bio {},
+        data: string_to_bytes("team"),
+    };
+    let _ = execute_record_mint(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &record_mint_msg,
+    );
+
+    let wildcard_msg = PnsSetWildcardMsg {
+        domain: "team.project".to_string(),
+        enabled: true,
+    };
+    let _ = execute_set_wildcard(
+        &mock_contract_context(alice),
+        &mut state,
+        &nft,
+        &wildcard_msg,
+    );
+
+    for (domain, target) in [("other", "team.project"), ("source", "other")] {
+        let alias_msg = PnsSetAliasMsg {
+            domain: domain.to_string(),
+            target: Some(target.to_string()),
+        };
+        let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+    }
+
+    let now = unix_epoch_now();
+    assert_eq!(
+        state.resolve_record("any.team.project", &RecordClass::Bio {}, now),
+        Some(string_to_bytes("team"))
+    );
+    assert_eq!(
+        state.resolve_alias("source", now).target,
+        Some("team.project".to_string())
+    );
+
+    // The subdomain never expires itself, but its root does
+    let later = tomorrow_timestamp() + 1;
+    assert_eq!(state.get_answering_domain_name("team.project", later), None);
+    assert!(!state.is_resolvable("team.project", later));
+    assert!(!state.is_resolvable("any.team.project", later));
+
+    let resolution = state.resolve_alias("other", later);
+    assert_eq!(resolution.target, None);
+    assert_eq!(resolution.chain, vec!["other", "team.project"]);
+
+    // An expired alias source is not answered with its own records either
+    let resolution = state.resolve_alias("source", later);
+    assert_eq!(resolution.target, None);
+    assert_eq!(resolution.chain, Vec::<String>::new());
+}
+
+#[test]
+#[should_panic(expected = "The alias target must be another minted domain")]
+fn set_alias_fails_when_target_is_not_minted() {
+    let minter = 1u8;
+    let alice = 10u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+    let nft = mock_nft_state(alice, &[1]);
+
+    let mint_msg = PnsMintMsg {
+        domain: "name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    let alias_msg = PnsSetAliasMsg {
+        domain: "name".to_string(),
+        target: Some("not-minted".to_string()),
+    };
+    let _ = execute_set_alias(&mock_contract_context(alice), &mut state, &nft, &alias_msg);
+}
+
+#[test]
+fn proper_name_parsing() {
+    assert!(is_valid_label("mpc"));
+    assert!(is_valid_label("meta-names"));
+    assert!(is_valid_label("🗼"));
+    assert!(is_valid_label("ñandú"));
+    assert!(is_valid_label(&"a".repeat(63)));
+    assert!(!is_valid_label(&"a".repeat(64)));
+    assert!(!is_valid_label(""));
+    assert!(!is_valid_label("-mpc"));
+    assert!(!is_valid_label("mpc-"));
+    assert!(!is_valid_label("Mpc"));
+    assert!(!is_valid_label("Ñandú"));
+    assert!(!is_valid_label("mpc_name"));
+    assert!(!is_valid_label("mpc name"));
+    assert!(!is_valid_label("mp\u{200B}c"));
+    assert!(!is_valid_label("mp\u{200D}c"));
+    assert!(!is_valid_label("mp\u{202E}c"));
+    assert!(!is_valid_label("mp\u{2066}c"));
+    assert!(!is_valid_label("mpc\u{3002}name"));
+    assert!(!is_valid_label("mpc\u{FF0E}name"));
+    assert!(!is_valid_label("\u{0430}pple"));
+    assert!(!is_valid_label("ñandu\u{0301}"));
+    assert!(!is_valid_label("mpc\u{00F7}"));
+
+    assert_eq!(
+        parse_labels("sub.mpc.name"),
+        Some(vec!["sub", "mpc", "name"])
+    );
+    assert_eq!(parse_labels("mpc..name"), None);
+    assert_eq!(parse_labels(".mpc"), None);
+    assert_eq!(parse_labels("mpc."), None);
+
+    assert!(is_valid_name("sub.mpc.name"));
+    assert!(is_valid_name(&"🗼".repeat(32)));
+    assert!(!is_valid_name(&"🗼".repeat(33)));
+    assert!(!is_valid_name("this.is.a.too.long.domain.meta.name"));
+    assert!(!is_valid_name(""));
+    assert!(!is_valid_name("mpc\u{3002}name"));
+
+    assert_eq!(get_parent_name("sub.mpc.name"), Some("mpc.name"));
+    assert_eq!(get_parent_name("name"), None);
+    assert_eq!(
+        get_ancestor_names("deep.sub.mpc.name"),
+        vec!["sub.mpc.name", "mpc.name", "name"]
+    );
+    assert_eq!(get_ancestor_names("name"), Vec::<&str>::new());
+
+    assert!(is_child_name("sub.mpc.name", "mpc.name"));
+    assert!(!is_child_name("deep.sub.mpc.name", "mpc.name"));
+    assert!(!is_child_name("mpc.name.sub", "mpc.name"));
+    assert!(!is_child_name("mpc.namexyz", "mpc.name"));
+    assert!(!is_child_name(".mpc.name", "mpc.name"));
+    assert!(!is_child_name("mpc.name", "mpc.name"));
+}
+
+#[test]
+fn proper_legacy_name_migration() {
+    assert_eq!(
+        migrate_legacy_name("mpc.name.sub", "mpc.name", "mpc.name"),
+        Some("sub.mpc.name".to_string())
+    );
+    assert_eq!(
+        migrate_legacy_name("mpc.name.sub.deep", "mpc.name.sub", "sub.mpc.name"),
+        Some("deep.sub.mpc.name".to_string())
+    );
+    assert_eq!(
+        migrate_legacy_name("mpc.name.a.b", "mpc.name", "mpc.name"),
+        Some("b.a.mpc.name".to_string())
+    );
+    assert_eq!(
+        migrate_legacy_name("mpc.namexyz", "mpc.name", "mpc.name"),
+        None
+    );
+    assert_eq!(
+        migrate_legacy_name("mpc.name.", "mpc.name", "mpc.name"),
+        None
+    );
+}
+
+#[test]
+fn proper_domain_validation() {
+    validate_domain("sub.mpc.name");
+    validate_domain_with_parent("sub.mpc.name", "mpc.name");
+}
+
+#[test]
+#[should_panic(expected = "The specified domain is not valid")]
+fn validate_domain_fails_when_a_label_is_empty() {
+    validate_domain("mpc..name");
+}
+
+#[test]
+#[should_panic(expected = "The specified domain is not valid with the parent domain")]
+fn validate_domain_with_parent_fails_when_parent_is_only_a_prefix() {
+    validate_domain_with_parent("mpc.namexyz", "mpc.name");
+}
+
+#[test]
+#[should_panic(
+    expected = "The specified domain is under a minted domain and must be minted as its subdomain"
+)]
+fn validate_root_domain_fails_when_an_ancestor_is_minted() {
+    let minter = 1u8;
+
+    let mut state = execute_init(&mock_contract_context(2));
+
+    let mint_msg = PnsMintMsg {
+        domain: "mpc.name".to_string(),
+        token_id: 1,
+        parent_id: None,
+        expires_at: Some(tomorrow_timestamp()),
+    };
+    let _ = execute_mint(&mock_contract_context(minter), &mut state, &mint_msg);
+
+    validate_root_domain(&state, "other.name");
+    validate_root_domain(&state, "deep.sub.mpc.name");
+}
@@ -0,0 +1,201 @@
+use crate::{
+    coin_address::encode_coin_address, content_hash::encode_content_hash, dns::encode_dns_record,
+    state::RecordClass, ContractError,
+};
+
+/// Length of a Partisia address, the address type byte followed by the identifier
+pub const ADDRESS_LENGTH: usize = 21;
+/// Highest address type byte, the address types go from account (0x00) to zk contract (0x03)
+pub const MAX_ADDRESS_TYPE: u8 = 0x03;
+/// Schemes allowed for the uri records
+pub const ALLOWED_URI_SCHEMES: [&str; 4] = ["https", "ipfs", "ipns", "ar"];
+
+pub const MAX_TWITTER_HANDLE_LEN: usize = 15;
+pub const MIN_DISCORD_USERNAME_LEN: usize = 2;
+pub const MAX_DISCORD_USERNAME_LEN: usize = 32;
+pub const MAX_EMAIL_LOCAL_PART_LEN: usize = 64;
+pub const MAX_EMAIL_DOMAIN_LABEL_LEN: usize = 63;
+
+/// Validate the record data against the rules of its class.
+/// Classes without rules accept any data.
+/// Returns [`()`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn validate_record_data(class: &RecordClass, data: &[u8]) {
+    let text = std::str::from_utf8(data).ok();
+
+    match class {
+        RecordClass::Wallet {} => assert!(
+            text.map_or(false, is_valid_wallet),
+            "{}",
+            ContractError::InvalidWalletRecord
+        ),
+        RecordClass::Email {} => assert!(
+            text.map_or(false, is_valid_email),
+            "{}",
+            ContractError::InvalidEmailRecord
+        ),
+        RecordClass::Uri {} => assert!(
+            text.map_or(false, is_valid_uri),
+            "{}",
+            ContractError::InvalidUriRecord
+        ),
+        RecordClass::Twitter {} => assert!(
+            text.map_or(false, is_valid_twitter_handle),
+            "{}",
+            ContractError::InvalidTwitterRecord
+        ),
+        RecordClass::Discord {} => assert!(
+            text.map_or(false, is_valid_discord_username),
+            "{}",
+            ContractError::InvalidDiscordRecord
+        ),
+        RecordClass::Avatar {} => assert!(
+            text.map_or(false, is_valid_avatar),
+            "{}",
+            ContractError::InvalidAvatarRecord
+        ),
+        _ => {}
+    }
+}
+
+/// Encodes the record data into the form it is stored in.
+/// The content hash is given in its text form and stored in its binary form,
+/// the DNS records are given in their text form and stored as their RDATA,
+/// the other classes are stored as given.
+/// Returns [`Vec<u8>`] if operation was successful,
+/// otherwise panics with error message defined in [`ContractError`]
+pub fn encode_record_data(class: &RecordClass, data: &[u8]) -> Vec<u8> {
+    match class {
+        RecordClass::ContentHash {} => {
+            let content_hash = std::str::from_utf8(data).ok().and_then(encode_content_hash);
+
+            match content_hash {
+                Some(content_hash) => content_hash,
+                None => panic!("{}", ContractError::InvalidContentHashRecord),
+            }
+        }
+        RecordClass::DnsA {}
+        | RecordClass::DnsAaaa {}
+        | RecordClass::DnsCname {}
+        | RecordClass::DnsTxt {}
+        | RecordClass::DnsMx {} => {
+            let rdata = std::str::from_utf8(data)
+                .ok()
+                .and_then(|text| encode_dns_record(class, text));
+
+            match rdata {
+                Some(rdata) => rdata,
+                None => panic!("{}", invalid_dns_record_error(class)),
+            }
+        }
+        _ => data.to_vec(),
+    }
+}
+
+/// A wallet is either a hex encoded Partisia address,
+/// or a multi-chain address prefixed by its supported coin type, as in `60:0xabc`,
+/// valid for the chain as per [`encode_coin_address`]
+pub fn is_valid_wallet(text: &str) -> bool {
+    match text.split_once(':') {
+        Some((coin_type, address)) => coin_type
+            .parse::<u32>()
+            .ok()
+            .and_then(|coin_type| encode_coin_address(coin_type, address))
+            .is_some(),
+        None => is_valid_partisia_address(text),
+    }
+}
+
+/// A Partisia address is the hex encoding of its 21 bytes, starting with a known address type
+pub fn is_valid_partisia_address(text: &str) -> bool {
+    text.len() == ADDRESS_LENGTH * 2
+        && text.chars().all(|c| c.is_ascii_hexdigit())
+        && u8::from_str_radix(&text[..2], 16).map_or(false, |t| t <= MAX_ADDRESS_TYPE)
+}
+
+/// An email is a dot-atom local part and a domain with at least two labels
+pub fn is_valid_email(text: &str) -> bool {
+    let (local, domain) = match text.rsplit_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let is_local_char = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~-.".contains(c);
+    let is_valid_local = !local.is_empty()
+        && local.len() <= MAX_EMAIL_LOCAL_PART_LEN
+        && local.chars().all(is_local_char)
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..");
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let is_valid_domain = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= MAX_EMAIL_DOMAIN_LABEL_LEN
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+        && labels.last().map_or(false, |tld| {
+            tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic())
+        });
+
+    is_valid_local && is_valid_domain
+}
+
+/// A uri must use one of the allowed schemes and have no whitespace nor control characters
+pub fn is_valid_uri(text: &str) -> bool {
+    match text.split_once("://") {
+        Some((scheme, rest)) => {
+            ALLOWED_URI_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+                && !rest.is_empty()
+                && !rest.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    }
+}
+
+/// A Twitter handle has up to 15 letters, digits and underscores, without the leading `@`
+pub fn is_valid_twitter_handle(text: &str) -> bool {
+    !text.is_empty()
+        && text.len() <= MAX_TWITTER_HANDLE_LEN
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A Discord username has 2 to 32 lowercase letters, digits, underscores and periods,
+/// without consecutive periods
+pub fn is_valid_discord_username(text: &str) -> bool {
+    (MIN_DISCORD_USERNAME_LEN..=MAX_DISCORD_USERNAME_LEN).contains(&text.len())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+        && !text.contains("..")
+}
+
+/// An avatar is either a uri or an NFT reference
+pub fn is_valid_avatar(text: &str) -> bool {
+    is_valid_uri(text) || is_valid_nft_reference(text)
+}
+
+/// An NFT reference is a contract address and a token id, as in `02abc.../42`
+pub fn is_valid_nft_reference(text: &str) -> bool {
+    match text.split_once('/') {
+        Some((contract, token_id)) => {
+            is_valid_partisia_address(contract)
+                && !contract.starts_with("00")
+                && token_id.parse::<u128>().is_ok()
+        }
+        None => false,
+    }
+}
+
+fn invalid_dns_record_error(class: &RecordClass) -> ContractError {
+    match class {
+        RecordClass::DnsA {} => ContractError::InvalidDnsARecord,
+        RecordClass::DnsAaaa {} => ContractError::InvalidDnsAaaaRecord,
+        RecordClass::DnsCname {} => ContractError::InvalidDnsCnameRecord,
+        RecordClass::DnsTxt {} => ContractError::InvalidDnsTxtRecord,
+        _ => ContractError::InvalidDnsMxRecord,
+    }
+}
@@ -1,6 +1,7 @@
 use std::{mem::take, panic::catch_unwind};
 
 use cucumber::{given, then, when, World};
+use nft::{actions as nft_actions, msg as nft_msg, state::NFTContractState};
 use partisia_name_system::{
     actions::{
         execute_init, execute_mint, execute_record_delete, execute_record_delete_all,
@@ -11,7 +12,7 @@ use partisia_name_system::{
     },
     state::{PartisiaNameSystemState, RecordClass},
 };
-use utils::tests::{mock_contract_context, tomorrow_timestamp, yesterday_timestamp};
+use utils::tests::{mock_address, mock_contract_context, tomorrow_timestamp, yesterday_timestamp};
 
 fn get_record_class_given(class: String) -> RecordClass {
     match class.as_str() {
@@ -26,6 +27,12 @@ fn get_record_class_given(class: String) -> RecordClass {
         "Custom3" => RecordClass::Custom3 {},
         "Custom4" => RecordClass::Custom4 {},
         "Custom5" => RecordClass::Custom5 {},
+        "ContentHash" => RecordClass::ContentHash {},
+        "DnsA" => RecordClass::DnsA {},
+        "DnsAaaa" => RecordClass::DnsAaaa {},
+        "DnsCname" => RecordClass::DnsCname {},
+        "DnsTxt" => RecordClass::DnsTxt {},
+        "DnsMx" => RecordClass::DnsMx {},
         _ => panic!("Unknown record class"),
     }
 }
@@ -33,13 +40,46 @@ fn get_record_class_given(class: String) -> RecordClass {
 #[derive(Debug, Default, World)]
 pub struct PartisiaNameSystemWorld {
     state: PartisiaNameSystemState,
+    nft: NFTContractState,
+}
+
+// Domains are owned by the minter, as the ownership is kept by the NFT state
+fn mint_domain(world: &mut PartisiaNameSystemWorld, msg: PnsMintMsg) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut state = take(&mut world.state);
+        execute_mint(&mock_contract_context(1), &mut state, &msg);
+        state
+    }));
+
+    if let Ok(new_state) = res {
+        world.state = new_state;
+
+        nft_actions::execute_mint(
+            &mock_contract_context(1),
+            &mut world.nft,
+            &nft_msg::NFTMintMsg {
+                to: mock_address(1),
+                token_id: msg.token_id,
+                token_uri: None,
+            },
+        );
+    }
 }
 
 #[given("a PNS contract")]
 fn pns_contract(world: &mut PartisiaNameSystemWorld) {
     let state = execute_init(&mock_contract_context(1));
+    let nft = nft_actions::execute_init(
+        &mock_contract_context(1),
+        &nft_msg::NFTInitMsg {
+            name: "Meta Names".to_string(),
+            symbol: "mpc".to_string(),
+            uri_template: "metanames.io".to_string(),
+        },
+    );
 
     world.state = state;
+    world.nft = nft;
 }
 
 #[given(regex = "'(.+)' domain is expired")]
@@ -54,20 +94,12 @@ fn domain_is_expired(world: &mut PartisiaNameSystemWorld, domain_name: String) {
 fn mint_a_domain(world: &mut PartisiaNameSystemWorld, domain: String) {
     let msg = PnsMintMsg {
         domain,
-        token_id: 0,
+        token_id: world.nft.get_next_token_id(),
         parent_id: None,
         expires_at: Some(tomorrow_timestamp()),
     };
 
-    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
-        let mut state = take(&mut world.state);
-        execute_mint(&mock_contract_context(1), &mut state, &msg);
-        state
-    }));
-
-    if let Ok(new_state) = res {
-        world.state = new_state;
-    }
+    mint_domain(world, msg);
 }
 
 #[given(regex = ".+ minted '(.+)' domain with '(.+)' domain as the parent")]
@@ -75,20 +107,12 @@ fn mint_a_domain(world: &mut PartisiaNameSystemWorld, domain: String) {
 fn mint_a_domain_with_parent(world: &mut PartisiaNameSystemWorld, domain: String, parent: String) {
     let msg = PnsMintMsg {
         domain,
-        token_id: 0,
+        token_id: world.nft.get_next_token_id(),
         parent_id: Some(parent),
         expires_at: None,
     };
 
-    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
-        let mut state = take(&mut world.state);
-        execute_mint(&mock_contract_context(1), &mut state, &msg);
-        state
-    }));
-
-    if let Ok(new_state) = res {
-        world.state = new_state;
-    }
+    mint_domain(world, msg);
 }
 
 #[given(regex = ".+ (minted) the '(.+)' record with '(.+)' data for the '(.+)' domain")]
@@ -110,7 +134,7 @@ fn mint_a_record(
                     class: get_record_class_given(class),
                     data: data.into_bytes(),
                 };
-                execute_record_mint(context, &mut state, &msg);
+                execute_record_mint(context, &mut state, &world.nft, &msg);
             }
 
             "updates" => {
@@ -120,7 +144,7 @@ fn mint_a_record(
                     data: data.into_bytes(),
                 };
 
-                execute_record_update(context, &mut state, &msg);
+                execute_record_update(context, &mut state, &world.nft, &msg);
             }
 
             _ => panic!("Not handled"),
@@ -141,7 +165,12 @@ fn domain_record_delete(world: &mut PartisiaNameSystemWorld, class: String, doma
         class: get_record_class_given(class),
     };
 
-    execute_record_delete(&mock_contract_context(1), &mut world.state, &msg);
+    execute_record_delete(
+        &mock_contract_context(1),
+        &mut world.state,
+        &world.nft,
+        &msg,
+    );
 }
 
 #[when(regex = ".+ deletes all records for the '(.+)' domain")]
@@ -0,0 +1,550 @@
+use crate::{
+    msg::{DomainInfoEvent, InitMsg, OwnerInfoEvent, SubdomainsEvent},
+    state::{ContractState, UserRole},
+    ContractError,
+};
+
+use access_control::{actions as ac_actions, msg as ac_msg};
+use contract_version_base::state::ContractVersionBase;
+use nft::{actions as nft_actions, msg as nft_msg};
+use partisia_name_system::{actions as pns_actions, msg as pns_msg, state::RecordClass};
+use pbc_contract_common::{
+    address::Address, context::ContractContext, events::EventGroup, upgrade::ContractHashes,
+};
+use utils::time::milliseconds_in_years;
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[init]
+pub fn initialize(ctx: ContractContext, msg: InitMsg) -> (ContractState, Vec<EventGroup>) {
+    let pns = pns_actions::execute_init(&ctx);
+    let mut nft = nft_actions::execute_init(
+        &ctx,
+        &nft_msg::NFTInitMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            uri_template: msg.uri_template,
+        },
+    );
+    // Domains are minted through the registrar role, the deployer must not operate on them
+    nft.contract_owner = None;
+
+    let mut access_control = ac_actions::execute_init(&ac_msg::ACInitMsg {
+        admin_addresses: msg.admin_addresses,
+        additional_roles: vec![UserRole::Registrar {} as u8],
+    });
+    for registrar in msg.registrar_addresses.iter() {
+        access_control._set_role(UserRole::Registrar {} as u8, registrar);
+    }
+
+    let state = ContractState {
+        access_control,
+        nft,
+        pns,
+        version: ContractVersionBase::new(CONTRACT_NAME, CONTRACT_VERSION),
+    };
+
+    (state, vec![])
+}
+
+/// Only admins are allowed to upgrade the contract
+#[upgrade_is_allowed]
+pub fn is_upgrade_allowed(
+    ctx: ContractContext,
+    state: ContractState,
+    _old_contract_hashes: ContractHashes,
+    _new_contract_hashes: ContractHashes,
+    _new_contract_rpc: Vec<u8>,
+) -> bool {
+    state
+        .access_control
+        .has_role(UserRole::Admin {} as u8, &ctx.sender)
+}
+
+/// Keeps the state of the previous contract version, which has the same layout
+#[upgrade]
+pub fn upgrade(_ctx: ContractContext, mut state: ContractState) -> ContractState {
+    assert!(
+        state.version.get_contract_name() == CONTRACT_NAME,
+        "{}",
+        ContractError::UpgradeFromAnotherContract
+    );
+
+    state
+        .version
+        .set_contract_version(CONTRACT_NAME, CONTRACT_VERSION);
+
+    state
+}
+
+/// Transfers a domain, the records of the domain are cleared
+#[action(shortname = 0x03)]
+pub fn transfer_from(
+    ctx: ContractContext,
+    mut state: ContractState,
+    from: Address,
+    to: Address,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut nft_events = nft_actions::execute_transfer_from(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTTransferFromMsg { from, to, token_id },
+    );
+
+    let (name, _) = state.pns.get_domain_by_token_id(token_id).unwrap();
+    let msg = &pns_msg::PnsRecordDeleteAllMsg { domain: name };
+    let pns_events = pns_actions::execute_record_delete_all(&ctx, &mut state.pns, msg);
+    state.pns._set_manager(&msg.domain, None);
+
+    nft_events.extend(pns_events);
+
+    (state, nft_events)
+}
+
+#[action(shortname = 0x04)]
+pub fn transfer_domain(
+    ctx: ContractContext,
+    state: ContractState,
+    from: Address,
+    to: Address,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    let token_id = assert_and_get_token_id(&state, &domain);
+
+    transfer_from(ctx, state, from, to, token_id)
+}
+
+#[action(shortname = 0x05)]
+pub fn approve(
+    ctx: ContractContext,
+    mut state: ContractState,
+    approved: Option<Address>,
+    token_id: u128,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_approve(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveMsg { approved, token_id },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x06)]
+pub fn approve_domain(
+    ctx: ContractContext,
+    state: ContractState,
+    approved: Option<Address>,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    let token_id = assert_and_get_token_id(&state, &domain);
+
+    approve(ctx, state, approved, token_id)
+}
+
+#[action(shortname = 0x07)]
+pub fn set_approval_for_all(
+    ctx: ContractContext,
+    mut state: ContractState,
+    operator: Address,
+    approved: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = nft_actions::execute_set_approval_for_all(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTApproveForAllMsg { operator, approved },
+    );
+
+    (state, events)
+}
+
+/// Registers a domain, only registrars are allowed to register
+/// Registrars are responsible for pricing, a subdomain is only registered
+/// if the receiver or the registrar is the owner of the parent or approved on it
+/// Domains without a parent expire after the subscription years, if given
+#[action(shortname = 0x09)]
+pub fn register(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    to: Address,
+    parent_id: Option<String>,
+    subscription_years: Option<u32>,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Registrar {}, &ctx.sender);
+    assert!(!state.pns.is_minted(&domain), "{}", ContractError::Minted);
+
+    pns_actions::validate_domain(&domain);
+
+    let mut expires_at = None;
+    if let Some(parent_id) = &parent_id {
+        assert!(
+            state.pns.is_minted(parent_id),
+            "{}",
+            ContractError::DomainNotMinted
+        );
+        assert!(
+            state.pns.is_active(parent_id, ctx.block_production_time),
+            "{}",
+            ContractError::DomainNotActive
+        );
+
+        pns_actions::validate_domain_with_parent(&domain, parent_id);
+
+        let parent_token_id = state.pns.get_token_id(parent_id).unwrap();
+        let now = ctx.block_production_time;
+        assert!(
+            state.nft.is_approved_or_owner(to, parent_token_id, now)
+                || state
+                    .nft
+                    .is_approved_or_owner(ctx.sender, parent_token_id, now),
+            "{}",
+            ContractError::Unauthorized
+        );
+    } else {
+        pns_actions::validate_root_domain(&state.pns, &domain);
+
+        if let Some(years) = subscription_years {
+            expires_at = Some(ctx.block_production_time + milliseconds_in_years(years as i64));
+        }
+    }
+
+    let token_id = state.nft.get_next_token_id();
+    let nft_events = nft_actions::execute_mint(
+        &ctx,
+        &mut state.nft,
+        &nft_msg::NFTMintMsg {
+            to,
+            token_id,
+            token_uri: None,
+        },
+    );
+
+    let pns_events = pns_actions::execute_mint(
+        &ctx,
+        &mut state.pns,
+        &pns_msg::PnsMintMsg {
+            domain,
+            expires_at,
+            parent_id: parent_id.clone(),
+            token_id,
+        },
+    );
+
+    if let Some(parent_id) = &parent_id {
+        let parent_token_id = state.pns.get_token_id(parent_id).unwrap();
+        state.nft._set_parent_token(token_id, parent_token_id);
+    }
+
+    let events = nft_events.into_iter().chain(pns_events).collect();
+
+    (state, events)
+}
+
+/// Returns owner info as data in the event
+/// the event data is of type OwnerInfoEvent
+#[action(shortname = 0x11)]
+pub fn owner_info(
+    ctx: ContractContext,
+    state: ContractState,
+    address: Address,
+) -> (ContractState, Vec<EventGroup>) {
+    let mut event_builder = EventGroup::builder();
+
+    let owner_info_event = OwnerInfoEvent {
+        owner: address,
+        domain_count: state.nft.balance_of(address),
+        total_supply: state.nft.supply,
+    };
+
+    event_builder.return_data(owner_info_event);
+
+    (state, vec![event_builder.build()])
+}
+
+#[action(shortname = 0x12)]
+pub fn is_domain_owner(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    address: Address,
+) -> (ContractState, Vec<EventGroup>) {
+    let token_id = assert_and_get_token_id(&state, &domain);
+
+    let is_owner = state.nft.owner_of(token_id) == address;
+    assert!(is_owner, "{}", ContractError::Unauthorized);
+
+    (state, vec![])
+}
+
+#[action(shortname = 0x17)]
+pub fn mint_custom_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    key: String,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_custom_record_mint(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsCustomRecordMintMsg { domain, key, data },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x18)]
+pub fn update_custom_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    key: String,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_custom_record_update(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsCustomRecordUpdateMsg { domain, key, data },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x19)]
+pub fn delete_custom_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    key: String,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_custom_record_delete(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsCustomRecordDeleteMsg { domain, key },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x21)]
+pub fn mint_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    class: RecordClass,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_record_mint(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsRecordMintMsg {
+            domain,
+            class,
+            data,
+        },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x22)]
+pub fn update_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    class: RecordClass,
+    data: Vec<u8>,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_record_update(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsRecordUpdateMsg {
+            domain,
+            class,
+            data,
+        },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x23)]
+pub fn delete_record(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    class: RecordClass,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_record_delete(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsRecordDeleteMsg { domain, class },
+    );
+
+    (state, events)
+}
+
+#[action(shortname = 0x24)]
+pub fn update_user_role(
+    ctx: ContractContext,
+    mut state: ContractState,
+    role: UserRole,
+    address: Address,
+    active: bool,
+) -> (ContractState, Vec<EventGroup>) {
+    let msg = ac_msg::ACRoleMsg {
+        role: role as u8,
+        account: address,
+    };
+
+    if active {
+        ac_actions::execute_grant_role(&ctx, &mut state.access_control, &msg);
+    } else {
+        ac_actions::execute_revoke_role(&ctx, &mut state.access_control, &msg);
+    }
+
+    (state, vec![])
+}
+
+/// Extends the expiration of a domain without a parent, only registrars are allowed to renew
+/// The subscription years are added to the current expiration,
+/// domains registered without subscription years never expire and cannot be renewed
+#[action(shortname = 0x26)]
+pub fn renew(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    subscription_years: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_has_role(&state, UserRole::Registrar {}, &ctx.sender);
+    assert!(
+        subscription_years > 0,
+        "{}",
+        ContractError::InvalidSubscriptionYears
+    );
+
+    let current = state.pns.get_domain(&domain);
+    assert!(current.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let current = current.unwrap();
+    assert!(
+        current.parent_id.is_none(),
+        "{}",
+        ContractError::RenewSubdomain
+    );
+
+    assert!(
+        current.expires_at.is_some(),
+        "{}",
+        ContractError::RenewNonExpiringDomain
+    );
+
+    let expires_at = current.expires_at.unwrap() + milliseconds_in_years(subscription_years as i64);
+
+    let events = pns_actions::execute_update_expiration(
+        &ctx,
+        &mut state.pns,
+        &pns_msg::PnsDomainUpdateExpirationMsg {
+            domain,
+            expires_at: Some(expires_at),
+        },
+    );
+
+    (state, events)
+}
+
+/// Returns a page of the direct subdomains of a domain as data in the event
+/// the event data is of type SubdomainsEvent
+#[action(shortname = 0x32)]
+pub fn subdomains(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+    offset: u32,
+    limit: u32,
+) -> (ContractState, Vec<EventGroup>) {
+    assert_and_get_token_id(&state, &domain);
+
+    let mut event_builder = EventGroup::builder();
+
+    let subdomains_event = SubdomainsEvent {
+        subdomains: state.pns.get_subdomains(&domain, offset, limit),
+        subdomain_count: state.pns.get_subdomain_count(&domain),
+        domain,
+    };
+
+    event_builder.return_data(subdomains_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Returns the ownership and expiration of a domain as data in the event
+/// the event data is of type DomainInfoEvent
+#[action(shortname = 0x41)]
+pub fn domain_info(
+    ctx: ContractContext,
+    state: ContractState,
+    domain: String,
+) -> (ContractState, Vec<EventGroup>) {
+    let info = state.pns.get_domain(&domain);
+    assert!(info.is_some(), "{}", ContractError::DomainNotMinted);
+
+    let info = info.unwrap();
+    let mut event_builder = EventGroup::builder();
+
+    let domain_info_event = DomainInfoEvent {
+        domain,
+        token_id: info.token_id,
+        owner: state.nft.owner_of(info.token_id),
+        parent_id: info.parent_id,
+        minted_at: info.minted_at,
+        expires_at: info.expires_at,
+    };
+
+    event_builder.return_data(domain_info_event);
+
+    (state, vec![event_builder.build()])
+}
+
+/// Sets or clears the manager of a domain, which is allowed to manage the records
+/// of the domain without being able to transfer it
+#[action(shortname = 0x42)]
+pub fn set_domain_manager(
+    ctx: ContractContext,
+    mut state: ContractState,
+    domain: String,
+    manager: Option<Address>,
+) -> (ContractState, Vec<EventGroup>) {
+    let events = pns_actions::execute_set_manager(
+        &ctx,
+        &mut state.pns,
+        &state.nft,
+        &pns_msg::PnsDomainSetManagerMsg { domain, manager },
+    );
+
+    (state, events)
+}
+
+fn assert_and_get_token_id(state: &ContractState, domain: &str) -> u128 {
+    let token_id = state.pns.get_token_id(domain);
+    assert!(token_id.is_some(), "{}", ContractError::DomainNotMinted);
+
+    token_id.unwrap()
+}
+
+fn assert_has_role(state: &ContractState, role: UserRole, account: &Address) {
+    assert!(
+        state.access_control.has_role(role as u8, account),
+        "{}",
+        ContractError::Unauthorized
+    );
+}
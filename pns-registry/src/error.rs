@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+/// This enum describes pns registry contract errors
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("The specified domain is not minted")]
+    DomainNotMinted,
+
+    #[error("The specified domain is already minted")]
+    Minted,
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("The specified domain is not active")]
+    DomainNotActive,
+
+    #[error("The given subscription years value is not valid")]
+    InvalidSubscriptionYears,
+
+    #[error("Only domains without a parent can be renewed")]
+    RenewSubdomain,
+
+    #[error("The specified domain never expires and cannot be renewed")]
+    RenewNonExpiringDomain,
+
+    #[error("The contract can only be upgraded from a previous version of itself")]
+    UpgradeFromAnotherContract,
+}
@@ -0,0 +1,47 @@
+use create_type_spec_derive::CreateTypeSpec;
+use pbc_contract_common::address::Address;
+use read_write_rpc_derive::ReadWriteRPC;
+
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct InitMsg {
+    pub admin_addresses: Vec<Address>,
+    pub registrar_addresses: Vec<Address>,
+    pub name: String,
+    pub symbol: String,
+    pub uri_template: String,
+}
+
+// Events structs
+
+/// Struct for domain info event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct DomainInfoEvent {
+    pub domain: String,
+    pub token_id: u128,
+    pub owner: Address,
+    pub parent_id: Option<String>,
+    pub minted_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+/// Struct for owner info event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct OwnerInfoEvent {
+    pub owner: Address,
+    pub domain_count: u128,
+    pub total_supply: u128,
+}
+
+/// Struct for subdomains event
+/// Copy this struct to the external contracts that are using this action
+/// to be able to decode the event data
+#[derive(ReadWriteRPC, CreateTypeSpec, Clone, PartialEq, Eq, Debug)]
+pub struct SubdomainsEvent {
+    pub domain: String,
+    pub subdomains: Vec<String>,
+    pub subdomain_count: u32,
+}
@@ -0,0 +1,253 @@
+use std::{mem::take, panic::catch_unwind};
+
+use cucumber::{given, then, when, World};
+use partisia_name_system::state::RecordClass;
+use pns_registry::{
+    contract::{approve_domain, initialize, mint_record, register, renew, transfer_domain},
+    msg::InitMsg,
+    state::ContractState,
+};
+use utils::{
+    tests::{get_address_for_user, mock_address, mock_contract_context, SYSTEM_ADDRESS},
+    time::milliseconds_in_years,
+};
+
+const REGISTRAR_ADDRESS: u8 = 30;
+
+#[derive(Debug, Default, World)]
+pub struct ContractWorld {
+    state: ContractState,
+}
+
+#[given(regex = "a pns registry contract")]
+fn pns_registry_contract(world: &mut ContractWorld) {
+    let msg = InitMsg {
+        admin_addresses: vec![mock_address(SYSTEM_ADDRESS)],
+        registrar_addresses: vec![mock_address(REGISTRAR_ADDRESS)],
+        name: "Meta Names".to_string(),
+        symbol: "mpc".to_string(),
+        uri_template: "metanames.io".to_string(),
+    };
+
+    let (state, _) = initialize(mock_contract_context(SYSTEM_ADDRESS), msg);
+
+    world.state = state;
+}
+
+#[given(expr = "the registrar registered '{word}' domain for {word} for {int} years")]
+#[when(expr = "the registrar registers '{word}' domain for {word} for {int} years")]
+fn registrar_registers(world: &mut ContractWorld, domain: String, user: String, years: u32) {
+    let (state, _) = register(
+        mock_contract_context(REGISTRAR_ADDRESS),
+        take(&mut world.state),
+        domain,
+        mock_address(get_address_for_user(user)),
+        None,
+        Some(years),
+    );
+
+    world.state = state;
+}
+
+#[given(expr = "the registrar registered '{word}' domain for {word} without expiration")]
+fn registrar_registers_without_expiration(world: &mut ContractWorld, domain: String, user: String) {
+    let (state, _) = register(
+        mock_contract_context(REGISTRAR_ADDRESS),
+        take(&mut world.state),
+        domain,
+        mock_address(get_address_for_user(user)),
+        None,
+        None,
+    );
+
+    world.state = state;
+}
+
+#[when(expr = "the registrar registers '{word}' subdomain of '{word}' for {word}")]
+fn registrar_registers_subdomain(
+    world: &mut ContractWorld,
+    domain: String,
+    parent: String,
+    user: String,
+) {
+    let (state, _) = register(
+        mock_contract_context(REGISTRAR_ADDRESS),
+        take(&mut world.state),
+        domain,
+        mock_address(get_address_for_user(user)),
+        Some(parent),
+        None,
+    );
+
+    world.state = state;
+}
+
+#[then(expr = "the registrar cannot register '{word}' subdomain of '{word}' for {word}")]
+fn registrar_cannot_register_subdomain(
+    world: &mut ContractWorld,
+    domain: String,
+    parent: String,
+    user: String,
+) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        register(
+            mock_contract_context(REGISTRAR_ADDRESS),
+            take(&mut world.state),
+            domain,
+            mock_address(get_address_for_user(user)),
+            Some(parent),
+            None,
+        )
+    }));
+
+    assert!(res.is_err());
+}
+
+#[when(expr = "{word} registers '{word}' domain for {word} for {int} years")]
+fn user_registers(world: &mut ContractWorld, user: String, domain: String, to: String, years: u32) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let (new_state, _) = register(
+            mock_contract_context(get_address_for_user(user)),
+            take(&mut world.state),
+            domain,
+            mock_address(get_address_for_user(to)),
+            None,
+            Some(years),
+        );
+
+        new_state
+    }));
+
+    if let Ok(new_state) = res {
+        world.state = new_state;
+    }
+}
+
+#[when(expr = "the registrar renews '{word}' domain for {int} years")]
+fn registrar_renews(world: &mut ContractWorld, domain: String, years: u32) {
+    let (state, _) = renew(
+        mock_contract_context(REGISTRAR_ADDRESS),
+        take(&mut world.state),
+        domain,
+        years,
+    );
+
+    world.state = state;
+}
+
+#[then(expr = "the registrar cannot renew '{word}' domain for {int} years")]
+fn registrar_cannot_renew(world: &mut ContractWorld, domain: String, years: u32) {
+    let res = catch_unwind(std::panic::AssertUnwindSafe(|| {
+        renew(
+            mock_contract_context(REGISTRAR_ADDRESS),
+            take(&mut world.state),
+            domain,
+            years,
+        )
+    }));
+
+    assert!(res.is_err());
+}
+
+#[given(expr = "{word} approved {word} on '{word}' domain")]
+fn user_approves_domain(world: &mut ContractWorld, user: String, approved: String, domain: String) {
+    let (state, _) = approve_domain(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        Some(mock_address(get_address_for_user(approved))),
+        domain,
+    );
+
+    world.state = state;
+}
+
+#[given(expr = "{word} approved the registrar on '{word}' domain")]
+fn user_approves_registrar(world: &mut ContractWorld, user: String, domain: String) {
+    let (state, _) = approve_domain(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        Some(mock_address(REGISTRAR_ADDRESS)),
+        domain,
+    );
+
+    world.state = state;
+}
+
+#[given(expr = "{word} minted the '{word}' record with '{word}' data for the '{word}' domain")]
+fn user_mints_record(
+    world: &mut ContractWorld,
+    user: String,
+    class: String,
+    data: String,
+    domain: String,
+) {
+    let (state, _) = mint_record(
+        mock_contract_context(get_address_for_user(user)),
+        take(&mut world.state),
+        domain,
+        get_record_class_given(class),
+        data.into_bytes(),
+    );
+
+    world.state = state;
+}
+
+#[when(expr = "{word} transfers the '{word}' domain to {word}")]
+fn user_transfers_domain(world: &mut ContractWorld, user: String, domain: String, to: String) {
+    let (state, _) = transfer_domain(
+        mock_contract_context(get_address_for_user(user.clone())),
+        take(&mut world.state),
+        mock_address(get_address_for_user(user)),
+        mock_address(get_address_for_user(to)),
+        domain,
+    );
+
+    world.state = state;
+}
+
+#[then(expr = "{word} owns '{word}' domain")]
+fn owns_the_domain(world: &mut ContractWorld, user: String, domain: String) {
+    let token_id = world.state.pns.get_token_id(&domain).unwrap();
+
+    assert_eq!(
+        world.state.nft.owner_of(token_id),
+        mock_address(get_address_for_user(user))
+    );
+}
+
+#[then(expr = "'{word}' domain is not minted")]
+fn domain_is_not_minted(world: &mut ContractWorld, domain: String) {
+    assert!(!world.state.pns.is_minted(&domain));
+}
+
+#[then(expr = "'{word}' domain expires in {int} years")]
+fn domain_expires_in(world: &mut ContractWorld, domain: String, years: u32) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+    let expected = domain.minted_at + milliseconds_in_years(years as i64);
+
+    assert_eq!(domain.expires_at, Some(expected));
+}
+
+#[then(expr = "'{word}' domain does not have a '{word}' record")]
+fn domain_has_no_record(world: &mut ContractWorld, domain: String, class: String) {
+    let domain = world.state.pns.get_domain(&domain).unwrap();
+
+    assert_eq!(domain.get_record(&get_record_class_given(class)), None);
+}
+
+fn get_record_class_given(class: String) -> RecordClass {
+    match class.as_str() {
+        "Bio" => RecordClass::Bio {},
+        "Twitter" => RecordClass::Twitter {},
+        "Wallet" => RecordClass::Wallet {},
+        _ => panic!("Unknown record class"),
+    }
+}
+
+// This runs before everything else, so you can setup things here.
+fn main() {
+    // You may choose any executor you like (`tokio`, `async-std`, etc.).
+    // You may even have an `async` main, it doesn't matter. The point is that
+    // Cucumber is composable. :)
+    futures::executor::block_on(ContractWorld::run("tests/features"));
+}
@@ -0,0 +1,48 @@
+Feature: Register feature
+
+  Scenario: The registrar registers a domain
+    Given a pns registry contract
+    When the registrar registers 'name.mpc' domain for Alice for 1 years
+    Then Alice owns 'name.mpc' domain
+    And 'name.mpc' domain expires in 1 years
+
+  Scenario: The register by an account which is not a registrar does not happen
+    Given a pns registry contract
+    When Alice registers 'name.mpc' domain for Alice for 1 years
+    Then 'name.mpc' domain is not minted
+
+  Scenario: The registrar renews a domain
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    When the registrar renews 'name.mpc' domain for 2 years
+    Then 'name.mpc' domain expires in 3 years
+
+  Scenario: The registrar does not renew a domain that never expires
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice without expiration
+    Then the registrar cannot renew 'name.mpc' domain for 1 years
+
+  Scenario: The registrar registers a subdomain for the owner of the parent
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    When the registrar registers 'sub.name.mpc' subdomain of 'name.mpc' for Alice
+    Then Alice owns 'sub.name.mpc' domain
+
+  Scenario: The registrar registers a subdomain for an address approved on the parent
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    And Alice approved Bob on 'name.mpc' domain
+    When the registrar registers 'sub.name.mpc' subdomain of 'name.mpc' for Bob
+    Then Bob owns 'sub.name.mpc' domain
+
+  Scenario: The registrar approved on the parent registers a subdomain for anyone
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    And Alice approved the registrar on 'name.mpc' domain
+    When the registrar registers 'sub.name.mpc' subdomain of 'name.mpc' for Bob
+    Then Bob owns 'sub.name.mpc' domain
+
+  Scenario: The registrar does not register a subdomain without the consent of the owner of the parent
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    Then the registrar cannot register 'sub.name.mpc' subdomain of 'name.mpc' for Bob
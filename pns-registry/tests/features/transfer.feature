@@ -0,0 +1,9 @@
+Feature: Transfer feature
+
+  Scenario: The transfer cleans all the existing records
+    Given a pns registry contract
+    And the registrar registered 'name.mpc' domain for Alice for 1 years
+    And Alice minted the 'Wallet' record with '000000000000000000000000000000000000000001' data for the 'name.mpc' domain
+    When Alice transfers the 'name.mpc' domain to Bob
+    Then Bob owns 'name.mpc' domain
+    And 'name.mpc' domain does not have a 'Wallet' record